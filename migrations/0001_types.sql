@@ -0,0 +1,55 @@
+-- Enumerations and the row type returned by the schedule functions.
+-- Types have no IF NOT EXISTS, so databases created from the old script keep theirs.
+
+DO $$
+BEGIN
+    CREATE TYPE days_of_week AS ENUM ('monday', 'tuesday', 'wednesday', 'thursday', 'friday', 'saturday', 'sunday');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
+
+DO $$
+BEGIN
+    CREATE TYPE distribution_week AS ENUM ('first', 'second', 'all');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
+
+DO $$
+BEGIN
+    CREATE TYPE lesson_types AS ENUM ('lecture', 'practice', 'laboratory work');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
+
+DO $$
+BEGIN
+    CREATE TYPE lesson AS(
+        subject_name VARCHAR(255),
+        lesson_type lesson_types,
+        time TIME,
+        teacher_name TEXT,
+        info VARCHAR(255),
+        room VARCHAR(32)
+    );
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END
+$$;
+
+-- The old script had schedule functions taking INTEGER ids and a `lesson` type without `room`.
+DROP FUNCTION IF EXISTS get_current_schedule(INTEGER);
+DROP FUNCTION IF EXISTS get_today_schedule(INTEGER);
+DROP FUNCTION IF EXISTS get_schedule(days_of_week, INTEGER);
+DROP FUNCTION IF EXISTS get_week_schedule(INTEGER, distribution_week);
+
+DO $$
+BEGIN
+    IF NOT EXISTS(SELECT * FROM pg_attribute WHERE attrelid = 'lesson'::regclass AND attname = 'room') THEN
+        ALTER TYPE lesson ADD ATTRIBUTE room VARCHAR(32);
+    END IF;
+END
+$$;
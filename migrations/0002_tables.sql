@@ -0,0 +1,84 @@
+-- Tables in the order of their references.
+
+CREATE TABLE IF NOT EXISTS teloxide_dialogues(
+    chat_id BIGINT PRIMARY KEY,
+    dialogue BYTEA NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS groups(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR (255)
+);
+
+CREATE TABLE IF NOT EXISTS subjects(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255),
+    choice BOOLEAN,
+    info VARCHAR(255)
+);
+
+CREATE TABLE IF NOT EXISTS teachers(
+    id SERIAL PRIMARY KEY,
+    last_name VARCHAR(255),
+    first_name VARCHAR(255),
+    patronymic_name VARCHAR(255),
+    telegram VARCHAR(255),
+    email VARCHAR(255),
+    phone_number VARCHAR(12),
+    UNIQUE(last_name, first_name, patronymic_name)
+);
+
+CREATE TABLE IF NOT EXISTS schedule(
+    id SERIAL PRIMARY KEY,
+    subject INTEGER REFERENCES subjects(id),
+    type lesson_types,
+    teacher INTEGER REFERENCES teachers(id),
+    day_of_week  days_of_week,
+    time TIME,
+    distribution distribution_week,
+    info VARCHAR(255),
+    room VARCHAR(32)
+);
+
+CREATE TABLE IF NOT EXISTS lessons_groups(
+    lesson INTEGER REFERENCES schedule(id),
+    "group" INTEGER REFERENCES groups(id),
+    PRIMARY KEY(lesson, "group")
+);
+
+CREATE TABLE IF NOT EXISTS students(
+    id SERIAL PRIMARY KEY,
+    telegram_id INTEGER UNIQUE,
+    "group" INTEGER REFERENCES groups(id),
+    last_name VARCHAR(255)
+);
+
+CREATE TABLE IF NOT EXISTS students_subjects(
+    student INTEGER REFERENCES students(id),
+    subject INTEGER REFERENCES subjects(id),
+    PRIMARY KEY(student, subject)
+);
+
+CREATE TABLE IF NOT EXISTS calendar_tokens(
+    student INTEGER PRIMARY KEY REFERENCES students(id),
+    token VARCHAR(32) UNIQUE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deadlines(
+    id SERIAL PRIMARY KEY,
+    subject INTEGER REFERENCES subjects(id),
+    "group" INTEGER REFERENCES groups(id),
+    description VARCHAR(255),
+    deadline TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS sessions(
+    token VARCHAR(32) PRIMARY KEY,
+    telegram_id BIGINT NOT NULL,
+    name VARCHAR(255),
+    username VARCHAR(255),
+    expires TIMESTAMP NOT NULL
+);
+
+-- Added after the first deployments.
+ALTER TABLE schedule ADD COLUMN IF NOT EXISTS room VARCHAR(32);
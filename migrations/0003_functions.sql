@@ -0,0 +1,186 @@
+-- SQL functions are checked when created, so callees come before their callers.
+
+CREATE OR REPLACE FUNCTION create_session(user_id BIGINT, user_name VARCHAR, user_username VARCHAR) RETURNS VARCHAR
+    LANGUAGE SQL
+AS
+$$
+DELETE FROM sessions WHERE expires < now();
+INSERT INTO sessions VALUES (md5(random()::text || clock_timestamp()::text), user_id, user_name, user_username, now() + INTERVAL '30 days')
+RETURNING token;
+$$;
+
+CREATE OR REPLACE FUNCTION get_calendar_token(user_id BIGINT) RETURNS VARCHAR
+    LANGUAGE plpgsql
+AS
+$$
+DECLARE
+    student_val students%ROWTYPE;
+    token_val VARCHAR;
+BEGIN
+    SELECT * INTO student_val FROM students WHERE telegram_id = user_id;
+    IF NOT FOUND THEN
+        RETURN NULL;
+    END IF;
+    INSERT INTO calendar_tokens VALUES (student_val.id, md5(random()::text || clock_timestamp()::text))
+    ON CONFLICT(student) DO NOTHING;
+    SELECT token INTO token_val FROM calendar_tokens WHERE student = student_val.id;
+    RETURN token_val;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION get_distribution_start() RETURNS DATE
+    LANGUAGE SQL
+AS
+$$
+SELECT '2021-02-01'::date
+$$;
+
+CREATE OR REPLACE FUNCTION get_distribution_week() RETURNS distribution_week
+    LANGUAGE SQL
+AS
+$$
+SELECT
+    CASE (CURRENT_DATE - get_distribution_start()) / 7 % 2
+        WHEN 0 THEN 'first'::distribution_week
+        ELSE 'second'::distribution_week
+        END
+$$;
+
+CREATE OR REPLACE FUNCTION get_schedule(day days_of_week, user_id BIGINT)
+    RETURNS TABLE(subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING, room CHARACTER VARYING)
+    LANGUAGE plpgsql
+AS
+$$
+DECLARE
+    student_val students%ROWTYPE;
+BEGIN
+    SELECT * INTO student_val FROM students WHERE telegram_id = user_id;
+    RETURN QUERY SELECT DISTINCT
+                     subjects.name,
+                     schedule.type,
+                     schedule.time,
+                     concat_ws(' ', teachers.last_name,  teachers.first_name, teachers.patronymic_name),
+                     schedule.info,
+                     COALESCE(schedule.room, '')
+                 FROM schedule
+                          JOIN subjects ON subjects.id = schedule.subject
+                          LEFT JOIN teachers ON teachers.id = schedule.teacher
+                 WHERE
+                     (distribution = 'all'::distribution_week OR distribution = (SELECT * FROM get_distribution_week())) AND
+                         day_of_week = day AND EXISTS(SELECT * FROM lessons_groups WHERE "group" = student_val."group" AND lesson = schedule.id) AND
+                     (NOT subjects.choice OR EXISTS(SELECT * FROM students_subjects WHERE student = student_val.id AND subject = schedule.subject))
+                 ORDER BY schedule.time;
+    RETURN;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION get_today_schedule(user_id BIGINT) RETURNS SETOF lesson
+    LANGUAGE SQL
+AS
+$$
+SELECT * FROM get_schedule(trim(to_char(now(), 'day'))::days_of_week, user_id)
+$$;
+
+CREATE OR REPLACE FUNCTION get_current_schedule(user_id BIGINT) RETURNS SETOF lesson
+    LANGUAGE SQL
+AS
+$$
+SELECT * FROM get_today_schedule(user_id)
+WHERE time <= (CURRENT_TIME AT TIME ZONE 'EETDST')::time + INTERVAL '1 hours 55 minutes' AND time + INTERVAL '1 hours 35 minutes' >= (CURRENT_TIME AT TIME ZONE 'EET')::time
+LIMIT 2
+$$;
+
+CREATE OR REPLACE FUNCTION get_week_schedule(user_id BIGINT, week distribution_week)
+    RETURNS TABLE(day_of_week days_of_week, subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING, room CHARACTER VARYING)
+    LANGUAGE plpgsql
+as
+$$
+DECLARE
+    student_val students%ROWTYPE;
+BEGIN
+    SELECT * INTO student_val FROM students WHERE telegram_id = user_id;
+    RETURN QUERY SELECT DISTINCT
+                     schedule.day_of_week,
+                     subjects.name,
+                     schedule.type,
+                     schedule.time,
+                     concat_ws(' ', teachers.last_name,  teachers.first_name, teachers.patronymic_name),
+                     schedule.info,
+                     COALESCE(schedule.room, '')
+                 FROM schedule
+                          JOIN subjects ON subjects.id = schedule.subject
+                          LEFT JOIN teachers ON teachers.id = schedule.teacher
+                 WHERE
+                     (distribution = 'all'::distribution_week OR distribution = week) AND
+                     EXISTS(SELECT * FROM lessons_groups WHERE "group" = student_val."group" AND lesson = schedule.id) AND
+                     (NOT subjects.choice OR EXISTS(SELECT * FROM students_subjects WHERE student = student_val.id AND subject = schedule.subject))
+                 ORDER BY schedule.day_of_week, schedule.time;
+    RETURN;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION get_group_week_schedule(group_id INTEGER, week distribution_week)
+    RETURNS TABLE(day_of_week days_of_week, subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING, room CHARACTER VARYING)
+    LANGUAGE SQL
+AS
+$$
+SELECT DISTINCT
+    schedule.day_of_week,
+    subjects.name,
+    schedule.type,
+    schedule.time,
+    concat_ws(' ', teachers.last_name,  teachers.first_name, teachers.patronymic_name),
+    schedule.info,
+    COALESCE(schedule.room, '')
+FROM schedule
+         JOIN subjects ON subjects.id = schedule.subject
+         LEFT JOIN teachers ON teachers.id = schedule.teacher
+WHERE
+    (distribution = 'all'::distribution_week OR distribution = week) AND
+    EXISTS(SELECT * FROM lessons_groups WHERE "group" = group_id AND lesson = schedule.id)
+ORDER BY schedule.day_of_week, schedule.time
+$$;
+
+CREATE OR REPLACE FUNCTION get_teacher_week_schedule(teacher_id INTEGER, week distribution_week)
+    RETURNS TABLE(day_of_week days_of_week, subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING, room CHARACTER VARYING)
+    LANGUAGE SQL
+AS
+$$
+SELECT DISTINCT
+    schedule.day_of_week,
+    subjects.name,
+    schedule.type,
+    schedule.time,
+    concat_ws(' ', teachers.last_name,  teachers.first_name, teachers.patronymic_name),
+    schedule.info,
+    COALESCE(schedule.room, '')
+FROM schedule
+         JOIN subjects ON subjects.id = schedule.subject
+         JOIN teachers ON teachers.id = schedule.teacher
+WHERE
+    (distribution = 'all'::distribution_week OR distribution = week) AND
+    schedule.teacher = teacher_id
+ORDER BY schedule.day_of_week, schedule.time
+$$;
+
+CREATE OR REPLACE FUNCTION get_room_week_schedule(room_name VARCHAR, week distribution_week)
+    RETURNS TABLE(day_of_week days_of_week, subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING, room CHARACTER VARYING)
+    LANGUAGE SQL
+AS
+$$
+SELECT DISTINCT
+    schedule.day_of_week,
+    subjects.name,
+    schedule.type,
+    schedule.time,
+    concat_ws(' ', teachers.last_name,  teachers.first_name, teachers.patronymic_name),
+    schedule.info,
+    COALESCE(schedule.room, '')
+FROM schedule
+         JOIN subjects ON subjects.id = schedule.subject
+         LEFT JOIN teachers ON teachers.id = schedule.teacher
+WHERE
+    (distribution = 'all'::distribution_week OR distribution = week) AND
+    schedule.room = room_name
+ORDER BY schedule.day_of_week, schedule.time
+$$;
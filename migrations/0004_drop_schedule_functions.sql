@@ -0,0 +1,11 @@
+-- Lessons are chosen in Rust (`schedule_core::selection`) from plain table reads.
+
+DROP FUNCTION IF EXISTS get_current_schedule(BIGINT);
+DROP FUNCTION IF EXISTS get_today_schedule(BIGINT);
+DROP FUNCTION IF EXISTS get_schedule(days_of_week, BIGINT);
+DROP FUNCTION IF EXISTS get_week_schedule(BIGINT, distribution_week);
+DROP FUNCTION IF EXISTS get_group_week_schedule(INTEGER, distribution_week);
+DROP FUNCTION IF EXISTS get_teacher_week_schedule(INTEGER, distribution_week);
+DROP FUNCTION IF EXISTS get_room_week_schedule(VARCHAR, distribution_week);
+DROP FUNCTION IF EXISTS get_distribution_week();
+DROP TYPE IF EXISTS lesson;
@@ -0,0 +1,8 @@
+-- Preferences of bot users, keyed by Telegram id so they work before registration.
+
+CREATE TYPE language AS ENUM ('uk', 'ru', 'en');
+
+CREATE TABLE user_settings(
+    telegram_id BIGINT PRIMARY KEY,
+    language language
+);
@@ -0,0 +1,9 @@
+-- Everything else `/settings` lets bot users choose; NULL turns a notification off.
+
+CREATE TYPE lesson_format AS ENUM ('compact', 'verbose');
+
+ALTER TABLE user_settings
+    ADD COLUMN reminder_minutes INTEGER CHECK (reminder_minutes > 0),
+    ADD COLUMN digest_time TIME,
+    ADD COLUMN lesson_format lesson_format NOT NULL DEFAULT 'verbose',
+    ADD COLUMN show_teacher BOOLEAN NOT NULL DEFAULT TRUE;
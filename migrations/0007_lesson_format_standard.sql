@@ -0,0 +1,5 @@
+-- The format chosen so far as `verbose` leaves out empty fields; it is `standard` now, and
+-- `verbose` shows every field.
+
+ALTER TYPE lesson_format RENAME VALUE 'verbose' TO 'standard';
+ALTER TYPE lesson_format ADD VALUE 'verbose';
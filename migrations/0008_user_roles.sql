@@ -0,0 +1,10 @@
+-- Roles of bot users, granted by admins with `/grant`. Registered students have the `student`
+-- role without a row here.
+
+CREATE TYPE role AS ENUM ('admin', 'group_leader', 'teacher', 'student');
+
+CREATE TABLE user_roles(
+    telegram_id BIGINT NOT NULL,
+    role role NOT NULL,
+    PRIMARY KEY (telegram_id, role)
+);
@@ -0,0 +1,20 @@
+-- Teachers linked to their Telegram accounts with `/link_teacher`, and the log of announcements
+-- with the users each was sent to, reviewed with `/announcements`.
+
+ALTER TABLE teachers ADD COLUMN telegram_id BIGINT UNIQUE;
+
+CREATE TABLE announcements(
+    id SERIAL PRIMARY KEY,
+    author BIGINT NOT NULL,
+    author_name VARCHAR(255) NOT NULL,
+    text TEXT NOT NULL,
+    sent_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE TABLE announcement_recipients(
+    announcement INTEGER NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    telegram_id BIGINT NOT NULL,
+    PRIMARY KEY (announcement, telegram_id)
+);
+
+CREATE INDEX announcement_recipients_telegram_id ON announcement_recipients(telegram_id);
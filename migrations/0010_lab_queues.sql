@@ -0,0 +1,11 @@
+-- Queues for defending laboratory works, one per lesson of the timetable. Called students stay
+-- at the head of the queue until the teacher marks them done.
+
+CREATE TABLE lab_queue(
+    id SERIAL PRIMARY KEY,
+    lesson INTEGER NOT NULL REFERENCES schedule(id) ON DELETE CASCADE,
+    telegram_id BIGINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    called_at TIMESTAMP,
+    UNIQUE (lesson, telegram_id)
+);
@@ -0,0 +1,23 @@
+-- Homework due at the lessons of a subject on a date, given to a group by its leader or
+-- teacher, with the Telegram files attached to it.
+
+CREATE TYPE attachment AS ENUM ('document', 'photo');
+
+CREATE TABLE homework(
+    id SERIAL PRIMARY KEY,
+    subject INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+    "group" INTEGER NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    text TEXT NOT NULL,
+    author BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX homework_group_date ON homework("group", date);
+
+CREATE TABLE homework_files(
+    id SERIAL PRIMARY KEY,
+    homework INTEGER NOT NULL REFERENCES homework(id) ON DELETE CASCADE,
+    file_id VARCHAR(255) NOT NULL,
+    kind attachment NOT NULL
+);
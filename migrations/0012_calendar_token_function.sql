@@ -0,0 +1,4 @@
+-- Calendar tokens come from the operating system's random source in Rust
+-- (`schedule_core::database::token`) instead of `md5(random())`.
+
+DROP FUNCTION IF EXISTS get_calendar_token(BIGINT);
@@ -0,0 +1,6 @@
+-- Session tokens come from the operating system's random source in Rust
+-- (`schedule_core::database::token`) instead of `md5(random())`. Sessions with the old tokens
+-- are ended, so their users log in again.
+
+DROP FUNCTION IF EXISTS create_session(BIGINT, VARCHAR, VARCHAR);
+DELETE FROM sessions;
@@ -0,0 +1,10 @@
+-- Queues belong to a single occurrence of a laboratory work, so that students who weren't called
+-- at one are not queued ahead of everyone at the next. Entries from before can't be told apart
+-- by occurrence and are dropped.
+
+DELETE FROM lab_queue;
+
+ALTER TABLE lab_queue
+    ADD COLUMN date DATE NOT NULL,
+    DROP CONSTRAINT lab_queue_lesson_telegram_id_key,
+    ADD UNIQUE (lesson, date, telegram_id);
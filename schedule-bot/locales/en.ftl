@@ -0,0 +1,139 @@
+help =
+    Supported commands:
+    /help — show this message.
+    /today — show today's timetable.
+    /day — show the timetable of a chosen day.
+    /current — show the current and the next lesson.
+    /week — show the timetable of a chosen week. (Type 1 or 2 after the command).
+    /week_image — send the timetable of a week as a picture. (1 or 2 may follow, the current week by default).
+    /pdf — get the timetable of both weeks as a printable PDF.
+    /deadline — show the list of deadlines.[WIP]
+    /current_week — show which week it is.
+    /calendar — get a link to subscribe to the timetable in a calendar.
+    /lang — choose the language (uk, ru or en).
+    /settings — change the language, the look of lessons, reminders and the digest.
+    /roles — show your Telegram id and roles.
+    /announcements — show the last announcements sent to you.
+    /queue — the queue to defend your nearest laboratory work: join, leave or list (the default).
+    /cancel — stop what you started, such as choosing a day.
+    compact, standard or verbose after /today, /current, /week, /week_image or /pdf changes the look of lessons once, e.g. /week 1 compact.
+
+help-admin =
+    Admin commands:
+    /roles <id> — show the roles of a user.
+    /grant <id> <role> — grant a role: admin, group_leader, teacher or student.
+    /revoke <id> <role> — take a role away.
+    /link_teacher <id> <teacher id> — link a teacher of the timetable to a user and grant the teacher role.
+roles =
+    Telegram id: { $id }
+    Roles: { $roles }
+roles-none = none
+role-admin = admin
+role-group-leader = group leader
+role-teacher = teacher
+role-student = student
+role-granted = Role «{ $role }» granted to { $id }
+role-revoked = Role «{ $role }» taken from { $id }
+role-not-granted = { $id } has no role «{ $role }»
+
+help-group-leader =
+    Group leader commands:
+    /announce — send an announcement to students.
+    /homework — give homework for a lesson.
+announce-no-audience = There is no one you can send an announcement to
+announce-choose = Who is the announcement for?
+announce-everyone = Everyone
+announce-text = Send the text of the announcement for { $audience }
+announce-confirm =
+    Announcement for { $audience }, recipients: { $count }
+
+    { $text }
+
+    Send it?
+announce-send = ✅ Send
+announce-cancel = ❌ Cancel
+announce-sending = Sending the announcement, recipients: { $count }. I will report when it is done.
+announce-report = Announcement sent. Delivered: { $delivered }, blocked the bot: { $blocked }, failed: { $failed }.
+announcement =
+    📢 { $author }:
+    { $text }
+cancelled = Cancelled
+help-teacher =
+    Teacher commands:
+    /notify — send an announcement to the students of your subjects.
+    /next — call the next student of the queue at your nearest laboratory work.
+    /done — mark the called students done.
+    /homework — give homework for a lesson.
+announcements-none = No announcements have been sent to you yet
+teacher-linked = Teacher { $teacher } linked to { $id }
+queue-joined = You joined the queue for { $lab }, position: { $position }
+queue-already = You are already in the queue for { $lab }, position: { $position }
+queue-left = You left the queue for { $lab }
+queue-called = Called: { $names }
+queue-empty = No one is waiting
+queue-called-student = Called { $name }
+queue-done = Done: { $names }
+queue-your-turn = 🔔 Your turn to defend { $lab }!
+queue-next-up = You are next in the queue for { $lab }
+queue-position = Your position in the queue for { $lab }: { $position }
+homework-choose = Which subject is the homework for?
+homework-choose-date = Which lesson is the homework due at?
+homework-text = Send the text of the homework for { $subject } due { $date }. A file may come with the text as its caption.
+homework-files = Files attached: { $count }. Send more files or save the homework.
+homework-save = 💾 Save
+homework-cancel = ❌ Cancel
+homework-saved = Homework for { $subject } due { $date } saved
+
+choose-day = Choose a day of the week
+current-week = It is the { $week } week now
+calendar-link =
+    Link to subscribe to the timetable:
+    { $url }
+language-changed = From now on I will write in English
+settings =
+    ⚙️ Settings
+    🌐 Language: { $language }
+    📋 Lessons: { $format }
+    👤 Teachers: { $teacher }
+    🔔 Reminder: { $reminder }
+    📰 Daily digest: { $digest }
+settings-compact = compact
+settings-standard = standard
+settings-verbose = full
+settings-shown = shown
+settings-hidden = hidden
+settings-show = show
+settings-hide = hide
+settings-off = off
+settings-reminder-before = { $minutes } min before a lesson
+reminder = ⏰ In { $minutes } min:
+digest = 🗓 Tomorrow, { $day }:
+send-text = Send a text message
+unknown-command = Unknown command, type /help
+
+error-database = Could not get the data, try again later
+error-not-registered = You are not registered as a student
+error-forbidden = This command is not available to you
+error-telegram = Could not send the answer, try again later
+error-render = Could not draw the timetable, try again later
+invalid-day = Invalid day of the week
+invalid-arguments = Invalid command arguments, type /help
+week-usage = Type 1 or 2 after the command, then compact, standard or verbose if you like
+format-usage = Type compact, standard or verbose after the command, or nothing
+lang-usage = Type the language after the command: /lang uk, /lang ru or /lang en
+grant-usage = Type the Telegram id and the role after the command: admin, group_leader, teacher or student
+announce-unknown-audience = Choose the recipients on the keyboard
+announce-confirm-usage = Press «✅ Send» or «❌ Cancel»
+teacher-not-linked = Your account is not linked to a teacher of the timetable, ask an admin
+unknown-teacher = There is no teacher with this id
+link-teacher-usage = Type the Telegram id and the teacher id after the command: /link_teacher 123456789 7
+queue-usage = Type join, leave or list after /queue
+queue-no-labs = There are no laboratory works coming up
+queue-not-queued = You are not in a queue
+queue-none-called = No one has been called, use /next
+homework-no-subjects = There are no subjects you can give homework for
+homework-no-dates = There are no lessons of this subject soon
+homework-unknown-subject = Choose a subject on the keyboard
+homework-unknown-date = Choose a lesson on the keyboard
+homework-text-usage = Send the text of the homework
+homework-files-usage = Send a file or press «💾 Save» or «❌ Cancel»
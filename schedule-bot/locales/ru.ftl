@@ -0,0 +1,139 @@
+help =
+    Список поддерживаемых команд:
+    /help — показать это сообщение.
+    /today — показать расписание на сегодня.
+    /day — показать расписание на выбранный день.
+    /current — показать текущую и последующую пары.
+    /week — показать расписание на выбранную неделю. (Введите 1 или 2 после команды).
+    /week_image — прислать расписание недели картинкой. (Можно указать 1 или 2, по умолчанию текущая).
+    /pdf — получить расписание на обе недели в PDF для печати.
+    /deadline — показать список дедлайнов.[WIP]
+    /current_week — показать какая неделя по счету.
+    /calendar — получить ссылку для подписки на расписание в календаре.
+    /lang — выбрать язык (uk, ru или en).
+    /settings — настроить язык, вид пар, напоминания и сводку.
+    /roles — показать ваш Telegram id и роли.
+    /announcements — показать последние объявления, отправленные вам.
+    /queue — очередь на защиту ближайшей лабораторной работы: join (встать), leave (выйти) или list (показать, по умолчанию).
+    /cancel — отменить начатое, например выбор дня.
+    compact, standard или verbose после /today, /current, /week, /week_image или /pdf меняет вид пар один раз, например /week 1 compact.
+
+help-admin =
+    Команды администратора:
+    /roles <id> — показать роли пользователя.
+    /grant <id> <роль> — выдать роль: admin, group_leader, teacher или student.
+    /revoke <id> <роль> — забрать роль.
+    /link_teacher <id> <id преподавателя> — связать преподавателя из расписания с пользователем и выдать роль преподавателя.
+roles =
+    Telegram id: { $id }
+    Роли: { $roles }
+roles-none = нет
+role-admin = администратор
+role-group-leader = староста
+role-teacher = преподаватель
+role-student = студент
+role-granted = Роль «{ $role }» выдана { $id }
+role-revoked = Роль «{ $role }» забрана у { $id }
+role-not-granted = У { $id } нет роли «{ $role }»
+
+help-group-leader =
+    Команды старосты:
+    /announce — отправить объявление студентам.
+    /homework — задать домашнее задание к паре.
+announce-no-audience = Вам некому отправить объявление
+announce-choose = Кому отправить объявление?
+announce-everyone = Всем
+announce-text = Отправьте текст объявления для { $audience }
+announce-confirm =
+    Объявление для { $audience }, получателей: { $count }
+
+    { $text }
+
+    Отправить?
+announce-send = ✅ Отправить
+announce-cancel = ❌ Отменить
+announce-sending = Отправляю объявление, получателей: { $count }. Сообщу, когда закончу.
+announce-report = Объявление отправлено. Доставлено: { $delivered }, заблокировали бота: { $blocked }, ошибок: { $failed }.
+announcement =
+    📢 { $author }:
+    { $text }
+cancelled = Отменено
+help-teacher =
+    Команды преподавателя:
+    /notify — отправить объявление студентам ваших предметов.
+    /next — вызвать следующего студента из очереди на вашей ближайшей лабораторной работе.
+    /done — отметить вызванных студентов как защитившихся.
+    /homework — задать домашнее задание к паре.
+announcements-none = Вам ещё не отправляли объявлений
+teacher-linked = Преподаватель { $teacher } связан с { $id }
+queue-joined = Вы в очереди на { $lab }, позиция: { $position }
+queue-already = Вы уже в очереди на { $lab }, позиция: { $position }
+queue-left = Вы вышли из очереди на { $lab }
+queue-called = Вызваны: { $names }
+queue-empty = Никто не ждёт
+queue-called-student = Вызван: { $name }
+queue-done = Защитились: { $names }
+queue-your-turn = 🔔 Ваша очередь защищать { $lab }!
+queue-next-up = Вы следующий в очереди на { $lab }
+queue-position = Ваша позиция в очереди на { $lab }: { $position }
+homework-choose = К какому предмету домашнее задание?
+homework-choose-date = К какой паре домашнее задание?
+homework-text = Отправьте текст домашнего задания по { $subject } на { $date }. Файл можно отправить с текстом в подписи.
+homework-files = Прикреплено файлов: { $count }. Отправьте ещё файлы или сохраните задание.
+homework-save = 💾 Сохранить
+homework-cancel = ❌ Отменить
+homework-saved = Домашнее задание по { $subject } на { $date } сохранено
+
+choose-day = Выберите день недели
+current-week = Сейчас { $week } неделя
+calendar-link =
+    Ссылка для подписки на расписание:
+    { $url }
+language-changed = Теперь я буду писать по-русски
+settings =
+    ⚙️ Настройки
+    🌐 Язык: { $language }
+    📋 Пары: { $format }
+    👤 Преподаватели: { $teacher }
+    🔔 Напоминание: { $reminder }
+    📰 Ежедневная сводка: { $digest }
+settings-compact = кратко
+settings-standard = обычно
+settings-verbose = полностью
+settings-shown = показаны
+settings-hidden = скрыты
+settings-show = показать
+settings-hide = скрыть
+settings-off = выкл.
+settings-reminder-before = за { $minutes } мин до пары
+reminder = ⏰ Через { $minutes } мин:
+digest = 🗓 Завтра, { $day }:
+send-text = Отправьте текстовое сообщение
+unknown-command = Неизвестная команда, введите /help
+
+error-database = Не удалось получить данные, попробуйте позже
+error-not-registered = Вы не зарегистрированы как студент
+error-forbidden = Эта команда вам недоступна
+error-telegram = Не удалось отправить ответ, попробуйте позже
+error-render = Не удалось нарисовать расписание, попробуйте позже
+invalid-day = Неверно введен день недели
+invalid-arguments = Неверные аргументы команды, введите /help
+week-usage = Введите 1 или 2 после команды, затем при желании compact, standard или verbose
+format-usage = Введите compact, standard или verbose после команды либо ничего
+lang-usage = Введите язык после команды: /lang uk, /lang ru или /lang en
+grant-usage = Введите Telegram id и роль после команды: admin, group_leader, teacher или student
+announce-unknown-audience = Выберите получателей на клавиатуре
+announce-confirm-usage = Нажмите «✅ Отправить» или «❌ Отменить»
+teacher-not-linked = Ваша учётная запись не связана с преподавателем из расписания, обратитесь к администратору
+unknown-teacher = Преподавателя с таким id нет
+link-teacher-usage = Укажите после команды Telegram id и id преподавателя: /link_teacher 123456789 7
+queue-usage = Укажите после /queue join, leave или list
+queue-no-labs = В ближайшее время лабораторных работ нет
+queue-not-queued = Вы не стоите в очереди
+queue-none-called = Никто не вызван, воспользуйтесь /next
+homework-no-subjects = Нет предметов, к которым вы можете задать домашнее задание
+homework-no-dates = В ближайшее время пар по этому предмету нет
+homework-unknown-subject = Выберите предмет на клавиатуре
+homework-unknown-date = Выберите пару на клавиатуре
+homework-text-usage = Отправьте текст домашнего задания
+homework-files-usage = Отправьте файл или нажмите «💾 Сохранить» либо «❌ Отменить»
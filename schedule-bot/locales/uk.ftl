@@ -0,0 +1,139 @@
+help =
+    Список підтримуваних команд:
+    /help — показати це повідомлення.
+    /today — показати розклад на сьогодні.
+    /day — показати розклад на обраний день.
+    /current — показати поточну та наступну пари.
+    /week — показати розклад на обраний тиждень. (Введіть 1 або 2 після команди).
+    /week_image — надіслати розклад тижня картинкою. (Можна вказати 1 або 2, за замовчуванням поточний).
+    /pdf — отримати розклад на обидва тижні у PDF для друку.
+    /deadline — показати список дедлайнів.[WIP]
+    /current_week — показати, який зараз тиждень.
+    /calendar — отримати посилання для підписки на розклад у календарі.
+    /lang — обрати мову (uk, ru або en).
+    /settings — налаштувати мову, вигляд пар, нагадування та зведення.
+    /roles — показати ваш Telegram id і ролі.
+    /announcements — показати останні оголошення, надіслані вам.
+    /queue — черга на захист найближчої лабораторної роботи: join (стати), leave (вийти) або list (показати, за замовчуванням).
+    /cancel — скасувати розпочате, наприклад вибір дня.
+    compact, standard або verbose після /today, /current, /week, /week_image або /pdf змінює вигляд пар один раз, наприклад /week 1 compact.
+
+help-admin =
+    Команди адміністратора:
+    /roles <id> — показати ролі користувача.
+    /grant <id> <роль> — надати роль: admin, group_leader, teacher або student.
+    /revoke <id> <роль> — забрати роль.
+    /link_teacher <id> <id викладача> — пов'язати викладача з розкладу з користувачем і надати роль викладача.
+roles =
+    Telegram id: { $id }
+    Ролі: { $roles }
+roles-none = немає
+role-admin = адміністратор
+role-group-leader = староста
+role-teacher = викладач
+role-student = студент
+role-granted = Роль «{ $role }» надано { $id }
+role-revoked = Роль «{ $role }» забрано в { $id }
+role-not-granted = У { $id } немає ролі «{ $role }»
+
+help-group-leader =
+    Команди старости:
+    /announce — надіслати оголошення студентам.
+    /homework — задати домашнє завдання до пари.
+announce-no-audience = Вам нікому надіслати оголошення
+announce-choose = Кому надіслати оголошення?
+announce-everyone = Усім
+announce-text = Надішліть текст оголошення для { $audience }
+announce-confirm =
+    Оголошення для { $audience }, отримувачів: { $count }
+
+    { $text }
+
+    Надіслати?
+announce-send = ✅ Надіслати
+announce-cancel = ❌ Скасувати
+announce-sending = Надсилаю оголошення, отримувачів: { $count }. Повідомлю, коли закінчу.
+announce-report = Оголошення надіслано. Доставлено: { $delivered }, заблокували бота: { $blocked }, помилок: { $failed }.
+announcement =
+    📢 { $author }:
+    { $text }
+cancelled = Скасовано
+help-teacher =
+    Команди викладача:
+    /notify — надіслати оголошення студентам ваших предметів.
+    /next — викликати наступного студента з черги на вашій найближчій лабораторній роботі.
+    /done — позначити викликаних студентів як тих, хто захистився.
+    /homework — задати домашнє завдання до пари.
+announcements-none = Вам ще не надсилали оголошень
+teacher-linked = Викладача { $teacher } пов'язано з { $id }
+queue-joined = Ви в черзі на { $lab }, позиція: { $position }
+queue-already = Ви вже в черзі на { $lab }, позиція: { $position }
+queue-left = Ви вийшли з черги на { $lab }
+queue-called = Викликано: { $names }
+queue-empty = Ніхто не чекає
+queue-called-student = Викликано: { $name }
+queue-done = Захистилися: { $names }
+queue-your-turn = 🔔 Ваша черга захищати { $lab }!
+queue-next-up = Ви наступні в черзі на { $lab }
+queue-position = Ваша позиція в черзі на { $lab }: { $position }
+homework-choose = До якого предмета домашнє завдання?
+homework-choose-date = До якої пари домашнє завдання?
+homework-text = Надішліть текст домашнього завдання з { $subject } на { $date }. Файл можна надіслати з текстом у підписі.
+homework-files = Прикріплено файлів: { $count }. Надішліть ще файли або збережіть завдання.
+homework-save = 💾 Зберегти
+homework-cancel = ❌ Скасувати
+homework-saved = Домашнє завдання з { $subject } на { $date } збережено
+
+choose-day = Оберіть день тижня
+current-week = Зараз { $week } тиждень
+calendar-link =
+    Посилання для підписки на розклад:
+    { $url }
+language-changed = Тепер я писатиму українською
+settings =
+    ⚙️ Налаштування
+    🌐 Мова: { $language }
+    📋 Пари: { $format }
+    👤 Викладачі: { $teacher }
+    🔔 Нагадування: { $reminder }
+    📰 Щоденне зведення: { $digest }
+settings-compact = коротко
+settings-standard = звичайно
+settings-verbose = повністю
+settings-shown = показані
+settings-hidden = приховані
+settings-show = показати
+settings-hide = приховати
+settings-off = вимк.
+settings-reminder-before = за { $minutes } хв до пари
+reminder = ⏰ За { $minutes } хв:
+digest = 🗓 Завтра, { $day }:
+send-text = Надішліть текстове повідомлення
+unknown-command = Невідома команда, введіть /help
+
+error-database = Не вдалося отримати дані, спробуйте пізніше
+error-not-registered = Ви не зареєстровані як студент
+error-forbidden = Ця команда вам недоступна
+error-telegram = Не вдалося надіслати відповідь, спробуйте пізніше
+error-render = Не вдалося намалювати розклад, спробуйте пізніше
+invalid-day = Неправильно введено день тижня
+invalid-arguments = Неправильні аргументи команди, введіть /help
+week-usage = Введіть 1 або 2 після команди, потім за бажанням compact, standard або verbose
+format-usage = Введіть compact, standard або verbose після команди або нічого
+lang-usage = Введіть мову після команди: /lang uk, /lang ru або /lang en
+grant-usage = Введіть Telegram id і роль після команди: admin, group_leader, teacher або student
+announce-unknown-audience = Оберіть отримувачів на клавіатурі
+announce-confirm-usage = Натисніть «✅ Надіслати» або «❌ Скасувати»
+teacher-not-linked = Ваш обліковий запис не пов'язано з викладачем із розкладу, зверніться до адміністратора
+unknown-teacher = Викладача з таким id немає
+link-teacher-usage = Вкажіть після команди Telegram id і id викладача: /link_teacher 123456789 7
+queue-usage = Вкажіть після /queue join, leave або list
+queue-no-labs = Найближчим часом лабораторних робіт немає
+queue-not-queued = Ви не стоїте в черзі
+queue-none-called = Нікого не викликано, скористайтеся /next
+homework-no-subjects = Немає предметів, до яких ви можете задати домашнє завдання
+homework-no-dates = Найближчим часом пар із цього предмета немає
+homework-unknown-subject = Оберіть предмет на клавіатурі
+homework-unknown-date = Оберіть пару на клавіатурі
+homework-text-usage = Надішліть текст домашнього завдання
+homework-files-usage = Надішліть файл або натисніть «💾 Зберегти» чи «❌ Скасувати»
@@ -0,0 +1,39 @@
+//! Roles of bot users and the checks of the commands that need one.
+
+use schedule_core::database::role::Role;
+use schedule_core::database::teacher::Teacher;
+use schedule_core::repository;
+
+use crate::config::Config;
+use crate::error::BotError;
+
+/// Roles of the user, with `Admin` for the admins of the config.
+pub async fn roles(user_id: i64) -> Result<Vec<Role>, BotError> {
+    let mut roles = repository::global().get_roles(user_id).await?;
+    if Config::global().admins.contains(&user_id) && !roles.contains(&Role::Admin) {
+        roles.insert(0, Role::Admin);
+    }
+    Ok(roles)
+}
+
+/// Whether one with `roles` may do what `role` may; admins may do anything.
+pub fn allows(roles: &[Role], role: Role) -> bool {
+    roles.contains(&Role::Admin) || roles.contains(&role)
+}
+
+/// Fails with [`BotError::Forbidden`] unless the user may do what `role` may.
+pub async fn require(user_id: i64, role: Role) -> Result<(), BotError> {
+    if allows(&roles(user_id).await?, role) {
+        Ok(())
+    } else {
+        Err(BotError::Forbidden)
+    }
+}
+
+/// Teacher of the timetable linked to the user with `/link_teacher`.
+pub async fn linked_teacher(user_id: i64) -> Result<Teacher, BotError> {
+    repository::global()
+        .get_linked_teacher(user_id)
+        .await?
+        .ok_or(BotError::InvalidInput("teacher-not-linked"))
+}
@@ -0,0 +1,277 @@
+//! `/announce`: group leaders write to the students of their group and admins to everyone, any
+//! group or the students of an elective. `/notify`: teachers write to the students they teach a
+//! subject. Messages go out one by one under Telegram's limits and are logged for
+//! `/announcements`.
+
+use once_cell::sync::Lazy;
+use schedule_core::database::announcement::Audience;
+use schedule_core::database::role::Role;
+use schedule_core::locale::Language;
+use schedule_core::render;
+use schedule_core::repository;
+use std::time::Duration;
+use teloxide::dispatching::UpdateWithCx;
+use teloxide::prelude::*;
+use teloxide::types::{KeyboardButton, KeyboardMarkup, KeyboardRemove};
+use teloxide::{ApiError, RequestError};
+
+use crate::access;
+use crate::dialogue::states::{AnnounceAudienceState, AnnounceTextState};
+use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::locale;
+use crate::schedule::reply;
+
+/// Pause between two messages, keeping under Telegram's limit of 30 messages a second.
+pub const INTERVAL: Duration = Duration::from_millis(40);
+
+/// Announcements shown by `/announcements`.
+const HISTORY: i64 = 10;
+
+/// Attempts to send a message when Telegram asks to retry later.
+const ATTEMPTS: usize = 3;
+
+/// Announcements are delivered one at a time, so that two don't add up over the limit.
+static DELIVERY: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// Audiences the user may announce to, labelled as on the keyboard.
+pub async fn audiences(
+    user_id: i64,
+    language: Language,
+) -> Result<Vec<(String, Audience)>, BotError> {
+    let roles = access::roles(user_id).await?;
+    let repository = repository::global();
+    let mut audiences = vec![];
+    if roles.contains(&Role::Admin) {
+        audiences.push((
+            format!("📣 {}", locale::text(language, "announce-everyone")),
+            Audience::Everyone,
+        ));
+        for group in repository.get_groups().await? {
+            audiences.push((format!("👥 {}", group.name), Audience::Group(group.id)));
+        }
+        for subject in repository.get_elective_subjects().await? {
+            audiences.push((
+                format!("📚 {}", subject.name),
+                Audience::Subject(subject.id),
+            ));
+        }
+    } else if roles.contains(&Role::GroupLeader) {
+        if let Some(group) = repository.get_student_group(user_id).await? {
+            audiences.push((format!("👥 {}", group.name), Audience::Group(group.id)));
+        }
+    }
+    Ok(audiences)
+}
+
+/// Audiences of the subjects the user teaches as the linked teacher: all the groups of a
+/// subject, and each group on its own when there are several.
+pub async fn taught(user_id: i64) -> Result<Vec<(String, Audience)>, BotError> {
+    let teacher = access::linked_teacher(user_id).await?;
+    let taught = repository::global().get_taught_groups(teacher.id).await?;
+    let mut audiences = vec![];
+    for groups in taught.chunk_by(|a, b| a.subject == b.subject) {
+        let subject = &groups[0];
+        audiences.push((
+            format!("📚 {}", subject.subject_name),
+            Audience::Taught {
+                teacher: teacher.id,
+                subject: subject.subject,
+                group: None,
+            },
+        ));
+        if groups.len() > 1 {
+            for group in groups {
+                audiences.push((
+                    format!("📚 {} · {}", group.subject_name, group.group_name),
+                    Audience::Taught {
+                        teacher: teacher.id,
+                        subject: group.subject,
+                        group: Some(group.group),
+                    },
+                ));
+            }
+        }
+    }
+    Ok(audiences)
+}
+
+/// Whether the user may still announce to the audience; roles and links may have changed since
+/// it was chosen.
+pub async fn allows(user_id: i64, audience: Audience) -> Result<bool, BotError> {
+    let audiences = match audience {
+        Audience::Taught { .. } => {
+            if !access::allows(&access::roles(user_id).await?, Role::Teacher) {
+                return Ok(false);
+            }
+            taught(user_id).await?
+        }
+        _ => audiences(user_id, Language::default()).await?,
+    };
+    Ok(audiences.iter().any(|(_, other)| *other == audience))
+}
+
+/// Asks which of the audiences to announce to, unless there is a single choice.
+pub async fn start(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    audiences: Vec<(String, Audience)>,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    match audiences.as_slice() {
+        [] => Err(BotError::InvalidInput("announce-no-audience")),
+        [(label, audience)] => ask_text(cx, *audience, label.clone(), language).await,
+        _ => {
+            let keyboard = audiences.iter().fold(
+                KeyboardMarkup::default().resize_keyboard(true),
+                |keyboard, (label, _)| keyboard.append_row(vec![KeyboardButton::new(label)]),
+            );
+            cx.answer(locale::text(language, "announce-choose"))
+                .reply_markup(keyboard)
+                .send()
+                .await?;
+            next(Dialogue::AnnounceAudience(AnnounceAudienceState {
+                audiences,
+            }))
+        }
+    }
+}
+
+pub async fn ask_text(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    audience: Audience,
+    label: String,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    cx.answer(locale::format(
+        language,
+        "announce-text",
+        vec![("audience", label.as_str().into())],
+    ))
+    .reply_markup(KeyboardRemove::new())
+    .send()
+    .await?;
+    next(Dialogue::AnnounceText(AnnounceTextState {
+        audience,
+        label,
+    }))
+}
+
+/// Sends the last announcements the user received, newest first.
+pub async fn history(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    language: Language,
+) -> Result<(), BotError> {
+    let announcements = repository::global()
+        .get_announcements(user_id, HISTORY)
+        .await?;
+    if announcements.is_empty() {
+        cx.answer(locale::text(language, "announcements-none"))
+            .send()
+            .await?;
+        return Ok(());
+    }
+    let parts = announcements
+        .iter()
+        .map(|announcement| {
+            format!(
+                "📢 <b>{}</b> · {}\n{}",
+                render::escape(&announcement.author_name),
+                announcement.sent_at.format("%d.%m.%Y %H:%M"),
+                render::escape(&announcement.text)
+            )
+        })
+        .collect();
+    reply::parts(cx, parts).await?;
+    Ok(())
+}
+
+/// Sends the announcement in the background and then the report to `chat_id`.
+pub fn spawn(
+    bot: AutoSend<Bot>,
+    chat_id: i64,
+    language: Language,
+    recipients: Vec<i64>,
+    author: String,
+    text: String,
+) {
+    tokio::spawn(async move {
+        let report = deliver(&bot, &recipients, &author, &text).await;
+        let message = locale::format(
+            language,
+            "announce-report",
+            vec![
+                ("delivered", report.delivered.into()),
+                ("blocked", report.blocked.into()),
+                ("failed", report.failed.into()),
+            ],
+        );
+        if let Err(error) = bot.send_message(chat_id, message).await {
+            log::error!(
+                "Chat {}: failed to report an announcement: {}",
+                chat_id,
+                error
+            );
+        }
+    });
+}
+
+#[derive(Default)]
+struct Report {
+    delivered: usize,
+    /// Recipients who blocked the bot or are gone from Telegram.
+    blocked: usize,
+    failed: usize,
+}
+
+async fn deliver(bot: &AutoSend<Bot>, recipients: &[i64], author: &str, text: &str) -> Report {
+    let _delivery = DELIVERY.lock().await;
+    let mut report = Report::default();
+    for &chat_id in recipients {
+        let language = locale::chosen_language(chat_id).await;
+        let message = locale::format(
+            language,
+            "announcement",
+            vec![("author", author.into()), ("text", text.into())],
+        );
+        match send(bot, chat_id, &message).await {
+            Ok(()) => report.delivered += 1,
+            Err(RequestError::ApiError { kind, .. }) if is_blocked(&kind) => report.blocked += 1,
+            Err(error) => {
+                log::warn!(
+                    "Chat {}: failed to deliver an announcement: {}",
+                    chat_id,
+                    error
+                );
+                report.failed += 1;
+            }
+        }
+        tokio::time::sleep(INTERVAL).await;
+    }
+    report
+}
+
+/// Sends the message, waiting as long as Telegram asks when the bot sends too many.
+pub async fn send(bot: &AutoSend<Bot>, chat_id: i64, text: &str) -> Result<(), RequestError> {
+    let mut attempt = 1;
+    loop {
+        match bot.send_message(chat_id, text).await {
+            Err(RequestError::RetryAfter(seconds)) if attempt < ATTEMPTS => {
+                tokio::time::sleep(Duration::from_secs(seconds.max(1) as u64)).await;
+                attempt += 1;
+            }
+            result => return result.map(|_| ()),
+        }
+    }
+}
+
+fn is_blocked(kind: &ApiError) -> bool {
+    matches!(
+        kind,
+        ApiError::BotBlocked
+            | ApiError::BotKicked
+            | ApiError::UserDeactivated
+            | ApiError::ChatNotFound
+            | ApiError::CantInitiateConversation
+    )
+}
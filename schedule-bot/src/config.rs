@@ -1,32 +1,10 @@
 use anyhow::Result;
-use serde::de::DeserializeOwned;
+use once_cell::sync::OnceCell;
+use schedule_core::config::Configurable;
 use serde::Deserialize;
 use serde::Serialize;
-use std::fs;
-use std::fs::OpenOptions;
-use std::path::Path;
-
-trait Configurable: Default + Serialize + DeserializeOwned {
-    fn get_config<P: AsRef<Path>>(config_path: P) -> Result<Self> {
-        if let Some(path) = config_path.as_ref().parent() {
-            fs::create_dir_all(path)?;
-        }
-
-        if !config_path.as_ref().exists() {
-            let file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(config_path)?;
-
-            let config = Self::default();
-            serde_json::to_writer_pretty(&file, &config)?;
-            return Ok(config);
-        }
 
-        let config_file = OpenOptions::new().read(true).open(config_path)?;
-        serde_json::from_reader(&config_file).map_err(|error| anyhow::anyhow!(error))
-    }
-}
+static INSTANCE: OnceCell<Config> = OnceCell::new();
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
@@ -34,6 +12,12 @@ pub struct Config {
     pub bot_name: String,
     pub database_url: String,
     pub max_connections: u32,
+    #[serde(default = "default_web_url")]
+    pub web_url: String,
+    /// Telegram ids of the users who are admins without being granted the role, so that there
+    /// is someone to grant it.
+    #[serde(default)]
+    pub admins: Vec<i64>,
 }
 
 impl Default for Config {
@@ -43,14 +27,30 @@ impl Default for Config {
             bot_name: "Schedule IPT".to_string(),
             database_url: "".to_string(),
             max_connections: 5,
+            web_url: default_web_url(),
+            admins: vec![],
         }
     }
 }
 
+fn default_web_url() -> String {
+    "http://localhost:8080".to_string()
+}
+
 impl Configurable for Config {}
 
 impl Config {
     pub fn new() -> Result<Self> {
         Self::get_config("config.json")
     }
+
+    pub fn global() -> &'static Config {
+        INSTANCE.get().expect("Config is not initialized")
+    }
+}
+
+pub fn initialize() -> Result<()> {
+    INSTANCE
+        .set(Config::new()?)
+        .map_err(|_| anyhow::anyhow!("Failed to initialize config!"))
 }
@@ -1,22 +1,34 @@
 use serde::{Deserialize, Serialize};
 use teloxide::macros::Transition;
 
-use crate::dialogue::states::{DayState, StartState};
+use crate::dialogue::states::{
+    AnnounceAudienceState, AnnounceConfirmState, AnnounceTextState, DayState, HomeworkDateState,
+    HomeworkFilesState, HomeworkSubjectState, HomeworkTextState, StartState,
+};
 
 pub mod states;
 
-#[derive(Transition, Serialize, Deserialize)]
+#[derive(Clone, Transition, Serialize, Deserialize)]
 pub enum Dialogue {
     Start(StartState),
     Day(DayState),
+    AnnounceAudience(AnnounceAudienceState),
+    AnnounceText(AnnounceTextState),
+    AnnounceConfirm(AnnounceConfirmState),
+    HomeworkSubject(HomeworkSubjectState),
+    HomeworkDate(HomeworkDateState),
+    HomeworkText(HomeworkTextState),
+    HomeworkFiles(HomeworkFilesState),
 }
 
 impl Dialogue {
     pub fn is_start(&self) -> bool {
-        match &self {
-            Dialogue::Start(_) => true,
-            _ => false,
-        }
+        matches!(self, Dialogue::Start(_))
+    }
+
+    /// Whether a message with a file and no text is an answer, its caption taken as the text.
+    pub fn accepts_files(&self) -> bool {
+        matches!(self, Dialogue::HomeworkText(_) | Dialogue::HomeworkFiles(_))
     }
 }
 
@@ -1,5 +1,11 @@
+pub use announce::{AnnounceAudienceState, AnnounceConfirmState, AnnounceTextState};
 pub use day::DayState;
+pub use homework::{
+    HomeworkDateState, HomeworkFilesState, HomeworkSubjectState, HomeworkTextState,
+};
 pub use start::StartState;
 
+mod announce;
 mod day;
+mod homework;
 mod start;
@@ -0,0 +1,139 @@
+use schedule_core::database::announcement::Audience;
+use schedule_core::repository;
+use serde::{Deserialize, Serialize};
+use teloxide::prelude::*;
+use teloxide::types::{KeyboardButton, KeyboardMarkup, KeyboardRemove};
+
+use crate::announce;
+use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::locale;
+
+/// Waiting for one of the audiences on the keyboard.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnnounceAudienceState {
+    pub audiences: Vec<(String, Audience)>,
+}
+
+/// Waiting for the text of the announcement.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnnounceTextState {
+    pub audience: Audience,
+    pub label: String,
+}
+
+/// Waiting for the announcement to be confirmed.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnnounceConfirmState {
+    pub audience: Audience,
+    pub label: String,
+    pub text: String,
+}
+
+#[teloxide(subtransition)]
+async fn announce_audience(
+    state: AnnounceAudienceState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    let (label, audience) = state
+        .audiences
+        .into_iter()
+        .find(|(label, _)| *label == ans)
+        .ok_or(BotError::InvalidInput("announce-unknown-audience"))?;
+    announce::ask_text(&cx, audience, label, language).await
+}
+
+#[teloxide(subtransition)]
+async fn announce_text(
+    state: AnnounceTextState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    let recipients = repository::global().get_recipients(state.audience).await?;
+    let preview = locale::format(
+        language,
+        "announce-confirm",
+        vec![
+            ("audience", state.label.as_str().into()),
+            ("count", recipients.len().into()),
+            ("text", ans.as_str().into()),
+        ],
+    );
+    let keyboard = KeyboardMarkup::default()
+        .append_row(vec![
+            KeyboardButton::new(locale::text(language, "announce-send")),
+            KeyboardButton::new(locale::text(language, "announce-cancel")),
+        ])
+        .resize_keyboard(true);
+    cx.answer(preview).reply_markup(keyboard).send().await?;
+    next(Dialogue::AnnounceConfirm(AnnounceConfirmState {
+        audience: state.audience,
+        label: state.label,
+        text: ans,
+    }))
+}
+
+#[teloxide(subtransition)]
+async fn announce_confirm(
+    state: AnnounceConfirmState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    if ans == locale::text(language, "announce-cancel") {
+        cx.answer(locale::text(language, "cancelled"))
+            .reply_markup(KeyboardRemove::new())
+            .send()
+            .await?;
+        return next(Dialogue::default());
+    }
+    if ans != locale::text(language, "announce-send") {
+        return Err(BotError::InvalidInput("announce-confirm-usage"));
+    }
+    let user_id = cx.chat_id();
+    if !announce::allows(user_id, state.audience).await? {
+        return Err(BotError::Forbidden);
+    }
+    let recipients = repository::global().get_recipients(state.audience).await?;
+    cx.answer(locale::format(
+        language,
+        "announce-sending",
+        vec![("count", recipients.len().into())],
+    ))
+    .reply_markup(KeyboardRemove::new())
+    .send()
+    .await?;
+    // Teachers sign with the name students see in the timetable.
+    let teacher = match state.audience {
+        Audience::Taught { .. } => repository::global().get_linked_teacher(user_id).await?,
+        _ => None,
+    };
+    let author = match teacher {
+        Some(teacher) => teacher.full_name(),
+        None => cx
+            .update
+            .from()
+            .map(|user| user.full_name())
+            .unwrap_or_default(),
+    };
+    repository::global()
+        .save_announcement(
+            user_id,
+            author.clone(),
+            state.text.clone(),
+            recipients.clone(),
+        )
+        .await?;
+    announce::spawn(
+        cx.requester.clone(),
+        cx.chat_id(),
+        language,
+        recipients,
+        author,
+        state.text,
+    );
+    next(Dialogue::default())
+}
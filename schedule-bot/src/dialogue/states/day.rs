@@ -1,10 +1,17 @@
+use chrono::{Datelike, Duration, Local};
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::Lessons;
+use schedule_core::repository;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use teloxide::prelude::*;
 
-use crate::database::Database;
-use crate::database::day::Day;
 use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::homework;
+use crate::locale;
+use crate::schedule::command::student;
+use crate::settings;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DayState;
@@ -14,19 +21,33 @@ async fn day(
     _state: DayState,
     cx: TransitionIn<AutoSend<Bot>>,
     ans: String,
-) -> TransitionOut<Dialogue> {
-    if let Ok(day) = Day::from_str(ans.as_ref()) {
-        let msg = format!(
-            "{}",
-            Database::global()
-                .get_schedule_by_day(day, cx.chat_id())
-                .await
-                .unwrap()
-        );
-        cx.answer(msg).send().await?;
-        next(Dialogue::Day(DayState))
-    } else {
-        cx.answer("Неверно введен день недели").await?;
-        return next(Dialogue::Day(DayState));
+) -> TransitionOut<Dialogue, BotError> {
+    let day = Day::from_str(ans.as_ref()).map_err(|_| BotError::InvalidInput("invalid-day"))?;
+    let user_id = student(cx.chat_id()).await?;
+    let language = locale::language(cx.update.from()).await;
+    // The next date on that day, today included, as the homework dates are; the lessons are
+    // those of its week.
+    let today = Local::now().date_naive();
+    let days = (day as i64 - Day::from(today.weekday()) as i64).rem_euclid(7);
+    let date = today + Duration::days(days);
+    let repository = repository::global();
+    let mut week = repository.get_distribution_week().await?;
+    if date.weekday().num_days_from_monday() < today.weekday().num_days_from_monday() {
+        week = week.next();
     }
+    let lessons = repository
+        .get_week_schedule(user_id, week)
+        .await?
+        .0
+        .remove(&day)
+        .unwrap_or(Lessons(vec![]));
+    homework::reply(
+        &cx,
+        user_id,
+        lessons,
+        date,
+        settings::style(user_id, language, None).await?,
+    )
+    .await?;
+    next(Dialogue::Day(DayState))
 }
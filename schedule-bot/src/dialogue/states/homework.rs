@@ -0,0 +1,173 @@
+use chrono::NaiveDate;
+use schedule_core::database::homework::HomeworkFile;
+use schedule_core::locale::Language;
+use schedule_core::repository;
+use serde::{Deserialize, Serialize};
+use teloxide::prelude::*;
+use teloxide::types::{KeyboardButton, KeyboardMarkup, KeyboardRemove};
+
+use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::homework::{self, Target};
+use crate::locale;
+
+/// Waiting for one of the subjects on the keyboard.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HomeworkSubjectState {
+    pub targets: Vec<Target>,
+}
+
+/// Waiting for one of the dates of the subject's lessons on the keyboard.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HomeworkDateState {
+    pub target: Target,
+    pub dates: Vec<NaiveDate>,
+}
+
+/// Waiting for the text of the homework, possibly as the caption of a file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HomeworkTextState {
+    pub target: Target,
+    pub date: NaiveDate,
+}
+
+/// Waiting for files to attach until the homework is saved.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HomeworkFilesState {
+    pub target: Target,
+    pub date: NaiveDate,
+    pub text: String,
+    pub files: Vec<HomeworkFile>,
+}
+
+#[teloxide(subtransition)]
+async fn homework_subject(
+    state: HomeworkSubjectState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    let target = state
+        .targets
+        .into_iter()
+        .find(|target| target.label == ans)
+        .ok_or(BotError::InvalidInput("homework-unknown-subject"))?;
+    homework::ask_date(&cx, target, language).await
+}
+
+#[teloxide(subtransition)]
+async fn homework_date(
+    state: HomeworkDateState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    let date = state
+        .dates
+        .into_iter()
+        .find(|date| homework::date_label(*date, language) == ans)
+        .ok_or(BotError::InvalidInput("homework-unknown-date"))?;
+    homework::ask_text(&cx, state.target, date, language).await
+}
+
+#[teloxide(subtransition)]
+async fn homework_text(
+    state: HomeworkTextState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    if ans.trim().is_empty() {
+        return Err(BotError::InvalidInput("homework-text-usage"));
+    }
+    let files = homework::attachment(&cx.update).into_iter().collect();
+    ask_files(
+        &cx,
+        HomeworkFilesState {
+            target: state.target,
+            date: state.date,
+            text: ans,
+            files,
+        },
+        language,
+    )
+    .await
+}
+
+#[teloxide(subtransition)]
+async fn homework_files(
+    mut state: HomeworkFilesState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    if let Some(file) = homework::attachment(&cx.update) {
+        state.files.push(file);
+        return ask_files(&cx, state, language).await;
+    }
+    if ans == locale::text(language, "homework-cancel") {
+        cx.answer(locale::text(language, "cancelled"))
+            .reply_markup(KeyboardRemove::new())
+            .send()
+            .await?;
+        return next(Dialogue::default());
+    }
+    if ans != locale::text(language, "homework-save") {
+        return Err(BotError::InvalidInput("homework-files-usage"));
+    }
+    // The roles may have changed since the subject was chosen.
+    let user_id = cx.chat_id();
+    let target = state.target;
+    if !homework::targets(user_id)
+        .await?
+        .iter()
+        .any(|other| other.subject == target.subject && other.group == target.group)
+    {
+        return Err(BotError::Forbidden);
+    }
+    repository::global()
+        .add_homework(
+            user_id,
+            target.subject,
+            target.group,
+            state.date,
+            state.text,
+            state.files,
+        )
+        .await?;
+    cx.answer(locale::format(
+        language,
+        "homework-saved",
+        vec![
+            ("subject", target.label.as_str().into()),
+            ("date", homework::date_label(state.date, language).into()),
+        ],
+    ))
+    .reply_markup(KeyboardRemove::new())
+    .send()
+    .await?;
+    next(Dialogue::default())
+}
+
+/// Offers to attach more files or to save the homework.
+async fn ask_files(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    state: HomeworkFilesState,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    let keyboard = KeyboardMarkup::default()
+        .append_row(vec![
+            KeyboardButton::new(locale::text(language, "homework-save")),
+            KeyboardButton::new(locale::text(language, "homework-cancel")),
+        ])
+        .resize_keyboard(true);
+    cx.answer(locale::format(
+        language,
+        "homework-files",
+        vec![("count", state.files.len().into())],
+    ))
+    .reply_markup(keyboard)
+    .send()
+    .await?;
+    next(Dialogue::HomeworkFiles(state))
+}
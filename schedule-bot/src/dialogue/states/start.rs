@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use teloxide::prelude::*;
 
 use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::locale;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StartState;
@@ -11,7 +13,8 @@ async fn start(
     _state: StartState,
     cx: TransitionIn<AutoSend<Bot>>,
     _ans: String,
-) -> TransitionOut<Dialogue> {
-    cx.answer("Неизвестная комманда, введите /help").await?;
+) -> TransitionOut<Dialogue, BotError> {
+    let language = locale::language(cx.update.from()).await;
+    cx.answer(locale::text(language, "unknown-command")).await?;
     next(Dialogue::Start(StartState))
 }
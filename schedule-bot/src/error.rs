@@ -0,0 +1,87 @@
+use schedule_core::locale::Language;
+use std::fmt;
+use teloxide::prelude::*;
+use teloxide::RequestError;
+
+use crate::locale;
+
+/// Errors of the message handlers. They are reported to the user by [`report`] and never stop
+/// the dispatcher.
+#[derive(Debug)]
+pub enum BotError {
+    /// The database or the dialogue storage failed.
+    Database(anyhow::Error),
+    /// The user is not registered as a student.
+    NotRegistered,
+    /// The command needs a role the user doesn't have.
+    Forbidden,
+    /// The message can't be understood; the id names the message telling what is expected.
+    InvalidInput(&'static str),
+    /// Telegram did not accept a request of the bot.
+    Telegram(RequestError),
+    /// The timetable could not be drawn as an image or a PDF.
+    Render(anyhow::Error),
+}
+
+impl BotError {
+    /// Message shown to the user.
+    pub fn reply(&self, language: Language) -> String {
+        let id = match self {
+            BotError::Database(_) => "error-database",
+            BotError::NotRegistered => "error-not-registered",
+            BotError::Forbidden => "error-forbidden",
+            BotError::InvalidInput(id) => id,
+            BotError::Telegram(_) => "error-telegram",
+            BotError::Render(_) => "error-render",
+        };
+        locale::text(language, id)
+    }
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BotError::Database(error) => write!(f, "database error: {:#}", error),
+            BotError::NotRegistered => write!(f, "user is not registered"),
+            BotError::Forbidden => write!(f, "user lacks the role"),
+            BotError::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            BotError::Telegram(error) => write!(f, "Telegram API error: {}", error),
+            BotError::Render(error) => write!(f, "rendering error: {:#}", error),
+        }
+    }
+}
+
+impl std::error::Error for BotError {}
+
+impl From<anyhow::Error> for BotError {
+    fn from(error: anyhow::Error) -> Self {
+        BotError::Database(error)
+    }
+}
+
+impl From<RequestError> for BotError {
+    fn from(error: RequestError) -> Self {
+        BotError::Telegram(error)
+    }
+}
+
+/// Logs the error with the chat and the message it happened on and tells the user about it.
+pub async fn report(
+    bot: &AutoSend<Bot>,
+    chat_id: i64,
+    text: Option<&str>,
+    language: Language,
+    error: &BotError,
+) {
+    match error {
+        BotError::Database(_) | BotError::Telegram(_) | BotError::Render(_) => {
+            log::error!("Chat {}, message {:?}: {}", chat_id, text, error)
+        }
+        BotError::NotRegistered | BotError::Forbidden | BotError::InvalidInput(_) => {
+            log::debug!("Chat {}, message {:?}: {}", chat_id, text, error)
+        }
+    }
+    if let Err(error) = bot.send_message(chat_id, error.reply(language)).await {
+        log::error!("Chat {}: failed to report an error: {}", chat_id, error);
+    }
+}
@@ -0,0 +1,203 @@
+//! `/homework`: group leaders give their group homework for a lesson and teachers the groups
+//! they teach, with files attached. `/today` and `/day` show it under the lesson it is due at.
+
+use chrono::{Datelike, Local, NaiveDate};
+use schedule_core::database::day::Day;
+use schedule_core::database::homework::{Attachment, HomeworkFile, LessonsHomework};
+use schedule_core::database::lesson::Lessons;
+use schedule_core::database::role::Role;
+use schedule_core::locale::Language;
+use schedule_core::render::Style;
+use schedule_core::repository;
+use serde::{Deserialize, Serialize};
+use teloxide::dispatching::UpdateWithCx;
+use teloxide::prelude::*;
+use teloxide::types::{InputFile, KeyboardButton, KeyboardMarkup, KeyboardRemove};
+
+use crate::access;
+use crate::dialogue::states::{HomeworkDateState, HomeworkSubjectState, HomeworkTextState};
+use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::locale;
+use crate::schedule::reply;
+
+/// A subject of a group the user may give homework for, labelled as on the keyboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    pub label: String,
+    pub subject: i32,
+    pub group: i32,
+}
+
+/// Subjects of the group of a group leader, then those a teacher teaches.
+pub async fn targets(user_id: i64) -> Result<Vec<Target>, BotError> {
+    let roles = access::roles(user_id).await?;
+    let repository = repository::global();
+    let mut targets = vec![];
+    if access::allows(&roles, Role::GroupLeader) {
+        if let Some(group) = repository.get_student_group(user_id).await? {
+            for subject in repository.get_group_subjects(group.id).await? {
+                targets.push(Target {
+                    label: format!("📚 {}", subject.name),
+                    subject: subject.id,
+                    group: group.id,
+                });
+            }
+        }
+    }
+    if access::allows(&roles, Role::Teacher) {
+        if let Some(teacher) = repository.get_linked_teacher(user_id).await? {
+            for taught in repository.get_taught_groups(teacher.id).await? {
+                if !targets
+                    .iter()
+                    .any(|target| target.subject == taught.subject && target.group == taught.group)
+                {
+                    targets.push(Target {
+                        label: format!("📚 {} · {}", taught.subject_name, taught.group_name),
+                        subject: taught.subject,
+                        group: taught.group,
+                    });
+                }
+            }
+        }
+    }
+    Ok(targets)
+}
+
+/// Asks which subject the homework is for, unless there is a single choice.
+pub async fn start(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    let roles = access::roles(user_id).await?;
+    if !access::allows(&roles, Role::GroupLeader) && !access::allows(&roles, Role::Teacher) {
+        return Err(BotError::Forbidden);
+    }
+    let targets = targets(user_id).await?;
+    match targets.as_slice() {
+        [] => Err(BotError::InvalidInput("homework-no-subjects")),
+        [target] => ask_date(cx, target.clone(), language).await,
+        _ => {
+            let keyboard = targets.iter().fold(
+                KeyboardMarkup::default().resize_keyboard(true),
+                |keyboard, target| keyboard.append_row(vec![KeyboardButton::new(&target.label)]),
+            );
+            cx.answer(locale::text(language, "homework-choose"))
+                .reply_markup(keyboard)
+                .send()
+                .await?;
+            next(Dialogue::HomeworkSubject(HomeworkSubjectState { targets }))
+        }
+    }
+}
+
+/// Asks which of the next lessons of the subject the homework is due at.
+pub async fn ask_date(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    target: Target,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    let dates = repository::global()
+        .get_lesson_dates(target.group, target.subject)
+        .await?;
+    if dates.is_empty() {
+        return Err(BotError::InvalidInput("homework-no-dates"));
+    }
+    let keyboard = dates.iter().fold(
+        KeyboardMarkup::default().resize_keyboard(true),
+        |keyboard, date| {
+            keyboard.append_row(vec![KeyboardButton::new(date_label(*date, language))])
+        },
+    );
+    cx.answer(locale::text(language, "homework-choose-date"))
+        .reply_markup(keyboard)
+        .send()
+        .await?;
+    next(Dialogue::HomeworkDate(HomeworkDateState { target, dates }))
+}
+
+pub async fn ask_text(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    target: Target,
+    date: NaiveDate,
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
+    cx.answer(locale::format(
+        language,
+        "homework-text",
+        vec![
+            ("subject", target.label.as_str().into()),
+            ("date", date_label(date, language).into()),
+        ],
+    ))
+    .reply_markup(KeyboardRemove::new())
+    .send()
+    .await?;
+    next(Dialogue::HomeworkText(HomeworkTextState { target, date }))
+}
+
+/// A date on the keyboard, e.g. `Пн 14.03`.
+pub fn date_label(date: NaiveDate, language: Language) -> String {
+    format!(
+        "{} {}",
+        Day::from(date.weekday()).short_name(language),
+        date.format("%d.%m")
+    )
+}
+
+/// The document or the largest size of the photo in the message.
+pub fn attachment(message: &Message) -> Option<HomeworkFile> {
+    if let Some(document) = message.document() {
+        return Some(HomeworkFile {
+            file_id: document.file_id.clone(),
+            kind: Attachment::Document,
+        });
+    }
+    message
+        .photo()
+        .and_then(|sizes| sizes.last())
+        .map(|photo| HomeworkFile {
+            file_id: photo.file_id.clone(),
+            kind: Attachment::Photo,
+        })
+}
+
+/// Sends the lessons of the date with the homework due at them, if the date hasn't passed, and
+/// then the files attached to it.
+pub async fn reply(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    lessons: Lessons,
+    date: NaiveDate,
+    style: Style,
+) -> Result<(), BotError> {
+    let mut homework = if date >= Local::now().date_naive() {
+        repository::global().get_homework(user_id, date).await?
+    } else {
+        vec![]
+    };
+    homework.retain(|homework| {
+        lessons
+            .0
+            .iter()
+            .any(|lesson| lesson.subject_name == homework.subject_name)
+    });
+    let lessons = LessonsHomework { lessons, homework };
+    reply::html(cx, &lessons, style).await?;
+    for homework in &lessons.homework {
+        let caption = format!("📝 {}", homework.subject_name);
+        for file in &homework.files {
+            let input = InputFile::file_id(file.file_id.clone());
+            match file.kind {
+                Attachment::Document => {
+                    cx.answer_document(input).caption(&caption).send().await?;
+                }
+                Attachment::Photo => {
+                    cx.answer_photo(input).caption(&caption).send().await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Texts of the bot in the user's language. The bot's own messages are in `locales/`, next to
+//! the messages of `schedule_core`.
+
+use once_cell::sync::Lazy;
+use schedule_core::locale::{FluentValue, Language, Messages};
+use schedule_core::repository;
+use teloxide::types::User;
+
+static MESSAGES: Lazy<Messages> = Lazy::new(|| {
+    Messages::new(&[
+        (Language::Uk, include_str!("../locales/uk.ftl")),
+        (Language::Ru, include_str!("../locales/ru.ftl")),
+        (Language::En, include_str!("../locales/en.ftl")),
+    ])
+});
+
+pub fn text(language: Language, id: &str) -> String {
+    MESSAGES.get(language, id)
+}
+
+pub fn format<'a, I>(language: Language, id: &str, args: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, FluentValue<'a>)>,
+{
+    MESSAGES.format(language, id, args)
+}
+
+/// Language chosen with `/lang`, otherwise the one of the user's Telegram client.
+pub async fn language(user: Option<&User>) -> Language {
+    let user = match user {
+        Some(user) => user,
+        None => return Language::default(),
+    };
+    match repository::global().get_language(user.id).await {
+        Ok(Some(language)) => language,
+        Ok(None) => client_language(user),
+        Err(error) => {
+            log::error!("Failed to get language of user {}: {:#}", user.id, error);
+            client_language(user)
+        }
+    }
+}
+
+/// Language chosen by a user the bot writes to unprompted, without their Telegram client at
+/// hand.
+pub async fn chosen_language(user_id: i64) -> Language {
+    match repository::global().get_language(user_id).await {
+        Ok(language) => language.unwrap_or_default(),
+        Err(error) => {
+            log::error!("Failed to get language of user {}: {:#}", user_id, error);
+            Language::default()
+        }
+    }
+}
+
+/// Language of the user's Telegram client, if the bot speaks it.
+pub fn client_language(user: &User) -> Language {
+    user.language_code
+        .as_deref()
+        .and_then(Language::from_code)
+        .unwrap_or_default()
+}
@@ -1,48 +1,97 @@
 use anyhow::Result;
 use config::Config;
-use std::sync::Arc;
+use schedule_core::database::Database;
+use schedule_core::locale::Language;
+use schedule_core::repository::{self, MemoryRepository};
+use std::path::Path;
+use teloxide::dispatching::dialogue::serializer::Json;
 use teloxide::prelude::*;
-use teloxide::utils::command::BotCommand;
+use teloxide::utils::command::{BotCommand, ParseError};
 use teloxide::Bot;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use crate::database::Database;
 use crate::dialogue::states::StartState;
 use crate::dialogue::Dialogue;
-use crate::schedule::command::Command;
+use crate::error::BotError;
+use crate::schedule::command::{self, Command};
+use crate::storage::DialogueStorage;
 
+mod access;
+mod announce;
 mod config;
-mod database;
 mod dialogue;
+mod error;
+mod homework;
+mod locale;
+mod queue;
+mod reminder;
 mod schedule;
+mod settings;
+mod storage;
 
 #[tokio::main]
 async fn main() {
     teloxide::enable_logging!();
     log::info!("Starting schedule_bot...");
 
-    let config = Config::new().expect("Failed to initialize config!");
-    database::initialize(&config).await.unwrap();
+    config::initialize().expect("Failed to initialize config!");
+    let config = Config::global();
+    initialize_repository(config)
+        .await
+        .expect("Failed to initialize repository!");
 
-    let bot = Bot::new(config.token).auto_send();
+    let bot = Bot::new(&config.token).auto_send();
     run(bot).await.expect("Something get wrong with main task");
 }
 
+/// `database_url` is either a PostgreSQL URL or `memory:<file>` with a timetable export to serve
+/// from memory, for running the bot without a database server.
+async fn initialize_repository(config: &Config) -> Result<()> {
+    match config.database_url.strip_prefix("memory:") {
+        Some(path) => {
+            let repository = MemoryRepository::from_file(Path::new(path))?;
+            repository::initialize(Box::leak(Box::new(repository)))
+        }
+        None => {
+            schedule_core::database::initialize(&config.database_url, config.max_connections)
+                .await?;
+            repository::initialize(Database::global())
+        }
+    }
+}
+
 type In = DialogueWithCx<AutoSend<Bot>, Message, Dialogue, anyhow::Error>;
 
 async fn run(bot: AutoSend<Bot>) -> Result<()> {
-    teloxide::enable_logging!();
-    log::info!("Starting queue_bot...");
-
+    reminder::spawn(bot.clone());
     Dispatcher::new(bot)
         .messages_handler(DialogueDispatcher::with_storage(
             |DialogueWithCx { cx, dialogue }: In| async move {
-                let dialogue = dialogue.expect("std::convert::Infallible");
-                handle_message(cx, dialogue)
-                    .await
-                    .expect("Something wrong with the bot!")
+                let bot = cx.requester.clone();
+                let chat_id = cx.chat_id();
+                let text = cx.update.text().map(ToOwned::to_owned);
+                let language = locale::language(cx.update.from()).await;
+                let dialogue = match dialogue {
+                    Ok(dialogue) => dialogue,
+                    Err(error) => {
+                        let error = BotError::Database(error);
+                        error::report(&bot, chat_id, text.as_deref(), language, &error).await;
+                        Dialogue::default()
+                    }
+                };
+                match handle_message(cx, dialogue.clone(), language).await {
+                    Ok(stage) => stage,
+                    Err(error) => {
+                        error::report(&bot, chat_id, text.as_deref(), language, &error).await;
+                        DialogueStage::Next(dialogue)
+                    }
+                }
             },
-            Arc::new(Database::global()),
+            DialogueStorage::new(Json),
         ))
+        .callback_queries_handler(|rx: DispatcherHandlerRx<AutoSend<Bot>, CallbackQuery>| {
+            UnboundedReceiverStream::new(rx).for_each_concurrent(None, settings::callback)
+        })
         .dispatch()
         .await;
     Ok(())
@@ -51,10 +100,15 @@ async fn run(bot: AutoSend<Bot>) -> Result<()> {
 async fn handle_message(
     cx: UpdateWithCx<AutoSend<Bot>, Message>,
     dialogue: Dialogue,
-) -> TransitionOut<Dialogue> {
+    language: Language,
+) -> TransitionOut<Dialogue, BotError> {
     match cx.update.text().map(ToOwned::to_owned) {
+        None if dialogue.accepts_files() => {
+            let caption = cx.update.caption().unwrap_or_default().to_owned();
+            dialogue.react(cx, caption).await
+        }
         None => {
-            cx.answer("Отправьте текстовое сообщение").await?;
+            cx.answer(locale::text(language, "send-text")).await?;
             next(dialogue)
         }
         Some(ans) => match ans.as_str() {
@@ -62,12 +116,15 @@ async fn handle_message(
                 if !dialogue.is_start() {
                     next(Dialogue::Start(StartState))
                 } else {
-                    (Command::Help).answer(&cx, dialogue).await
+                    (Command::Help).answer(&cx, dialogue, language).await
                 }
             }
             ans => match Command::parse(ans, "") {
-                Ok(command) => command.answer(&cx, dialogue).await,
-                Err(_) => dialogue.react(cx, ans.to_string()).await,
+                Ok(command) => command.answer(&cx, dialogue, language).await,
+                Err(ParseError::UnknownCommand(_)) | Err(ParseError::WrongBotName(_)) => {
+                    dialogue.react(cx, ans.to_string()).await
+                }
+                Err(_) => Err(BotError::InvalidInput(command::usage(ans))),
             },
         },
     }
@@ -0,0 +1,303 @@
+//! Queues for defending laboratory works. Students join the queue of their nearest laboratory
+//! work with `/queue`, its teacher calls them with `/next` and marks them done with `/done`.
+//! Students hear when their turn comes and when they move up in the queue.
+
+use schedule_core::database::queue::{LabLesson, QueueEntry};
+use schedule_core::locale::Language;
+use schedule_core::repository;
+use std::str::FromStr;
+use teloxide::dispatching::UpdateWithCx;
+use teloxide::prelude::*;
+use teloxide::types::User;
+
+use crate::access;
+use crate::announce;
+use crate::error::BotError;
+use crate::locale;
+use crate::schedule::command::student;
+
+/// What `/queue` does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Join,
+    Leave,
+    List,
+}
+
+impl FromStr for Action {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "join" => Ok(Action::Join),
+            "leave" => Ok(Action::Leave),
+            "list" => Ok(Action::List),
+            _ => Err("Expected join, leave or list"),
+        }
+    }
+}
+
+/// A message to a student about their place in a queue.
+enum Notice {
+    YourTurn,
+    /// Position among the waiting students, from 1.
+    Position(usize),
+}
+
+pub async fn answer(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user: &User,
+    action: Action,
+    language: Language,
+) -> Result<(), BotError> {
+    match action {
+        Action::Join => {
+            let user_id = student(user.id).await?;
+            let labs = repository::global().get_lab_lessons(user_id).await?;
+            let lab = labs
+                .first()
+                .ok_or(BotError::InvalidInput("queue-no-labs"))?;
+            let joined = repository::global()
+                .join_queue(lab.id, lab.starts_at.date(), user_id, user.full_name())
+                .await?;
+            let queue = repository::global()
+                .get_queue(lab.id, lab.starts_at.date())
+                .await?;
+            let msg = match position(&queue, user_id) {
+                Some(position) => locale::format(
+                    language,
+                    if joined {
+                        "queue-joined"
+                    } else {
+                        "queue-already"
+                    },
+                    vec![("lab", name(lab).into()), ("position", position.into())],
+                ),
+                None => {
+                    locale::format(language, "queue-your-turn", vec![("lab", name(lab).into())])
+                }
+            };
+            cx.answer(msg).send().await?;
+        }
+        Action::Leave => {
+            let user_id = student(user.id).await?;
+            let (lab, before) = joined(user_id)
+                .await?
+                .ok_or(BotError::InvalidInput("queue-not-queued"))?;
+            repository::global()
+                .leave_queue(lab.id, lab.starts_at.date(), user_id)
+                .await?;
+            let msg = locale::format(language, "queue-left", vec![("lab", name(&lab).into())]);
+            cx.answer(msg).send().await?;
+            notify_moved(cx.requester.clone(), &lab, &before).await?;
+        }
+        Action::List => {
+            let lab = if repository::global().is_student(user.id).await? {
+                match joined(user.id).await? {
+                    Some((lab, _)) => lab,
+                    None => nearest_lab(user.id).await?,
+                }
+            } else {
+                nearest_taught_lab(user.id).await?
+            };
+            let queue = repository::global()
+                .get_queue(lab.id, lab.starts_at.date())
+                .await?;
+            cx.answer(list(&lab, &queue, language)).send().await?;
+        }
+    }
+    Ok(())
+}
+
+/// `/next`: calls the first waiting student at the teacher's nearest laboratory work.
+pub async fn call_next(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    language: Language,
+) -> Result<(), BotError> {
+    let lab = nearest_taught_lab(user_id).await?;
+    let before = repository::global()
+        .get_queue(lab.id, lab.starts_at.date())
+        .await?;
+    let called = match repository::global()
+        .call_next(lab.id, lab.starts_at.date())
+        .await?
+    {
+        Some(called) => called,
+        None => {
+            cx.answer(locale::text(language, "queue-empty"))
+                .send()
+                .await?;
+            return Ok(());
+        }
+    };
+    let name = before
+        .iter()
+        .find(|entry| entry.telegram_id == called)
+        .map(|entry| entry.name.as_str())
+        .unwrap_or_default();
+    let msg = locale::format(
+        language,
+        "queue-called-student",
+        vec![("name", name.into())],
+    );
+    cx.answer(msg).send().await?;
+    notify_moved(cx.requester.clone(), &lab, &before).await
+}
+
+/// `/done`: takes the called students out of the queue of the teacher's nearest laboratory work.
+pub async fn finish(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    language: Language,
+) -> Result<(), BotError> {
+    let lab = nearest_taught_lab(user_id).await?;
+    let queue = repository::global()
+        .get_queue(lab.id, lab.starts_at.date())
+        .await?;
+    let done = repository::global()
+        .finish_called(lab.id, lab.starts_at.date())
+        .await?;
+    if done.is_empty() {
+        return Err(BotError::InvalidInput("queue-none-called"));
+    }
+    let names = queue
+        .iter()
+        .filter(|entry| done.contains(&entry.telegram_id))
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let msg = locale::format(language, "queue-done", vec![("names", names.into())]);
+    cx.answer(msg).send().await?;
+    Ok(())
+}
+
+async fn nearest_lab(user_id: i64) -> Result<LabLesson, BotError> {
+    repository::global()
+        .get_lab_lessons(user_id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(BotError::InvalidInput("queue-no-labs"))
+}
+
+async fn nearest_taught_lab(user_id: i64) -> Result<LabLesson, BotError> {
+    let teacher = access::linked_teacher(user_id).await?;
+    repository::global()
+        .get_taught_lab_lessons(teacher.id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(BotError::InvalidInput("queue-no-labs"))
+}
+
+/// The student's soonest laboratory work they are queued for, with its queue.
+async fn joined(user_id: i64) -> Result<Option<(LabLesson, Vec<QueueEntry>)>, BotError> {
+    for lab in repository::global().get_lab_lessons(user_id).await? {
+        let queue = repository::global()
+            .get_queue(lab.id, lab.starts_at.date())
+            .await?;
+        if queue.iter().any(|entry| entry.telegram_id == user_id) {
+            return Ok(Some((lab, queue)));
+        }
+    }
+    Ok(None)
+}
+
+/// Position of a waiting student, from 1; none for called students and those not in the queue.
+fn position(queue: &[QueueEntry], user_id: i64) -> Option<usize> {
+    queue
+        .iter()
+        .filter(|entry| !entry.called)
+        .position(|entry| entry.telegram_id == user_id)
+        .map(|position| position + 1)
+}
+
+/// Subject and start of the laboratory work, e.g. `Фізика (14.03 14:15)`.
+fn name(lab: &LabLesson) -> String {
+    format!(
+        "{} ({})",
+        lab.subject_name,
+        lab.starts_at.format("%d.%m %H:%M")
+    )
+}
+
+fn list(lab: &LabLesson, queue: &[QueueEntry], language: Language) -> String {
+    let mut lines = vec![if lab.room.is_empty() {
+        format!("🔬 {}", name(lab))
+    } else {
+        format!("🔬 {} · {}", name(lab), lab.room)
+    }];
+    let called: Vec<&str> = queue
+        .iter()
+        .filter(|entry| entry.called)
+        .map(|entry| entry.name.as_str())
+        .collect();
+    if !called.is_empty() {
+        lines.push(locale::format(
+            language,
+            "queue-called",
+            vec![("names", called.join(", ").into())],
+        ));
+    }
+    let waiting: Vec<&QueueEntry> = queue.iter().filter(|entry| !entry.called).collect();
+    if waiting.is_empty() {
+        lines.push(locale::text(language, "queue-empty"));
+    }
+    for (i, entry) in waiting.iter().enumerate() {
+        lines.push(format!("{}. {}", i + 1, entry.name));
+    }
+    lines.join("\n")
+}
+
+/// Tells the students whose place changed since `before` where they are now.
+async fn notify_moved(
+    bot: AutoSend<Bot>,
+    lab: &LabLesson,
+    before: &[QueueEntry],
+) -> Result<(), BotError> {
+    let after = repository::global()
+        .get_queue(lab.id, lab.starts_at.date())
+        .await?;
+    let notices: Vec<(i64, Notice)> = after
+        .iter()
+        .filter_map(|entry| {
+            let now = position(&after, entry.telegram_id);
+            if now == position(before, entry.telegram_id) {
+                return None;
+            }
+            Some(match now {
+                Some(position) => (entry.telegram_id, Notice::Position(position)),
+                None => (entry.telegram_id, Notice::YourTurn),
+            })
+        })
+        .collect();
+    let lab = name(lab);
+    tokio::spawn(async move {
+        for (chat_id, notice) in notices {
+            let language = locale::chosen_language(chat_id).await;
+            let msg = match notice {
+                Notice::YourTurn => locale::format(
+                    language,
+                    "queue-your-turn",
+                    vec![("lab", lab.as_str().into())],
+                ),
+                Notice::Position(1) => locale::format(
+                    language,
+                    "queue-next-up",
+                    vec![("lab", lab.as_str().into())],
+                ),
+                Notice::Position(position) => locale::format(
+                    language,
+                    "queue-position",
+                    vec![("lab", lab.as_str().into()), ("position", position.into())],
+                ),
+            };
+            if let Err(error) = announce::send(&bot, chat_id, &msg).await {
+                log::warn!("Chat {}: failed to notify of the queue: {}", chat_id, error);
+            }
+            tokio::time::sleep(announce::INTERVAL).await;
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Reminders before lessons and the digest of the next day's lessons, sent to the students who
+//! chose them with `/settings`. Subscriptions are checked at the start of every minute.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, NaiveDateTime, Timelike, Weekday};
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::Lessons;
+use schedule_core::database::settings::UserSettings;
+use schedule_core::locale::Localize;
+use schedule_core::render::{self, Html, Style};
+use schedule_core::repository;
+use teloxide::prelude::*;
+use teloxide::types::ParseMode;
+
+use crate::announce;
+use crate::locale;
+
+/// Starts sending reminders and digests in the background.
+pub fn spawn(bot: AutoSend<Bot>) {
+    tokio::spawn(async move {
+        loop {
+            let second = Local::now().second() as u64;
+            tokio::time::sleep(std::time::Duration::from_secs(60 - second.min(59))).await;
+            let now = Local::now().naive_local();
+            let minute = now - Duration::seconds(now.second().into());
+            let minute = minute.with_nanosecond(0).unwrap_or(minute);
+            if let Err(error) = send_due(&bot, minute).await {
+                log::error!("Failed to send reminders: {:#}", error);
+            }
+        }
+    });
+}
+
+/// Sends what is due at `now`, a time without seconds.
+async fn send_due(bot: &AutoSend<Bot>, now: NaiveDateTime) -> Result<()> {
+    for (user_id, settings) in repository::global().get_subscribers().await? {
+        if let Err(error) = send_user(bot, user_id, &settings, now).await {
+            log::warn!("Chat {}: failed to send a reminder: {:#}", user_id, error);
+        }
+    }
+    Ok(())
+}
+
+async fn send_user(
+    bot: &AutoSend<Bot>,
+    user_id: i64,
+    settings: &UserSettings,
+    now: NaiveDateTime,
+) -> Result<()> {
+    let repository = repository::global();
+    let language = settings.language.unwrap_or_default();
+    let style = Style::new(language, settings);
+
+    if let Some(minutes) = settings.reminder_minutes {
+        let start = now + Duration::minutes(minutes.into());
+        if start.date() == now.date() {
+            let Lessons(lessons) = repository.get_today_schedule(user_id).await?;
+            let starting: Vec<_> = lessons
+                .into_iter()
+                .filter(|lesson| lesson.time == start.time())
+                .collect();
+            if !starting.is_empty() {
+                let title = locale::format(language, "reminder", vec![("minutes", minutes.into())]);
+                send(bot, user_id, title, &Lessons(starting), style).await?;
+            }
+        }
+    }
+
+    if settings.digest_time == Some(now.time()) {
+        let tomorrow = now.date() + Duration::days(1);
+        let mut week = repository.get_distribution_week().await?;
+        if tomorrow.weekday() == Weekday::Mon {
+            week = week.next();
+        }
+        let day = Day::from(tomorrow.weekday());
+        let mut lessons = repository.get_week_schedule(user_id, week).await?.0;
+        if let Some(lessons) = lessons.remove(&day).filter(|lessons| !lessons.0.is_empty()) {
+            let title = locale::format(
+                language,
+                "digest",
+                vec![("day", day.localize(language).into())],
+            );
+            send(bot, user_id, title, &lessons, style).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn send(
+    bot: &AutoSend<Bot>,
+    chat_id: i64,
+    title: String,
+    lessons: &Lessons,
+    style: Style,
+) -> Result<()> {
+    let mut parts = vec![format!("<b>{}</b>", render::escape(&title))];
+    parts.extend(lessons.html_parts(style));
+    for message in render::split(parts, render::MESSAGE_LIMIT) {
+        bot.send_message(chat_id, message)
+            .parse_mode(ParseMode::Html)
+            .await?;
+        tokio::time::sleep(announce::INTERVAL).await;
+    }
+    Ok(())
+}
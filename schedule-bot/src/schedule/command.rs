@@ -1,38 +1,66 @@
-use crate::database::Database;
-use crate::database::week::WeekType;
+use crate::access;
+use crate::announce;
+use crate::config::Config;
 use crate::dialogue::states::DayState;
 use crate::dialogue::Dialogue;
+use crate::error::BotError;
+use crate::homework;
+use crate::locale;
+use crate::queue;
+use crate::schedule::reply;
+use crate::settings;
+use chrono::Local;
+use schedule_core::database::day::Day;
+use schedule_core::database::role::Role;
+use schedule_core::database::settings::LessonFormat;
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::{Language, Localize};
+use schedule_core::render::{image, pdf};
+use schedule_core::repository;
+use std::num::ParseIntError;
 use teloxide::dispatching::UpdateWithCx;
 use teloxide::prelude::*;
-use teloxide::types::{KeyboardButton, KeyboardMarkup, MessageKind};
-use teloxide::utils::command::BotCommand;
+use teloxide::types::{InputFile, KeyboardButton, KeyboardMarkup, KeyboardRemove, MessageKind};
+use teloxide::utils::command::{BotCommand, ParseError};
 
+// The descriptions of the commands are the `help` message of `locales/`, followed by the
+// `help-<role>` sections of the user's roles.
 #[derive(BotCommand)]
-#[command(
-    rename = "lowercase",
-    description = "Список поддерживаемых комманд:",
-    parse_with = "split"
-)]
+#[command(rename = "lowercase", parse_with = "split")]
 pub enum Command {
-    #[command(description = "показать это сообщение.")]
     Help,
-    #[command(description = "показать расписание на сегодня.")]
-    Today,
-    #[command(description = "показать расписание на выбранный день.")]
+    #[command(parse_with = "optional_format")]
+    Today(Option<LessonFormat>),
     Day,
-    #[command(description = "показать текущую и последующую пары.")]
-    Current,
-    #[command(
-        description = "показать расписание на выбранную неделю. (Введите 1 или 2 после команды)."
-    )]
-    Week(WeekType),
-    #[command(description = "показать список дедлайнов.[WIP]")]
+    #[command(parse_with = "optional_format")]
+    Current(Option<LessonFormat>),
+    #[command(parse_with = "week")]
+    Week(WeekType, Option<LessonFormat>),
     Deadline,
-    #[command(
-        rename = "current_week",
-        description = "показать какая неделя по счету."
-    )]
+    #[command(rename = "current_week")]
     CurrentWeek,
+    Calendar,
+    Lang(Language),
+    #[command(rename = "week_image", parse_with = "optional_week")]
+    WeekImage(Option<WeekType>, Option<LessonFormat>),
+    #[command(parse_with = "optional_format")]
+    Pdf(Option<LessonFormat>),
+    Settings,
+    #[command(parse_with = "optional_user")]
+    Roles(Option<i64>),
+    Grant(i64, Role),
+    Revoke(i64, Role),
+    Announce,
+    Notify,
+    Announcements,
+    #[command(rename = "link_teacher")]
+    LinkTeacher(i64, i32),
+    #[command(parse_with = "queue_action")]
+    Queue(queue::Action),
+    Next,
+    Done,
+    Homework,
+    Cancel,
 }
 
 impl Command {
@@ -40,73 +68,344 @@ impl Command {
         &self,
         cx: &UpdateWithCx<AutoSend<Bot>, Message>,
         dialogue: Dialogue,
-    ) -> TransitionOut<Dialogue> {
+        language: Language,
+    ) -> TransitionOut<Dialogue, BotError> {
         if let MessageKind::Common(msg) = &cx.update.kind {
             if let Some(user) = &msg.from {
+                if let Some(role) = self.role() {
+                    access::require(user.id, role).await?;
+                }
                 match self {
-                    Command::Help => cx.answer(Command::descriptions()).send().await?,
+                    Command::Help => {
+                        cx.answer(help(user.id, language).await?).send().await?;
+                    }
                     Command::Day => {
-                        cx.answer("Выберите день недели")
-                            .reply_markup(days())
+                        student(user.id).await?;
+                        cx.answer(locale::text(language, "choose-day"))
+                            .reply_markup(days(language))
                             .send()
                             .await?;
                         return next(Dialogue::Day(DayState));
                     }
-                    Command::Today => {
-                        let msg = format!(
-                            "{}",
-                            Database::global()
-                                .get_today_schedule(user.id)
-                                .await
-                                .unwrap()
+                    Command::Today(format) => {
+                        let user_id = student(user.id).await?;
+                        let lessons = repository::global().get_today_schedule(user_id).await?;
+                        let style = settings::style(user_id, language, *format).await?;
+                        let today = Local::now().date_naive();
+                        homework::reply(cx, user_id, lessons, today, style).await?;
+                    }
+                    Command::Current(format) => {
+                        let user_id = student(user.id).await?;
+                        let lessons = repository::global().get_current_schedule(user_id).await?;
+                        let style = settings::style(user_id, language, *format).await?;
+                        reply::html(cx, &lessons, style).await?;
+                    }
+                    Command::Week(week, format) => {
+                        let user_id = student(user.id).await?;
+                        let lessons = repository::global()
+                            .get_week_schedule(user_id, *week)
+                            .await?;
+                        let style = settings::style(user_id, language, *format).await?;
+                        reply::html(cx, &lessons, style).await?;
+                    }
+                    Command::WeekImage(week, format) => {
+                        let user_id = student(user.id).await?;
+                        let week = match week {
+                            Some(week) => *week,
+                            None => repository::global().get_distribution_week().await?,
+                        };
+                        let lessons = repository::global()
+                            .get_week_schedule(user_id, week)
+                            .await?;
+                        let style = settings::style(user_id, language, *format).await?;
+                        let png =
+                            image::week_png(&lessons, week, style).map_err(BotError::Render)?;
+                        cx.answer_photo(InputFile::memory("week.png", png))
+                            .send()
+                            .await?;
+                    }
+                    Command::Pdf(format) => {
+                        let user_id = student(user.id).await?;
+                        let mut weeks = vec![];
+                        for week in WeekType::all() {
+                            let lessons = repository::global()
+                                .get_week_schedule(user_id, week)
+                                .await?;
+                            weeks.push((week, lessons));
+                        }
+                        let style = settings::style(user_id, language, *format).await?;
+                        let pdf = pdf::timetable_pdf(&user.full_name(), &weeks, style)
+                            .map_err(BotError::Render)?;
+                        cx.answer_document(InputFile::memory("timetable.pdf", pdf))
+                            .send()
+                            .await?;
+                    }
+                    Command::CurrentWeek => {
+                        let week = repository::global().get_distribution_week().await?;
+                        let msg = locale::format(
+                            language,
+                            "current-week",
+                            vec![("week", week.localize(language).into())],
                         );
-                        cx.answer(msg).send().await?
-                    }
-                    Command::Current => {
-                        let msg = format!(
-                            "{}",
-                            Database::global()
-                                .get_current_schedule(user.id)
-                                .await
-                                .unwrap()
+                        cx.answer(msg).send().await?;
+                    }
+                    Command::Calendar => {
+                        let token = repository::global()
+                            .get_calendar_token(user.id)
+                            .await?
+                            .ok_or(BotError::NotRegistered)?;
+                        let url = format!(
+                            "{}/ical/{}.ics",
+                            Config::global().web_url.trim_end_matches('/'),
+                            token
                         );
-                        cx.answer(msg).send().await?
-                    }
-                    Command::Week(week) => {
-                        let msg = format!(
-                            "{}",
-                            Database::global()
-                                .get_week_schedule(user.id, week)
-                                .await
-                                .unwrap()
+                        let msg =
+                            locale::format(language, "calendar-link", vec![("url", url.into())]);
+                        cx.answer(msg).send().await?;
+                    }
+                    Command::Lang(language) => {
+                        repository::global()
+                            .set_language(user.id, *language)
+                            .await?;
+                        cx.answer(locale::text(*language, "language-changed"))
+                            .send()
+                            .await?;
+                    }
+                    Command::Settings => settings::show(cx, user.id, language).await?,
+                    Command::Roles(user_id) => {
+                        let user_id = user_id.unwrap_or(user.id);
+                        let roles = access::roles(user_id).await?;
+                        let msg = locale::format(
+                            language,
+                            "roles",
+                            vec![
+                                ("id", user_id.to_string().into()),
+                                ("roles", role_names(&roles, language).into()),
+                            ],
                         );
-                        cx.answer(msg).send().await?
+                        cx.answer(msg).send().await?;
                     }
-                    Command::CurrentWeek => {
-                        let msg = format!(
-                            "Сейчас {} неделя",
-                            Database::global().get_distribution_week().await.unwrap()
+                    Command::Grant(user_id, role) => {
+                        repository::global().grant_role(*user_id, *role).await?;
+                        cx.answer(role_message(language, "role-granted", *user_id, *role))
+                            .send()
+                            .await?;
+                    }
+                    Command::Revoke(user_id, role) => {
+                        let id = if repository::global().revoke_role(*user_id, *role).await? {
+                            "role-revoked"
+                        } else {
+                            "role-not-granted"
+                        };
+                        cx.answer(role_message(language, id, *user_id, *role))
+                            .send()
+                            .await?;
+                    }
+                    Command::Announce => {
+                        let audiences = announce::audiences(user.id, language).await?;
+                        return announce::start(cx, audiences, language).await;
+                    }
+                    Command::Notify => {
+                        let audiences = announce::taught(user.id).await?;
+                        return announce::start(cx, audiences, language).await;
+                    }
+                    Command::Announcements => announce::history(cx, user.id, language).await?,
+                    Command::LinkTeacher(user_id, teacher_id) => {
+                        if !repository::global()
+                            .link_teacher(*user_id, *teacher_id)
+                            .await?
+                        {
+                            return Err(BotError::InvalidInput("unknown-teacher"));
+                        }
+                        repository::global()
+                            .grant_role(*user_id, Role::Teacher)
+                            .await?;
+                        let msg = locale::format(
+                            language,
+                            "teacher-linked",
+                            vec![
+                                ("id", user_id.to_string().into()),
+                                ("teacher", teacher_id.to_string().into()),
+                            ],
                         );
-                        cx.answer(msg).send().await?
+                        cx.answer(msg).send().await?;
                     }
-                    _ => cx.answer(Command::descriptions()).send().await?,
-                };
+                    Command::Queue(action) => queue::answer(cx, user, *action, language).await?,
+                    Command::Next => queue::call_next(cx, user.id, language).await?,
+                    Command::Done => queue::finish(cx, user.id, language).await?,
+                    Command::Homework => return homework::start(cx, user.id, language).await,
+                    Command::Cancel => {
+                        cx.answer(locale::text(language, "cancelled"))
+                            .reply_markup(KeyboardRemove::new())
+                            .send()
+                            .await?;
+                        return next(Dialogue::default());
+                    }
+                    _ => {
+                        cx.answer(help(user.id, language).await?).send().await?;
+                    }
+                }
             }
         }
         next(dialogue)
     }
+
+    /// Role the command needs, if any.
+    fn role(&self) -> Option<Role> {
+        match self {
+            Command::Roles(Some(_))
+            | Command::Grant(..)
+            | Command::Revoke(..)
+            | Command::LinkTeacher(..) => Some(Role::Admin),
+            Command::Announce => Some(Role::GroupLeader),
+            Command::Notify | Command::Next | Command::Done => Some(Role::Teacher),
+            _ => None,
+        }
+    }
+}
+
+/// `help` with the sections of the roles the user has.
+async fn help(user_id: i64, language: Language) -> Result<String, BotError> {
+    let roles = access::roles(user_id).await?;
+    let mut help = locale::text(language, "help");
+    for role in Role::all().iter() {
+        if let Some(section) = help_section(*role) {
+            if access::allows(&roles, *role) {
+                help += "\n\n";
+                help += &locale::text(language, section);
+            }
+        }
+    }
+    Ok(help)
+}
+
+/// Message listing the commands of the role, for those with commands of their own.
+fn help_section(role: Role) -> Option<&'static str> {
+    match role {
+        Role::Admin => Some("help-admin"),
+        Role::GroupLeader => Some("help-group-leader"),
+        Role::Teacher => Some("help-teacher"),
+        Role::Student => None,
+    }
+}
+
+fn role_names(roles: &[Role], language: Language) -> String {
+    if roles.is_empty() {
+        return locale::text(language, "roles-none");
+    }
+    roles
+        .iter()
+        .map(|role| {
+            locale::text(
+                language,
+                &format!("role-{}", role.as_str().replace('_', "-")),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn role_message(language: Language, id: &str, user_id: i64, role: Role) -> String {
+    locale::format(
+        language,
+        id,
+        vec![
+            ("id", user_id.to_string().into()),
+            ("role", role_names(&[role], language).into()),
+        ],
+    )
+}
+
+/// Passes the user id through if the user is registered as a student.
+pub async fn student(user_id: i64) -> Result<i64, BotError> {
+    if repository::global().is_student(user_id).await? {
+        Ok(user_id)
+    } else {
+        Err(BotError::NotRegistered)
+    }
+}
+
+/// Message telling how to use the command starting `text` after its arguments were rejected.
+pub fn usage(text: &str) -> &'static str {
+    let command = text
+        .split_whitespace()
+        .next()
+        .and_then(|command| command.split('@').next());
+    match command {
+        Some("/week") | Some("/week_image") => "week-usage",
+        Some("/today") | Some("/current") | Some("/pdf") => "format-usage",
+        Some("/lang") => "lang-usage",
+        Some("/grant") | Some("/revoke") => "grant-usage",
+        Some("/link_teacher") => "link-teacher-usage",
+        Some("/queue") => "queue-usage",
+        _ => "invalid-arguments",
+    }
+}
+
+/// [`LessonFormat`] after a command, if any, overriding the one of `/settings`.
+fn optional_format(input: String) -> Result<(Option<LessonFormat>,), ParseError> {
+    match arguments(&input)? {
+        (None, format) => Ok((format,)),
+        (Some(_), _) => Err(incorrect("Expected compact, standard or verbose")),
+    }
+}
+
+/// Week after `/week` and an optional [`LessonFormat`].
+fn week(input: String) -> Result<(WeekType, Option<LessonFormat>), ParseError> {
+    match arguments(&input)? {
+        (Some(week), format) => Ok((week, format)),
+        (None, _) => Err(incorrect("Expected 1 or 2")),
+    }
+}
+
+/// Week after `/week_image`, none for the current one, and an optional [`LessonFormat`].
+fn optional_week(input: String) -> Result<(Option<WeekType>, Option<LessonFormat>), ParseError> {
+    arguments(&input)
+}
+
+/// Telegram id after `/roles`; none for the user's own.
+fn optional_user(input: String) -> Result<(Option<i64>,), ParseError> {
+    match input.trim() {
+        "" => Ok((None,)),
+        id => id
+            .parse()
+            .map(|id| (Some(id),))
+            .map_err(|error: ParseIntError| ParseError::IncorrectFormat(error.into())),
+    }
+}
+
+/// What to do with the queue after `/queue`; listing it by default.
+fn queue_action(input: String) -> Result<(queue::Action,), ParseError> {
+    match input.trim() {
+        "" => Ok((queue::Action::List,)),
+        action => action.parse().map(|action| (action,)).map_err(incorrect),
+    }
+}
+
+/// A week and a [`LessonFormat`], each optional, in any order.
+fn arguments(input: &str) -> Result<(Option<WeekType>, Option<LessonFormat>), ParseError> {
+    let (mut week, mut format) = (None, None);
+    for argument in input.split_whitespace() {
+        match (argument.parse(), argument.parse()) {
+            (Ok(parsed), _) if week.is_none() => week = Some(parsed),
+            (_, Ok(parsed)) if format.is_none() => format = Some(parsed),
+            _ => return Err(incorrect("Unexpected argument")),
+        }
+    }
+    Ok((week, format))
+}
+
+fn incorrect(message: &str) -> ParseError {
+    ParseError::IncorrectFormat(message.into())
 }
 
-fn days() -> KeyboardMarkup {
+fn days(language: Language) -> KeyboardMarkup {
     KeyboardMarkup::default()
-        .append_row(vec![
-            KeyboardButton::new("Пн"),
-            KeyboardButton::new("Вт"),
-            KeyboardButton::new("Ср"),
-            KeyboardButton::new("Чт"),
-            KeyboardButton::new("Пт"),
-            KeyboardButton::new("Сб"),
-            KeyboardButton::new("Вс"),
-        ])
+        .append_row(
+            Day::all()
+                .iter()
+                .map(|day| KeyboardButton::new(day.short_name(language)))
+                .collect(),
+        )
         .resize_keyboard(true)
 }
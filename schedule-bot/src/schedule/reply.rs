@@ -0,0 +1,28 @@
+use schedule_core::render::{self, Html, Style};
+use teloxide::dispatching::UpdateWithCx;
+use teloxide::prelude::*;
+use teloxide::types::ParseMode;
+use teloxide::RequestError;
+
+/// Sends the value as Telegram HTML in the user's style, in several messages if it is too long for one.
+pub async fn html<T: Html>(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    value: &T,
+    style: Style,
+) -> Result<(), RequestError> {
+    parts(cx, value.html_parts(style)).await
+}
+
+/// Sends parts of Telegram HTML joined by blank lines, in as few messages as fit.
+pub async fn parts(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    parts: Vec<String>,
+) -> Result<(), RequestError> {
+    for message in render::split(parts, render::MESSAGE_LIMIT) {
+        cx.answer(message)
+            .parse_mode(ParseMode::Html)
+            .send()
+            .await?;
+    }
+    Ok(())
+}
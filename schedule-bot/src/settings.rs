@@ -0,0 +1,306 @@
+//! `/settings`: a message with the user's settings and inline buttons changing them. A button
+//! press updates the settings and redraws the message in place.
+
+use chrono::NaiveTime;
+use schedule_core::database::settings::{LessonFormat, UserSettings};
+use schedule_core::locale::Language;
+use schedule_core::render::Style;
+use schedule_core::repository;
+use teloxide::dispatching::UpdateWithCx;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, User};
+
+use crate::error::{self, BotError};
+use crate::locale;
+
+/// Prefix of the callback data of the menu's buttons.
+const PREFIX: &str = "settings";
+
+/// Reminder lead times offered, in minutes.
+const REMINDERS: [Option<i32>; 5] = [None, Some(10), Some(15), Some(30), Some(60)];
+/// Digest times offered, as hours and minutes.
+const DIGESTS: [Option<(u32, u32)>; 5] = [
+    None,
+    Some((7, 0)),
+    Some((8, 0)),
+    Some((20, 0)),
+    Some((21, 0)),
+];
+
+/// How the user wants to see schedules in the language of the reply; `format` is the one given
+/// to the command, overriding the chosen one.
+pub async fn style(
+    user_id: i64,
+    language: Language,
+    format: Option<LessonFormat>,
+) -> Result<Style, BotError> {
+    let settings = repository::global().get_settings(user_id).await?;
+    let mut style = Style::new(language, &settings);
+    if let Some(format) = format {
+        style.format = format;
+    }
+    Ok(style)
+}
+
+/// Sends the menu.
+pub async fn show(
+    cx: &UpdateWithCx<AutoSend<Bot>, Message>,
+    user_id: i64,
+    language: Language,
+) -> Result<(), BotError> {
+    let settings = repository::global().get_settings(user_id).await?;
+    cx.answer(text(&settings, language))
+        .reply_markup(keyboard(&settings, language))
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Handles a press of a button of the menu.
+pub async fn callback(cx: UpdateWithCx<AutoSend<Bot>, CallbackQuery>) {
+    let query = &cx.update;
+    if let Err(error) = change(&cx).await {
+        let chat_id = query.message.as_ref().map(|message| message.chat.id);
+        match chat_id {
+            Some(chat_id) => {
+                let language = locale::language(Some(&query.from)).await;
+                error::report(
+                    &cx.requester,
+                    chat_id,
+                    query.data.as_deref(),
+                    language,
+                    &error,
+                )
+                .await
+            }
+            None => log::error!("Callback {:?}: {}", query.data, error),
+        }
+    }
+}
+
+async fn change(cx: &UpdateWithCx<AutoSend<Bot>, CallbackQuery>) -> Result<(), BotError> {
+    let query = &cx.update;
+    cx.requester.answer_callback_query(&query.id).await?;
+    let change = query
+        .data
+        .as_deref()
+        .and_then(Change::parse)
+        .ok_or(BotError::InvalidInput("invalid-arguments"))?;
+    let user_id = query.from.id;
+    let mut settings = repository::global().get_settings(user_id).await?;
+    if change.is_current(&settings, language(&settings, &query.from)) {
+        // Telegram refuses to edit a message into the same one.
+        return Ok(());
+    }
+    change.apply(&mut settings);
+    repository::global()
+        .set_settings(user_id, settings.clone())
+        .await?;
+
+    if let Some(message) = &query.message {
+        let language = language(&settings, &query.from);
+        cx.requester
+            .edit_message_text(message.chat.id, message.id, text(&settings, language))
+            .reply_markup(keyboard(&settings, language))
+            .await?;
+    }
+    Ok(())
+}
+
+/// The chosen language, otherwise the one of the user's Telegram client.
+fn language(settings: &UserSettings, user: &User) -> Language {
+    settings
+        .language
+        .unwrap_or_else(|| locale::client_language(user))
+}
+
+/// A setting changed by a button.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Language(Language),
+    Format(LessonFormat),
+    ShowTeacher(bool),
+    Reminder(Option<i32>),
+    Digest(Option<NaiveTime>),
+}
+
+impl Change {
+    /// Callback data of the button, e.g. `settings:reminder:15` or `settings:digest:off`.
+    fn data(&self) -> String {
+        let (field, value) = match self {
+            Change::Language(language) => ("lang", language.as_str().to_owned()),
+            Change::Format(format) => ("format", format.as_str().to_owned()),
+            Change::ShowTeacher(show) => ("teacher", if *show { "on" } else { "off" }.to_owned()),
+            Change::Reminder(minutes) => ("reminder", optional(minutes.map(|m| m.to_string()))),
+            Change::Digest(time) => (
+                "digest",
+                optional(time.map(|t| t.format("%H%M").to_string())),
+            ),
+        };
+        format!("{}:{}:{}", PREFIX, field, value)
+    }
+
+    fn parse(data: &str) -> Option<Change> {
+        let mut parts = data.splitn(3, ':');
+        if parts.next()? != PREFIX {
+            return None;
+        }
+        let (field, value) = (parts.next()?, parts.next()?);
+        let change = match (field, value) {
+            ("lang", language) => Change::Language(language.parse().ok()?),
+            ("format", format) => Change::Format(format.parse().ok()?),
+            ("teacher", "on") => Change::ShowTeacher(true),
+            ("teacher", "off") => Change::ShowTeacher(false),
+            ("reminder", "off") => Change::Reminder(None),
+            ("reminder", minutes) => Change::Reminder(Some(minutes.parse().ok()?)),
+            ("digest", "off") => Change::Digest(None),
+            ("digest", time) => Change::Digest(Some(NaiveTime::parse_from_str(time, "%H%M").ok()?)),
+            _ => return None,
+        };
+        Some(change)
+    }
+
+    fn apply(self, settings: &mut UserSettings) {
+        match self {
+            Change::Language(language) => settings.language = Some(language),
+            Change::Format(format) => settings.lesson_format = format,
+            Change::ShowTeacher(show) => settings.show_teacher = show,
+            Change::Reminder(minutes) => settings.reminder_minutes = minutes,
+            Change::Digest(time) => settings.digest_time = time,
+        }
+    }
+
+    /// Whether the button shows the current value of its setting, with the menu in `language`.
+    fn is_current(self, settings: &UserSettings, language: Language) -> bool {
+        match self {
+            Change::Language(other) => other == language,
+            Change::Format(format) => settings.lesson_format == format,
+            Change::ShowTeacher(show) => settings.show_teacher == show,
+            Change::Reminder(minutes) => settings.reminder_minutes == minutes,
+            Change::Digest(time) => settings.digest_time == time,
+        }
+    }
+}
+
+fn optional(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "off".to_owned())
+}
+
+fn digest_time((hour, minute): (u32, u32)) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn format_name(language: Language, format: LessonFormat) -> String {
+    locale::text(language, &format!("settings-{}", format.as_str()))
+}
+
+/// Summary of the settings above the buttons.
+fn text(settings: &UserSettings, language: Language) -> String {
+    let teacher = if settings.show_teacher {
+        "settings-shown"
+    } else {
+        "settings-hidden"
+    };
+    let reminder = match settings.reminder_minutes {
+        Some(minutes) => locale::format(
+            language,
+            "settings-reminder-before",
+            vec![("minutes", minutes.into())],
+        ),
+        None => locale::text(language, "settings-off"),
+    };
+    let digest = match settings.digest_time {
+        Some(time) => time.format("%H:%M").to_string(),
+        None => locale::text(language, "settings-off"),
+    };
+    locale::format(
+        language,
+        "settings",
+        vec![
+            ("language", language.name().into()),
+            (
+                "format",
+                format_name(language, settings.lesson_format).into(),
+            ),
+            ("teacher", locale::text(language, teacher).into()),
+            ("reminder", reminder.into()),
+            ("digest", digest.into()),
+        ],
+    )
+}
+
+/// A row of buttons for the values of a setting; the first is marked with the setting's emoji
+/// and the current value with a check mark.
+fn row(
+    settings: &UserSettings,
+    language: Language,
+    emoji: &str,
+    buttons: Vec<(Change, String)>,
+) -> Vec<InlineKeyboardButton> {
+    buttons
+        .into_iter()
+        .enumerate()
+        .map(|(i, (change, label))| {
+            let label = if i == 0 {
+                format!("{} {}", emoji, label)
+            } else {
+                label
+            };
+            let label = if change.is_current(settings, language) {
+                format!("✓ {}", label)
+            } else {
+                label
+            };
+            InlineKeyboardButton::callback(label, change.data())
+        })
+        .collect()
+}
+
+fn keyboard(settings: &UserSettings, language: Language) -> InlineKeyboardMarkup {
+    let off = locale::text(language, "settings-off");
+    let languages = Language::all()
+        .iter()
+        .map(|language| (Change::Language(*language), language.name().to_owned()))
+        .collect();
+    let formats = LessonFormat::all()
+        .iter()
+        .map(|format| (Change::Format(*format), format_name(language, *format)))
+        .collect();
+    let teacher = vec![
+        (
+            Change::ShowTeacher(true),
+            locale::text(language, "settings-show"),
+        ),
+        (
+            Change::ShowTeacher(false),
+            locale::text(language, "settings-hide"),
+        ),
+    ];
+    let reminders = REMINDERS
+        .iter()
+        .map(|minutes| {
+            let label = match minutes {
+                Some(minutes) => minutes.to_string(),
+                None => off.clone(),
+            };
+            (Change::Reminder(*minutes), label)
+        })
+        .collect();
+    let digests = DIGESTS
+        .iter()
+        .map(|time| {
+            let time = time.map(digest_time);
+            let label = match time {
+                Some(time) => time.format("%H:%M").to_string(),
+                None => off.clone(),
+            };
+            (Change::Digest(time), label)
+        })
+        .collect();
+    InlineKeyboardMarkup::default()
+        .append_row(row(settings, language, "🌐", languages))
+        .append_row(row(settings, language, "📋", formats))
+        .append_row(row(settings, language, "👤", teacher))
+        .append_row(row(settings, language, "🔔", reminders))
+        .append_row(row(settings, language, "📰", digests))
+}
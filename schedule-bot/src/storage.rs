@@ -0,0 +1,77 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use schedule_core::repository::{self, Repository};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+use teloxide::dispatching::dialogue::{Serializer, Storage};
+
+/// Dialogue storage backed by the global repository.
+pub struct DialogueStorage<S> {
+    repository: &'static dyn Repository,
+    serializer: S,
+}
+
+impl<S> DialogueStorage<S> {
+    pub fn new(serializer: S) -> Arc<Self> {
+        Arc::new(DialogueStorage {
+            repository: repository::global(),
+            serializer,
+        })
+    }
+}
+
+impl<S, D> Storage<D> for DialogueStorage<S>
+where
+    S: Send + Sync + Serializer<D> + 'static,
+    D: Send + Serialize + DeserializeOwned + 'static,
+    <S as Serializer<D>>::Error: Debug + Display,
+{
+    type Error = anyhow::Error;
+
+    fn remove_dialogue(
+        self: Arc<Self>,
+        chat_id: i64,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            Ok(match self.repository.get_dialogue(chat_id).await? {
+                Some(d) => {
+                    let prev_dialogue = self.serializer.deserialize(&d).map_err(|error| {
+                        anyhow::anyhow!("dialogue serialization error: {}", error)
+                    })?;
+                    self.repository.remove_dialogue(chat_id).await?;
+                    Some(prev_dialogue)
+                }
+                _ => None,
+            })
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: i64,
+        dialogue: D,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            let prev_dialogue = self
+                .repository
+                .get_dialogue(chat_id)
+                .await?
+                .map(|d| {
+                    self.serializer
+                        .deserialize(&d)
+                        .map_err(|error| anyhow::anyhow!("Database deserialize error: {}", error))
+                })
+                .transpose()?;
+            let upd_dialogue = self
+                .serializer
+                .serialize(&dialogue)
+                .map_err(|error| anyhow::anyhow!("Database serialize error: {}", error))?;
+            self.repository
+                .update_dialogue(chat_id, upd_dialogue)
+                .await?;
+            Ok(prev_dialogue)
+        })
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    // Migrations are embedded by `sqlx::migrate!`, which doesn't track the directory itself.
+    println!("cargo:rerun-if-changed=../migrations");
+}
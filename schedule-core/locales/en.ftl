@@ -0,0 +1,40 @@
+day-monday = Monday
+day-tuesday = Tuesday
+day-wednesday = Wednesday
+day-thursday = Thursday
+day-friday = Friday
+day-saturday = Saturday
+day-sunday = Sunday
+
+day-short-monday = Mon
+day-short-tuesday = Tue
+day-short-wednesday = Wed
+day-short-thursday = Thu
+day-short-friday = Fri
+day-short-saturday = Sat
+day-short-sunday = Sun
+
+day-off = Day off
+
+lesson-type-lecture = lecture
+lesson-type-practice = practice
+lesson-type-laboratory-work = laboratory work
+
+lesson-type-short-lecture = lect.
+lesson-type-short-practice = pract.
+lesson-type-short-laboratory-work = lab.
+
+lesson-heading = { $subject } ({ $type }): [{ $time }]
+lesson-teacher = Teacher: { $teacher }
+lesson-room = Room: { $room }
+
+week-off = No lessons this week
+week-title = Timetable: { $week } week
+week-first = first
+week-second = second
+
+distribution-first = first
+distribution-second = second
+distribution-all = both
+
+calendar-name = IPT schedule
@@ -0,0 +1,40 @@
+day-monday = Понедельник
+day-tuesday = Вторник
+day-wednesday = Среда
+day-thursday = Четверг
+day-friday = Пятница
+day-saturday = Суббота
+day-sunday = Воскресенье
+
+day-short-monday = Пн
+day-short-tuesday = Вт
+day-short-wednesday = Ср
+day-short-thursday = Чт
+day-short-friday = Пт
+day-short-saturday = Сб
+day-short-sunday = Вс
+
+day-off = Выходной день
+
+lesson-type-lecture = лекция
+lesson-type-practice = практика
+lesson-type-laboratory-work = лабораторная работа
+
+lesson-type-short-lecture = лекц.
+lesson-type-short-practice = практ.
+lesson-type-short-laboratory-work = лаб.
+
+lesson-heading = { $subject } ({ $type }): [{ $time }]
+lesson-teacher = Преподаватель: { $teacher }
+lesson-room = Аудитория: { $room }
+
+week-off = На этой неделе пар нет
+week-title = Расписание: { $week } неделя
+week-first = первая
+week-second = вторая
+
+distribution-first = первая
+distribution-second = вторая
+distribution-all = обе
+
+calendar-name = Расписание ИПТ
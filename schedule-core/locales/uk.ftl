@@ -0,0 +1,40 @@
+day-monday = Понеділок
+day-tuesday = Вівторок
+day-wednesday = Середа
+day-thursday = Четвер
+day-friday = Пʼятниця
+day-saturday = Субота
+day-sunday = Неділя
+
+day-short-monday = Пн
+day-short-tuesday = Вт
+day-short-wednesday = Ср
+day-short-thursday = Чт
+day-short-friday = Пт
+day-short-saturday = Сб
+day-short-sunday = Нд
+
+day-off = Вихідний день
+
+lesson-type-lecture = лекція
+lesson-type-practice = практика
+lesson-type-laboratory-work = лабораторна робота
+
+lesson-type-short-lecture = лекц.
+lesson-type-short-practice = практ.
+lesson-type-short-laboratory-work = лаб.
+
+lesson-heading = { $subject } ({ $type }): [{ $time }]
+lesson-teacher = Викладач: { $teacher }
+lesson-room = Аудиторія: { $room }
+
+week-off = Цього тижня пар немає
+week-title = Розклад: { $week } тиждень
+week-first = перший
+week-second = другий
+
+distribution-first = перший
+distribution-second = другий
+distribution-all = обидва
+
+calendar-name = Розклад ІПТ
@@ -0,0 +1,14 @@
+use chrono::NaiveTime;
+
+/// Length of a single pair in minutes.
+pub const LESSON_DURATION: i64 = 80;
+
+const BELLS: [(u32, u32); 6] = [(8, 30), (10, 25), (12, 20), (14, 15), (16, 10), (18, 30)];
+
+/// Start times of the pairs.
+pub fn bells() -> Vec<NaiveTime> {
+    BELLS
+        .iter()
+        .map(|&(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+        .collect()
+}
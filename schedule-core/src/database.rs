@@ -0,0 +1,363 @@
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
+use once_cell::sync::OnceCell;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Postgres, Row};
+
+use crate::database::admin::LESSON_ENTRY_QUERY;
+use crate::database::day::Day;
+use crate::database::deadline::Deadline;
+use crate::database::group::Group;
+use crate::database::lesson::{LessonDay, LessonEntry, Lessons, LessonsWeek};
+use crate::database::student::Student;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::week::WeekType;
+use crate::selection::{self, TimetableRows};
+
+pub mod admin;
+pub mod announcement;
+pub mod day;
+pub mod deadline;
+pub mod dialogue;
+pub mod group;
+pub mod homework;
+pub mod lesson;
+pub mod queue;
+pub mod role;
+pub mod session;
+pub mod settings;
+pub mod student;
+pub mod subject;
+pub mod teacher;
+pub mod week;
+
+static INSTANCE: OnceCell<Database> = OnceCell::new();
+
+/// Versioned schema migrations from `migrations/`, embedded at compile time. Applied versions and
+/// their checksums are kept in `_sqlx_migrations`; editing an applied migration fails startup.
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+pub struct Database {
+    pool: PgPool,
+}
+
+/// 32 random hex digits from the operating system, for calendar feeds and web sessions.
+pub fn token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|error| anyhow::anyhow!(error))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Connects to the database and brings its schema up to date.
+pub async fn initialize(database_url: &str, max_connections: u32) -> Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(database_url)
+        .await?;
+    MIGRATOR
+        .run(&pool)
+        .await
+        .map_err(|error| anyhow::anyhow!("Failed to migrate database: {}", error))?;
+    INSTANCE
+        .set(Database { pool })
+        .map_err(|_| anyhow::anyhow!("Failed to initialize database!"))
+}
+
+impl Database {
+    pub fn global() -> &'static Database {
+        INSTANCE.get().expect("Pool is not initialized")
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub async fn get_schedule_by_day(&self, day: Day, user_id: i64) -> Result<Lessons> {
+        let week = self.get_distribution_week().await?;
+        Ok(selection::day(self.student_week(user_id, week).await?, day))
+    }
+
+    pub async fn get_today_schedule(&self, user_id: i64) -> Result<Lessons> {
+        let today = Local::now().naive_local();
+        let week = WeekType::at(self.get_distribution_start().await?, today.date());
+        Ok(selection::day(
+            self.student_week(user_id, week).await?,
+            Day::from(today.weekday()),
+        ))
+    }
+
+    pub async fn get_current_schedule(&self, user_id: i64) -> Result<Lessons> {
+        let now = Local::now().naive_local();
+        Ok(selection::current(
+            self.get_today_schedule(user_id).await?,
+            now.time(),
+        ))
+    }
+
+    pub async fn get_week_schedule(&self, user_id: i64, week: &WeekType) -> Result<LessonsWeek> {
+        Ok(selection::by_day(self.student_week(user_id, *week).await?))
+    }
+
+    pub async fn get_group_week_schedule(
+        &self,
+        group_id: i32,
+        week: &WeekType,
+    ) -> Result<LessonsWeek> {
+        let rows = self
+            .timetable_rows(
+                r#"EXISTS(SELECT * FROM lessons_groups WHERE lesson = schedule.id AND "group" = $1)"#,
+                group_id,
+            )
+            .await?;
+        Ok(selection::by_day(rows.group_week(group_id, *week)))
+    }
+
+    pub async fn get_teacher_week_schedule(
+        &self,
+        teacher_id: i32,
+        week: &WeekType,
+    ) -> Result<LessonsWeek> {
+        let rows = self
+            .timetable_rows("schedule.teacher = $1", teacher_id)
+            .await?;
+        Ok(selection::by_day(rows.teacher_week(teacher_id, *week)))
+    }
+
+    pub async fn get_room_week_schedule(&self, room: &str, week: &WeekType) -> Result<LessonsWeek> {
+        let rows = self
+            .timetable_rows("schedule.room = $1", room.to_string())
+            .await?;
+        Ok(selection::by_day(rows.room_week(room, *week)))
+    }
+
+    /// Lessons the student attends in the given week; none for unknown users.
+    async fn student_week(&self, user_id: i64, week: WeekType) -> Result<Vec<LessonDay>> {
+        Ok(match self.student_rows(user_id).await? {
+            Some((group, electives, rows)) => rows.student_week(group, &electives, week),
+            None => vec![],
+        })
+    }
+
+    /// The student's group and electives with the lessons of the group; none for unknown users.
+    async fn student_rows(&self, user_id: i64) -> Result<Option<(i32, Vec<i32>, TimetableRows)>> {
+        let student = sqlx::query(
+            r#"
+            SELECT students."group",
+                   COALESCE(array_agg(students_subjects.subject) FILTER (WHERE students_subjects.subject IS NOT NULL), '{}')
+            FROM students
+            LEFT JOIN students_subjects ON students_subjects.student = students.id
+            WHERE students.telegram_id = $1
+            GROUP BY students.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (group, electives): (i32, Vec<i32>) =
+            match student.and_then(|row| Some((row.get::<Option<i32>, _>(0)?, row.get(1)))) {
+                Some(student) => student,
+                None => return Ok(None),
+            };
+        let rows = self
+            .timetable_rows(
+                r#"EXISTS(SELECT * FROM lessons_groups WHERE lesson = schedule.id AND "group" = $1)"#,
+                group,
+            )
+            .await?;
+        Ok(Some((group, electives, rows)))
+    }
+
+    /// Lessons matching `condition` on `$1`, with the subjects and teachers they refer to.
+    async fn timetable_rows<T>(&self, condition: &str, value: T) -> Result<TimetableRows>
+    where
+        T: 'static + Send + for<'q> sqlx::Encode<'q, Postgres> + sqlx::Type<Postgres>,
+    {
+        let lessons = sqlx::query_as::<_, LessonEntry>(&format!(
+            "{} WHERE {} GROUP BY schedule.id",
+            LESSON_ENTRY_QUERY, condition
+        ))
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await?;
+        let subjects: Vec<i32> = lessons.iter().map(|lesson| lesson.subject).collect();
+        let teachers: Vec<i32> = lessons.iter().filter_map(|lesson| lesson.teacher).collect();
+        Ok(TimetableRows {
+            subjects: sqlx::query_as::<_, Subject>(
+                r#"
+                SELECT id, COALESCE(name, '') AS name, COALESCE(choice, FALSE) AS choice, info
+                FROM subjects WHERE id = ANY($1)
+                "#,
+            )
+            .bind(subjects)
+            .fetch_all(&self.pool)
+            .await?,
+            teachers: sqlx::query_as::<_, Teacher>(
+                r#"
+                SELECT id, COALESCE(last_name, '') AS last_name, COALESCE(first_name, '') AS first_name,
+                       COALESCE(patronymic_name, '') AS patronymic_name, telegram, email, phone_number
+                FROM teachers WHERE id = ANY($1)
+                "#,
+            )
+            .bind(teachers)
+            .fetch_all(&self.pool)
+            .await?,
+            lessons,
+        })
+    }
+
+    pub async fn get_student(&self, user_id: i64) -> Result<Option<Student>> {
+        sqlx::query_as::<_, Student>(
+            r#"
+            SELECT id, telegram_id::BIGINT, "group", COALESCE(last_name, '') AS last_name
+            FROM students WHERE telegram_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn get_groups(&self) -> Result<Vec<Group>> {
+        sqlx::query_as::<_, Group>(
+            "SELECT id, COALESCE(name, '') AS name FROM groups ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn get_subjects(&self) -> Result<Vec<Subject>> {
+        sqlx::query_as::<_, Subject>(
+            r#"
+            SELECT id, COALESCE(name, '') AS name, COALESCE(choice, FALSE) AS choice, info
+            FROM subjects ORDER BY name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn get_teachers(&self) -> Result<Vec<Teacher>> {
+        sqlx::query_as::<_, Teacher>(
+            r#"
+            SELECT id, COALESCE(last_name, '') AS last_name, COALESCE(first_name, '') AS first_name,
+                   COALESCE(patronymic_name, '') AS patronymic_name, telegram, email, phone_number
+            FROM teachers ORDER BY last_name, first_name, patronymic_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn get_teacher(&self, id: i32) -> Result<Option<Teacher>> {
+        sqlx::query_as::<_, Teacher>(
+            r#"
+            SELECT id, COALESCE(last_name, '') AS last_name, COALESCE(first_name, '') AS first_name,
+                   COALESCE(patronymic_name, '') AS patronymic_name, telegram, email, phone_number
+            FROM teachers WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Rooms mentioned anywhere in the timetable.
+    pub async fn get_rooms(&self) -> Result<Vec<String>> {
+        sqlx::query("SELECT DISTINCT room FROM schedule WHERE room <> '' ORDER BY room")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|rows| rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    /// Upcoming deadlines, optionally only those of the given group. Deadlines without a group
+    /// or a date are skipped.
+    pub async fn get_deadlines(&self, group_id: Option<i32>) -> Result<Vec<Deadline>> {
+        sqlx::query_as::<_, Deadline>(
+            r#"
+            SELECT deadlines.id, COALESCE(subjects.name, '') AS subject_name, deadlines."group",
+                   COALESCE(deadlines.description, '') AS description, deadlines.deadline
+            FROM deadlines
+            JOIN subjects ON subjects.id = deadlines.subject
+            WHERE deadlines.deadline >= now() AND deadlines."group" IS NOT NULL
+              AND ($1::INTEGER IS NULL OR deadlines."group" = $1)
+            ORDER BY deadlines.deadline
+            "#,
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn is_student(&self, user_id: i64) -> Result<bool> {
+        sqlx::query("SELECT EXISTS(SELECT * FROM students WHERE telegram_id = $1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.get(0))
+    }
+
+    /// Token of the student's calendar feed, created on first use.
+    pub async fn get_calendar_token(&self, user_id: i64) -> Result<Option<String>> {
+        sqlx::query(
+            r#"
+            INSERT INTO calendar_tokens(student, token)
+            SELECT id, $2 FROM students WHERE telegram_id = $1
+            ON CONFLICT (student) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(token()?)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            r#"
+            SELECT calendar_tokens.token FROM calendar_tokens
+            JOIN students ON students.id = calendar_tokens.student
+            WHERE students.telegram_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|row| row.map(|row| row.get(0)))
+    }
+
+    pub async fn get_user_by_calendar_token(&self, token: &str) -> Result<Option<i64>> {
+        sqlx::query(
+            r#"
+            SELECT students.telegram_id::BIGINT FROM calendar_tokens
+            JOIN students ON students.id = calendar_tokens.student
+            WHERE calendar_tokens.token = $1
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|row| row.map(|row| row.get(0)))
+    }
+
+    pub async fn get_distribution_week(&self) -> Result<WeekType> {
+        let today = Local::now().naive_local().date();
+        Ok(WeekType::at(self.get_distribution_start().await?, today))
+    }
+
+    pub async fn get_distribution_start(&self) -> Result<NaiveDate> {
+        sqlx::query("SELECT * FROM get_distribution_start()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.get(0))
+    }
+}
@@ -0,0 +1,289 @@
+use anyhow::Result;
+use sqlx::{Postgres, Row, Transaction};
+
+use crate::database::group::Group;
+use crate::database::lesson::LessonEntry;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::Database;
+
+pub(crate) const LESSON_ENTRY_QUERY: &str = r#"
+    SELECT schedule.id, schedule.subject, schedule.type AS lesson_type, schedule.teacher,
+           schedule.day_of_week, schedule.time, schedule.distribution,
+           COALESCE(schedule.info, '') AS info, COALESCE(schedule.room, '') AS room,
+           COALESCE(array_agg(lessons_groups."group" ORDER BY lessons_groups."group") FILTER (WHERE lessons_groups."group" IS NOT NULL), '{}') AS groups
+    FROM schedule
+    LEFT JOIN lessons_groups ON lessons_groups.lesson = schedule.id
+"#;
+
+/// Editing queries used by the admin panel.
+impl Database {
+    pub async fn get_group(&self, id: i32) -> Result<Option<Group>> {
+        sqlx::query_as::<_, Group>(
+            "SELECT id, COALESCE(name, '') AS name FROM groups WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn insert_group(&self, group: &Group) -> Result<i32> {
+        sqlx::query("INSERT INTO groups(name) VALUES ($1) RETURNING id")
+            .bind(&group.name)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.get(0))
+    }
+
+    pub async fn update_group(&self, group: &Group) -> Result<()> {
+        sqlx::query("UPDATE groups SET name = $2 WHERE id = $1")
+            .bind(group.id)
+            .bind(&group.name)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+
+    pub async fn delete_group(&self, id: i32) -> Result<()> {
+        sqlx::query("DELETE FROM groups WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+
+    pub async fn insert_subject(&self, subject: &Subject) -> Result<i32> {
+        sqlx::query("INSERT INTO subjects(name, choice, info) VALUES ($1, $2, $3) RETURNING id")
+            .bind(&subject.name)
+            .bind(subject.choice)
+            .bind(&subject.info)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.get(0))
+    }
+
+    pub async fn update_subject(&self, subject: &Subject) -> Result<()> {
+        sqlx::query("UPDATE subjects SET name = $2, choice = $3, info = $4 WHERE id = $1")
+            .bind(subject.id)
+            .bind(&subject.name)
+            .bind(subject.choice)
+            .bind(&subject.info)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+
+    pub async fn delete_subject(&self, id: i32) -> Result<()> {
+        sqlx::query("DELETE FROM subjects WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+
+    pub async fn insert_teacher(&self, teacher: &Teacher) -> Result<i32> {
+        sqlx::query(
+            r#"
+            INSERT INTO teachers(last_name, first_name, patronymic_name, telegram, email, phone_number)
+            VALUES ($1, $2, $3, $4, $5, $6) RETURNING id
+            "#,
+        )
+        .bind(&teacher.last_name)
+        .bind(&teacher.first_name)
+        .bind(&teacher.patronymic_name)
+        .bind(&teacher.telegram)
+        .bind(&teacher.email)
+        .bind(&teacher.phone_number)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|row| row.get(0))
+    }
+
+    pub async fn update_teacher(&self, teacher: &Teacher) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE teachers SET last_name = $2, first_name = $3, patronymic_name = $4,
+                                telegram = $5, email = $6, phone_number = $7
+            WHERE id = $1
+            "#,
+        )
+        .bind(teacher.id)
+        .bind(&teacher.last_name)
+        .bind(&teacher.first_name)
+        .bind(&teacher.patronymic_name)
+        .bind(&teacher.telegram)
+        .bind(&teacher.email)
+        .bind(&teacher.phone_number)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|_| ())
+    }
+
+    pub async fn delete_teacher(&self, id: i32) -> Result<()> {
+        sqlx::query("DELETE FROM teachers WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+
+    pub async fn get_lesson_entry(&self, id: i32) -> Result<Option<LessonEntry>> {
+        sqlx::query_as::<_, LessonEntry>(&format!(
+            "{} WHERE schedule.id = $1 GROUP BY schedule.id",
+            LESSON_ENTRY_QUERY
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Lessons taught to the given group, ordered by day and time.
+    pub async fn get_group_lesson_entries(&self, group_id: i32) -> Result<Vec<LessonEntry>> {
+        sqlx::query_as::<_, LessonEntry>(&format!(
+            r#"{}
+            WHERE EXISTS(SELECT * FROM lessons_groups WHERE lesson = schedule.id AND "group" = $1)
+            GROUP BY schedule.id
+            ORDER BY schedule.day_of_week, schedule.time"#,
+            LESSON_ENTRY_QUERY
+        ))
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Another lesson of the same teacher that overlaps in time with the given one.
+    pub async fn find_teacher_conflict(&self, entry: &LessonEntry) -> Result<Option<LessonEntry>> {
+        let teacher = match entry.teacher {
+            Some(teacher) => teacher,
+            None => return Ok(None),
+        };
+        sqlx::query_as::<_, LessonEntry>(&format!(
+            r#"{}
+            WHERE schedule.id <> $1 AND schedule.teacher = $2 AND schedule.day_of_week = $3 AND
+                  schedule.time < $4 + make_interval(mins => $6) AND
+                  $4 < schedule.time + make_interval(mins => $6) AND
+                  (schedule.distribution = 'all'::distribution_week OR $5 = 'all'::distribution_week OR schedule.distribution = $5)
+            GROUP BY schedule.id
+            LIMIT 1"#,
+            LESSON_ENTRY_QUERY
+        ))
+        .bind(entry.id)
+        .bind(teacher)
+        .bind(entry.day)
+        .bind(entry.time)
+        .bind(entry.distribution)
+        .bind(crate::bell::LESSON_DURATION as i32)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn insert_lesson_entry(&self, entry: &LessonEntry) -> Result<i32> {
+        let mut transaction = self.pool.begin().await?;
+        let id = insert_lesson_entry(&mut transaction, entry).await?;
+        transaction.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn update_lesson_entry(&self, entry: &LessonEntry) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        update_lesson_entry(&mut transaction, entry).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete_lesson_entry(&self, id: i32) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM lessons_groups WHERE lesson = $1")
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM schedule WHERE id = $1")
+            .bind(id)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+pub(crate) async fn insert_lesson_entry(
+    transaction: &mut Transaction<'_, Postgres>,
+    entry: &LessonEntry,
+) -> Result<i32> {
+    let id: i32 = sqlx::query(
+        r#"
+        INSERT INTO schedule(subject, type, teacher, day_of_week, time, distribution, info, room)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id
+        "#,
+    )
+    .bind(entry.subject)
+    .bind(entry.lesson_type)
+    .bind(entry.teacher)
+    .bind(entry.day)
+    .bind(entry.time)
+    .bind(entry.distribution)
+    .bind(&entry.info)
+    .bind(&entry.room)
+    .fetch_one(&mut *transaction)
+    .await?
+    .get(0);
+    insert_lesson_groups(transaction, id, &entry.groups).await?;
+    Ok(id)
+}
+
+pub(crate) async fn update_lesson_entry(
+    transaction: &mut Transaction<'_, Postgres>,
+    entry: &LessonEntry,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE schedule SET subject = $2, type = $3, teacher = $4, day_of_week = $5,
+                            time = $6, distribution = $7, info = $8, room = $9
+        WHERE id = $1
+        "#,
+    )
+    .bind(entry.id)
+    .bind(entry.subject)
+    .bind(entry.lesson_type)
+    .bind(entry.teacher)
+    .bind(entry.day)
+    .bind(entry.time)
+    .bind(entry.distribution)
+    .bind(&entry.info)
+    .bind(&entry.room)
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query("DELETE FROM lessons_groups WHERE lesson = $1")
+        .bind(entry.id)
+        .execute(&mut *transaction)
+        .await?;
+    insert_lesson_groups(transaction, entry.id, &entry.groups).await
+}
+
+async fn insert_lesson_groups(
+    transaction: &mut Transaction<'_, Postgres>,
+    lesson: i32,
+    groups: &[i32],
+) -> Result<()> {
+    for group in groups {
+        sqlx::query(r#"INSERT INTO lessons_groups(lesson, "group") VALUES ($1, $2)"#)
+            .bind(lesson)
+            .bind(group)
+            .execute(&mut *transaction)
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::database::group::Group;
+use crate::database::teacher::Teacher;
+use crate::database::Database;
+
+/// Who an announcement of `/announce` is sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Audience {
+    /// Everyone the bot knows: the students and the users with settings or roles.
+    Everyone,
+    /// Students of the group.
+    Group(i32),
+    /// Students enrolled in the elective subject.
+    Subject(i32),
+    /// Students who have lessons of the subject with the teacher, in one group or all of them.
+    Taught {
+        teacher: i32,
+        subject: i32,
+        group: Option<i32>,
+    },
+}
+
+/// A group the teacher has lessons of the subject with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TaughtGroup {
+    pub subject: i32,
+    pub subject_name: String,
+    pub group: i32,
+    pub group_name: String,
+}
+
+/// An announcement as logged for its recipients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Announcement {
+    pub author_name: String,
+    pub text: String,
+    pub sent_at: NaiveDateTime,
+}
+
+/// Recipients of announcements.
+impl Database {
+    /// Telegram ids of the audience in ascending order.
+    pub async fn get_recipients(&self, audience: Audience) -> Result<Vec<i64>> {
+        let query = match audience {
+            Audience::Everyone => sqlx::query(
+                r#"
+                SELECT telegram_id::BIGINT FROM students WHERE telegram_id IS NOT NULL
+                UNION SELECT telegram_id FROM user_settings
+                UNION SELECT telegram_id FROM user_roles
+                ORDER BY 1
+                "#,
+            ),
+            Audience::Group(group) => sqlx::query(
+                r#"
+                SELECT DISTINCT telegram_id::BIGINT FROM students
+                WHERE "group" = $1 AND telegram_id IS NOT NULL
+                ORDER BY 1
+                "#,
+            )
+            .bind(group),
+            Audience::Subject(subject) => sqlx::query(
+                r#"
+                SELECT DISTINCT students.telegram_id::BIGINT FROM students
+                JOIN students_subjects ON students_subjects.student = students.id
+                WHERE students_subjects.subject = $1 AND students.telegram_id IS NOT NULL
+                ORDER BY 1
+                "#,
+            )
+            .bind(subject),
+            Audience::Taught {
+                teacher,
+                subject,
+                group,
+            } => sqlx::query(
+                r#"
+                SELECT DISTINCT students.telegram_id::BIGINT FROM schedule
+                JOIN subjects ON subjects.id = schedule.subject
+                JOIN lessons_groups ON lessons_groups.lesson = schedule.id
+                JOIN students ON students."group" = lessons_groups."group"
+                WHERE schedule.teacher = $1 AND schedule.subject = $2
+                  AND ($3::INTEGER IS NULL OR lessons_groups."group" = $3)
+                  AND students.telegram_id IS NOT NULL
+                  AND (NOT COALESCE(subjects.choice, FALSE) OR EXISTS(
+                      SELECT * FROM students_subjects
+                      WHERE students_subjects.student = students.id
+                        AND students_subjects.subject = schedule.subject
+                  ))
+                ORDER BY 1
+                "#,
+            )
+            .bind(teacher)
+            .bind(subject)
+            .bind(group),
+        };
+        query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|rows| rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// Group of the registered student, if they have one.
+    pub async fn get_student_group(&self, user_id: i64) -> Result<Option<Group>> {
+        sqlx::query_as::<_, Group>(
+            r#"
+            SELECT groups.id, COALESCE(groups.name, '') AS name FROM students
+            JOIN groups ON groups.id = students."group"
+            WHERE students.telegram_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Teacher linked to the Telegram user with [`Database::link_teacher`].
+    pub async fn get_linked_teacher(&self, user_id: i64) -> Result<Option<Teacher>> {
+        sqlx::query_as::<_, Teacher>(
+            r#"
+            SELECT id, COALESCE(last_name, '') AS last_name, COALESCE(first_name, '') AS first_name,
+                   COALESCE(patronymic_name, '') AS patronymic_name, telegram, email, phone_number
+            FROM teachers WHERE telegram_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Links the teacher to the Telegram user, unlinking the user's previous teacher; `false`
+    /// if there is no such teacher.
+    pub async fn link_teacher(&self, user_id: i64, teacher_id: i32) -> Result<bool> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("UPDATE teachers SET telegram_id = NULL WHERE telegram_id = $1")
+            .bind(user_id)
+            .execute(&mut transaction)
+            .await?;
+        let linked = sqlx::query("UPDATE teachers SET telegram_id = $1 WHERE id = $2")
+            .bind(user_id)
+            .bind(teacher_id)
+            .execute(&mut transaction)
+            .await?
+            .rows_affected()
+            > 0;
+        transaction.commit().await?;
+        Ok(linked)
+    }
+
+    /// Groups the teacher has lessons with, by subject and group name.
+    pub async fn get_taught_groups(&self, teacher_id: i32) -> Result<Vec<TaughtGroup>> {
+        sqlx::query_as::<_, TaughtGroup>(
+            r#"
+            SELECT DISTINCT subjects.id AS subject, COALESCE(subjects.name, '') AS subject_name,
+                   groups.id AS "group", COALESCE(groups.name, '') AS group_name
+            FROM schedule
+            JOIN subjects ON subjects.id = schedule.subject
+            JOIN lessons_groups ON lessons_groups.lesson = schedule.id
+            JOIN groups ON groups.id = lessons_groups."group"
+            WHERE schedule.teacher = $1
+            ORDER BY subject_name, subject, group_name, "group"
+            "#,
+        )
+        .bind(teacher_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Logs the announcement for its recipients.
+    pub async fn save_announcement(
+        &self,
+        author: i64,
+        author_name: &str,
+        text: &str,
+        recipients: &[i64],
+    ) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        let id: i32 = sqlx::query(
+            "INSERT INTO announcements(author, author_name, text) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(author)
+        .bind(author_name)
+        .bind(text)
+        .fetch_one(&mut transaction)
+        .await?
+        .get(0);
+        sqlx::query(
+            r#"
+            INSERT INTO announcement_recipients(announcement, telegram_id)
+            SELECT $1, UNNEST($2::BIGINT[]) ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(recipients)
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// The last `limit` announcements sent to the user, newest first.
+    pub async fn get_announcements(&self, user_id: i64, limit: i64) -> Result<Vec<Announcement>> {
+        sqlx::query_as::<_, Announcement>(
+            r#"
+            SELECT author_name, text, sent_at FROM announcements
+            JOIN announcement_recipients ON announcement_recipients.announcement = announcements.id
+            WHERE announcement_recipients.telegram_id = $1
+            ORDER BY sent_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+}
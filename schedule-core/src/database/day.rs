@@ -0,0 +1,110 @@
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::{self, Language, Localize};
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, sqlx::Type,
+)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "days_of_week")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Day {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Day {
+    pub fn all() -> [Day; 7] {
+        [
+            Day::Monday,
+            Day::Tuesday,
+            Day::Wednesday,
+            Day::Thursday,
+            Day::Friday,
+            Day::Saturday,
+            Day::Sunday,
+        ]
+    }
+
+    /// Name of the day as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Day::Monday => "monday",
+            Day::Tuesday => "tuesday",
+            Day::Wednesday => "wednesday",
+            Day::Thursday => "thursday",
+            Day::Friday => "friday",
+            Day::Saturday => "saturday",
+            Day::Sunday => "sunday",
+        }
+    }
+
+    /// Abbreviated name, e.g. `Пн`.
+    pub fn short_name(&self, language: Language) -> String {
+        locale::global().get(language, &format!("day-short-{}", self.as_str()))
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            Day::Monday => Weekday::Mon,
+            Day::Tuesday => Weekday::Tue,
+            Day::Wednesday => Weekday::Wed,
+            Day::Thursday => Weekday::Thu,
+            Day::Friday => Weekday::Fri,
+            Day::Saturday => Weekday::Sat,
+            Day::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+impl From<Weekday> for Day {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Mon => Day::Monday,
+            Weekday::Tue => Day::Tuesday,
+            Weekday::Wed => Day::Wednesday,
+            Weekday::Thu => Day::Thursday,
+            Weekday::Fri => Day::Friday,
+            Weekday::Sat => Day::Saturday,
+            Weekday::Sun => Day::Sunday,
+        }
+    }
+}
+
+impl FromStr for Day {
+    type Err = &'static str;
+
+    /// Short name of the day in any language, as on the bot's keyboard.
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Day::all()
+            .iter()
+            .copied()
+            .find(|day| {
+                Language::all()
+                    .iter()
+                    .any(|language| day.short_name(*language) == s)
+            })
+            .ok_or("Вы ввели неправильный формат дня недели!")
+    }
+}
+
+impl Localize for Day {
+    fn localize(&self, language: Language) -> String {
+        locale::global().get(language, &format!("day-{}", self.as_str()))
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
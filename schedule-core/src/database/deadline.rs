@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Deadline {
+    pub id: i32,
+    pub subject_name: String,
+    pub group: i32,
+    pub description: String,
+    pub deadline: NaiveDateTime,
+}
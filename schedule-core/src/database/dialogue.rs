@@ -0,0 +1,40 @@
+use anyhow::Result;
+use sqlx::Row;
+
+use crate::database::Database;
+
+/// Serialized bot dialogues, stored in the `teloxide_dialogues` table.
+impl Database {
+    pub async fn get_dialogue(&self, chat_id: i64) -> Result<Option<Vec<u8>>> {
+        sqlx::query("SELECT dialogue FROM teloxide_dialogues WHERE chat_id = $1")
+            .bind(chat_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.map(|row| row.get(0)))
+    }
+
+    pub async fn update_dialogue(&self, chat_id: i64, dialogue: &[u8]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO teloxide_dialogues VALUES ($1, $2)
+            ON CONFLICT(chat_id) DO UPDATE SET dialogue = excluded.dialogue
+            "#,
+        )
+        .bind(chat_id)
+        .bind(dialogue)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|_| ())
+    }
+
+    pub async fn remove_dialogue(&self, chat_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM teloxide_dialogues WHERE chat_id = $1")
+            .bind(chat_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+}
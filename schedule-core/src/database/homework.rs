@@ -0,0 +1,157 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::database::lesson::Lessons;
+use crate::database::subject::Subject;
+use crate::database::Database;
+
+/// How a file attached to homework is sent back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "attachment")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Attachment {
+    Document,
+    Photo,
+}
+
+/// A Telegram file, by the `file_id` the bot received it with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HomeworkFile {
+    pub file_id: String,
+    pub kind: Attachment,
+}
+
+/// Homework due at the lessons of a subject on a date.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Homework {
+    pub subject: i32,
+    pub subject_name: String,
+    pub text: String,
+    pub files: Vec<HomeworkFile>,
+}
+
+/// Lessons of a date with the homework due at them, shown under the first lesson of its
+/// subject.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LessonsHomework {
+    pub lessons: Lessons,
+    pub homework: Vec<Homework>,
+}
+
+/// Homework, stored in the `homework` and `homework_files` tables.
+impl Database {
+    /// Subjects the group has lessons of, by name.
+    pub async fn get_group_subjects(&self, group: i32) -> Result<Vec<Subject>> {
+        sqlx::query_as::<_, Subject>(
+            r#"
+            SELECT DISTINCT subjects.id, COALESCE(subjects.name, '') AS name,
+                   COALESCE(subjects.choice, FALSE) AS choice, subjects.info
+            FROM subjects
+            JOIN schedule ON schedule.subject = subjects.id
+            JOIN lessons_groups ON lessons_groups.lesson = schedule.id
+            WHERE lessons_groups."group" = $1
+            ORDER BY name, id
+            "#,
+        )
+        .bind(group)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Dates of the group's lessons of the subject from today on, within two weeks.
+    pub async fn get_lesson_dates(&self, group: i32, subject: i32) -> Result<Vec<NaiveDate>> {
+        let today = Local::now().date_naive();
+        let start = self.get_distribution_start().await?;
+        let rows = self
+            .timetable_rows(
+                r#"EXISTS(SELECT * FROM lessons_groups WHERE lesson = schedule.id AND "group" = $1)"#,
+                group,
+            )
+            .await?;
+        Ok(rows.lesson_dates(group, subject, start, today))
+    }
+
+    pub async fn add_homework(
+        &self,
+        author: i64,
+        subject: i32,
+        group: i32,
+        date: NaiveDate,
+        text: &str,
+        files: &[HomeworkFile],
+    ) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        let id: i32 = sqlx::query(
+            r#"
+            INSERT INTO homework(subject, "group", date, text, author) VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(subject)
+        .bind(group)
+        .bind(date)
+        .bind(text)
+        .bind(author)
+        .fetch_one(&mut transaction)
+        .await?
+        .get(0);
+        for file in files {
+            sqlx::query("INSERT INTO homework_files(homework, file_id, kind) VALUES ($1, $2, $3)")
+                .bind(id)
+                .bind(&file.file_id)
+                .bind(file.kind)
+                .execute(&mut transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Homework of the student's group due on the date, in the order given.
+    pub async fn get_homework(&self, user_id: i64, date: NaiveDate) -> Result<Vec<Homework>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT homework.id, homework.subject, COALESCE(subjects.name, ''), homework.text
+            FROM homework
+            JOIN subjects ON subjects.id = homework.subject
+            JOIN students ON students."group" = homework."group"
+            WHERE students.telegram_id = $1 AND homework.date = $2
+            ORDER BY homework.id
+            "#,
+        )
+        .bind(user_id)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+        let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+        let files = sqlx::query(
+            "SELECT homework, file_id, kind FROM homework_files WHERE homework = ANY($1) ORDER BY id",
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let id: i32 = row.get(0);
+                Homework {
+                    subject: row.get(1),
+                    subject_name: row.get(2),
+                    text: row.get(3),
+                    files: files
+                        .iter()
+                        .filter(|file| file.get::<i32, _>(0) == id)
+                        .map(|file| HomeworkFile {
+                            file_id: file.get(1),
+                            kind: file.get(2),
+                        })
+                        .collect(),
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::database::day::Day;
+use crate::database::week::Distribution;
+use crate::locale::{self, Language, Localize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "lesson_types")]
+#[sqlx(rename_all = "lowercase")]
+pub enum LessonType {
+    Lecture,
+    Practice,
+    #[sqlx(rename = "laboratory work")]
+    LaboratoryWork,
+}
+
+impl LessonType {
+    pub fn all() -> [LessonType; 3] {
+        [
+            LessonType::Lecture,
+            LessonType::Practice,
+            LessonType::LaboratoryWork,
+        ]
+    }
+
+    /// Name of the lesson type as used in URLs and forms.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LessonType::Lecture => "lecture",
+            LessonType::Practice => "practice",
+            LessonType::LaboratoryWork => "laboratory_work",
+        }
+    }
+
+    /// Abbreviated name, e.g. `лекц.`.
+    pub fn short_name(&self, language: Language) -> String {
+        let id = format!("lesson-type-short-{}", self.as_str().replace('_', "-"));
+        locale::global().get(language, &id)
+    }
+}
+
+impl Localize for LessonType {
+    fn localize(&self, language: Language) -> String {
+        let id = format!("lesson-type-{}", self.as_str().replace('_', "-"));
+        locale::global().get(language, &id)
+    }
+}
+
+impl fmt::Display for LessonType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Lesson {
+    pub subject_name: String,
+    pub lesson_type: LessonType,
+    pub time: NaiveTime,
+    pub teacher_name: String,
+    pub info: String,
+    pub room: String,
+}
+
+impl Localize for Lesson {
+    fn localize(&self, language: Language) -> String {
+        let messages = locale::global();
+        let mut text = messages.format(
+            language,
+            "lesson-heading",
+            vec![
+                ("subject", self.subject_name.as_str().into()),
+                ("type", self.lesson_type.localize(language).into()),
+                ("time", self.time.format("%R").to_string().into()),
+            ],
+        );
+        text.push('\n');
+        text += &messages.format(
+            language,
+            "lesson-teacher",
+            vec![("teacher", self.teacher_name.as_str().into())],
+        );
+        text.push('\n');
+        text += &self.info;
+        if !self.room.is_empty() {
+            text.push('\n');
+            text += &messages.format(
+                language,
+                "lesson-room",
+                vec![("room", self.room.as_str().into())],
+            );
+        }
+        text
+    }
+}
+
+impl fmt::Display for Lesson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Lessons(pub Vec<Lesson>);
+
+impl Localize for Lessons {
+    fn localize(&self, language: Language) -> String {
+        if self.0.is_empty() {
+            locale::global().get(language, "day-off")
+        } else {
+            self.0
+                .iter()
+                .map(|lesson| format!("\n{}\n", lesson.localize(language)))
+                .collect()
+        }
+    }
+}
+
+impl fmt::Display for Lessons {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct LessonDay {
+    #[sqlx(rename = "day_of_week")]
+    pub day: Day,
+    pub subject_name: String,
+    pub lesson_type: LessonType,
+    pub time: NaiveTime,
+    pub teacher_name: String,
+    pub info: String,
+    pub room: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LessonsWeek(pub BTreeMap<Day, Lessons>);
+
+impl Localize for LessonsWeek {
+    fn localize(&self, language: Language) -> String {
+        if self.0.is_empty() {
+            return locale::global().get(language, "week-off");
+        }
+        self.0
+            .iter()
+            .map(|(day, lessons)| {
+                format!(
+                    "{}\n{}\n",
+                    day.localize(language),
+                    lessons.localize(language)
+                )
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for LessonsWeek {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+/// Row of the `schedule` table together with the groups it is taught to.
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LessonEntry {
+    pub id: i32,
+    pub subject: i32,
+    pub lesson_type: LessonType,
+    pub teacher: Option<i32>,
+    #[sqlx(rename = "day_of_week")]
+    pub day: Day,
+    pub time: NaiveTime,
+    pub distribution: Distribution,
+    pub info: String,
+    pub room: String,
+    pub groups: Vec<i32>,
+}
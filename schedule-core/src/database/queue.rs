@@ -0,0 +1,150 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::database::Database;
+
+/// A laboratory work of the timetable students queue to defend at, at its next start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabLesson {
+    /// Id of the lesson in the `schedule` table.
+    pub id: i32,
+    pub subject_name: String,
+    pub room: String,
+    pub starts_at: NaiveDateTime,
+}
+
+/// A student in a queue, under the name they had in Telegram when joining.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QueueEntry {
+    pub telegram_id: i64,
+    pub name: String,
+    /// Called by the teacher and not yet marked done.
+    pub called: bool,
+}
+
+/// Queues for defending laboratory works, stored in the `lab_queue` table. A queue belongs to the
+/// occurrence of the lesson on `date`, the date of [`LabLesson::starts_at`].
+impl Database {
+    /// Laboratory works of the student, soonest first; none for unknown users.
+    pub async fn get_lab_lessons(&self, user_id: i64) -> Result<Vec<LabLesson>> {
+        let now = Local::now().naive_local();
+        let start = self.get_distribution_start().await?;
+        Ok(match self.student_rows(user_id).await? {
+            Some((group, electives, rows)) => rows.student_labs(group, &electives, start, now),
+            None => vec![],
+        })
+    }
+
+    /// Laboratory works of the teacher, soonest first.
+    pub async fn get_taught_lab_lessons(&self, teacher_id: i32) -> Result<Vec<LabLesson>> {
+        let now = Local::now().naive_local();
+        let start = self.get_distribution_start().await?;
+        let rows = self
+            .timetable_rows("schedule.teacher = $1", teacher_id)
+            .await?;
+        Ok(rows.teacher_labs(teacher_id, start, now))
+    }
+
+    /// The queue of the lesson: called students in the order called, then the waiting ones in
+    /// the order they joined.
+    pub async fn get_queue(&self, lesson: i32, date: NaiveDate) -> Result<Vec<QueueEntry>> {
+        sqlx::query_as::<_, QueueEntry>(
+            r#"
+            SELECT telegram_id, name, called_at IS NOT NULL AS called FROM lab_queue
+            WHERE lesson = $1 AND date = $2
+            ORDER BY called_at IS NULL, called_at, id
+            "#,
+        )
+        .bind(lesson)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    /// Puts the user at the end of the queue; `false` if they are in it already. Queues of the
+    /// lesson's earlier occurrences are over and removed.
+    pub async fn join_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+        name: &str,
+    ) -> Result<bool> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM lab_queue WHERE lesson = $1 AND date < $2")
+            .bind(lesson)
+            .bind(date)
+            .execute(&mut transaction)
+            .await?;
+        let joined = sqlx::query(
+            r#"
+            INSERT INTO lab_queue(lesson, date, telegram_id, name) VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(lesson)
+        .bind(date)
+        .bind(user_id)
+        .bind(name)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected()
+            > 0;
+        transaction.commit().await?;
+        Ok(joined)
+    }
+
+    /// Takes the user out of the queue; `false` if they weren't in it.
+    pub async fn leave_queue(&self, lesson: i32, date: NaiveDate, user_id: i64) -> Result<bool> {
+        sqlx::query("DELETE FROM lab_queue WHERE lesson = $1 AND date = $2 AND telegram_id = $3")
+            .bind(lesson)
+            .bind(date)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|result| result.rows_affected() > 0)
+    }
+
+    /// Calls the first waiting student, if any.
+    pub async fn call_next(&self, lesson: i32, date: NaiveDate) -> Result<Option<i64>> {
+        sqlx::query(
+            r#"
+            UPDATE lab_queue SET called_at = clock_timestamp()
+            WHERE id = (
+                SELECT id FROM lab_queue WHERE lesson = $1 AND date = $2 AND called_at IS NULL
+                ORDER BY id LIMIT 1 FOR UPDATE
+            )
+            RETURNING telegram_id
+            "#,
+        )
+        .bind(lesson)
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|row| row.map(|row| row.get(0)))
+    }
+
+    /// Takes the called students out of the queue as done, returning them in ascending order.
+    pub async fn finish_called(&self, lesson: i32, date: NaiveDate) -> Result<Vec<i64>> {
+        let mut done: Vec<i64> = sqlx::query(
+            r#"
+            DELETE FROM lab_queue WHERE lesson = $1 AND date = $2 AND called_at IS NOT NULL
+            RETURNING telegram_id
+            "#,
+        )
+        .bind(lesson)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+        done.sort_unstable();
+        Ok(done)
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::database::Database;
+
+/// What a bot user may do besides reading their own timetable.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "role")]
+#[sqlx(rename_all = "snake_case")]
+pub enum Role {
+    /// Grants roles and may do anything the others may.
+    Admin,
+    /// Starosta of their group.
+    GroupLeader,
+    Teacher,
+    Student,
+}
+
+impl Role {
+    pub fn all() -> [Role; 4] {
+        [Role::Admin, Role::GroupLeader, Role::Teacher, Role::Student]
+    }
+
+    /// Name of the role as stored in the database and typed after `/grant`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::GroupLeader => "group_leader",
+            Role::Teacher => "teacher",
+            Role::Student => "student",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::all()
+            .iter()
+            .copied()
+            .find(|role| role.as_str() == s)
+            .ok_or("Expected admin, group_leader, teacher or student")
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Roles of bot users, stored in the `user_roles` table.
+impl Database {
+    /// Roles granted to the user, and `Student` for a registered student, in order.
+    pub async fn get_roles(&self, user_id: i64) -> Result<Vec<Role>> {
+        sqlx::query(
+            r#"
+            SELECT role FROM user_roles WHERE telegram_id = $1
+            UNION
+            SELECT 'student'::role FROM students WHERE telegram_id = $1
+            ORDER BY 1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|rows| rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    pub async fn grant_role(&self, user_id: i64, role: Role) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO user_roles(telegram_id, role) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|_| ())
+    }
+
+    /// Takes the role away; `false` if the user didn't have it.
+    pub async fn revoke_role(&self, user_id: i64, role: Role) -> Result<bool> {
+        sqlx::query("DELETE FROM user_roles WHERE telegram_id = $1 AND role = $2")
+            .bind(user_id)
+            .bind(role)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|result| result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+
+use crate::database::{token, Database};
+
+/// Telegram user logged in to the web application.
+#[derive(sqlx::FromRow)]
+pub struct Session {
+    pub telegram_id: i64,
+    pub name: String,
+    pub username: Option<String>,
+}
+
+impl Database {
+    /// Starts a session of 30 days, removing the expired ones.
+    pub async fn create_session(
+        &self,
+        telegram_id: i64,
+        name: &str,
+        username: Option<&str>,
+    ) -> Result<String> {
+        sqlx::query("DELETE FROM sessions WHERE expires < now()")
+            .execute(&self.pool)
+            .await?;
+        let token = token()?;
+        sqlx::query(
+            r#"
+            INSERT INTO sessions(token, telegram_id, name, username, expires)
+            VALUES ($1, $2, $3, $4, now() + INTERVAL '30 days')
+            "#,
+        )
+        .bind(&token)
+        .bind(telegram_id)
+        .bind(name)
+        .bind(username)
+        .execute(&self.pool)
+        .await?;
+        Ok(token)
+    }
+
+    pub async fn get_session(&self, token: &str) -> Result<Option<Session>> {
+        sqlx::query_as::<_, Session>(
+            r#"
+            SELECT telegram_id, COALESCE(name, '') AS name, username FROM sessions
+            WHERE token = $1 AND expires >= now()
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+    }
+
+    pub async fn delete_session(&self, token: &str) -> Result<()> {
+        sqlx::query("DELETE FROM sessions WHERE token = $1")
+            .bind(token)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|_| ())
+    }
+}
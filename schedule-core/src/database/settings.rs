@@ -0,0 +1,166 @@
+use anyhow::Result;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row};
+use std::str::FromStr;
+
+use crate::database::Database;
+use crate::locale::Language;
+
+/// How much of a lesson the bot shows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "lesson_format")]
+#[sqlx(rename_all = "lowercase")]
+pub enum LessonFormat {
+    /// A line per lesson: time, subject, type, room and teacher.
+    Compact,
+    /// A line per field, leaving out the empty ones.
+    #[default]
+    Standard,
+    /// A line per field, the empty ones included.
+    Verbose,
+}
+
+impl LessonFormat {
+    pub fn all() -> [LessonFormat; 3] {
+        [
+            LessonFormat::Compact,
+            LessonFormat::Standard,
+            LessonFormat::Verbose,
+        ]
+    }
+
+    /// Name of the format as stored in the database and typed after a command.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LessonFormat::Compact => "compact",
+            LessonFormat::Standard => "standard",
+            LessonFormat::Verbose => "verbose",
+        }
+    }
+}
+
+impl FromStr for LessonFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LessonFormat::all()
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == s)
+            .ok_or("Expected compact, standard or verbose")
+    }
+}
+
+/// Preferences of a bot user, chosen with `/settings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserSettings {
+    /// `None` until chosen; the language of the user's Telegram client is used then.
+    pub language: Option<Language>,
+    /// How long before a lesson to remind of it; `None` for no reminders.
+    pub reminder_minutes: Option<i32>,
+    /// When to send the schedule of the next day; `None` for no digest.
+    pub digest_time: Option<NaiveTime>,
+    pub lesson_format: LessonFormat,
+    pub show_teacher: bool,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings {
+            language: None,
+            reminder_minutes: None,
+            digest_time: None,
+            lesson_format: LessonFormat::default(),
+            show_teacher: true,
+        }
+    }
+}
+
+/// Preferences of bot users, stored in the `user_settings` table.
+impl Database {
+    /// Language chosen with `/lang`, if any.
+    pub async fn get_language(&self, user_id: i64) -> Result<Option<Language>> {
+        sqlx::query("SELECT language FROM user_settings WHERE telegram_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))
+            .map(|row| row.and_then(|row| row.get(0)))
+    }
+
+    pub async fn set_language(&self, user_id: i64, language: Language) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_settings(telegram_id, language) VALUES ($1, $2)
+            ON CONFLICT(telegram_id) DO UPDATE SET language = excluded.language
+            "#,
+        )
+        .bind(user_id)
+        .bind(language)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|_| ())
+    }
+
+    /// Settings of the user, the defaults for one who hasn't changed any.
+    pub async fn get_settings(&self, user_id: i64) -> Result<UserSettings> {
+        sqlx::query_as::<_, UserSettings>(
+            r#"
+            SELECT language, reminder_minutes, digest_time, lesson_format, show_teacher
+            FROM user_settings WHERE telegram_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(Option::unwrap_or_default)
+    }
+
+    /// Students who chose reminders or a digest, with their settings.
+    pub async fn get_subscribers(&self) -> Result<Vec<(i64, UserSettings)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT telegram_id, language, reminder_minutes, digest_time, lesson_format, show_teacher
+            FROM user_settings
+            WHERE (reminder_minutes IS NOT NULL OR digest_time IS NOT NULL)
+              AND telegram_id IN (SELECT telegram_id FROM students)
+            ORDER BY telegram_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| Ok((row.get(0), UserSettings::from_row(row)?)))
+            .collect()
+    }
+
+    pub async fn set_settings(&self, user_id: i64, settings: &UserSettings) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_settings(
+                telegram_id, language, reminder_minutes, digest_time, lesson_format, show_teacher
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT(telegram_id) DO UPDATE SET
+                language = excluded.language,
+                reminder_minutes = excluded.reminder_minutes,
+                digest_time = excluded.digest_time,
+                lesson_format = excluded.lesson_format,
+                show_teacher = excluded.show_teacher
+            "#,
+        )
+        .bind(user_id)
+        .bind(settings.language)
+        .bind(settings.reminder_minutes)
+        .bind(settings.digest_time)
+        .bind(settings.lesson_format)
+        .bind(settings.show_teacher)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))
+        .map(|_| ())
+    }
+}
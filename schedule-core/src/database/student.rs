@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Student {
+    pub id: i32,
+    pub telegram_id: i64,
+    pub group: Option<i32>,
+    pub last_name: String,
+}
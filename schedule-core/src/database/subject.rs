@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Subject {
+    pub id: i32,
+    pub name: String,
+    /// Elective subjects are shown only to the students enrolled in them.
+    pub choice: bool,
+    pub info: Option<String>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Teacher {
+    pub id: i32,
+    pub last_name: String,
+    pub first_name: String,
+    pub patronymic_name: String,
+    pub telegram: Option<String>,
+    pub email: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+impl Teacher {
+    pub fn full_name(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.last_name, self.first_name, self.patronymic_name
+        )
+        .trim_end()
+        .to_string()
+    }
+}
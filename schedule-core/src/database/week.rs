@@ -0,0 +1,126 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::{self, Language, Localize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, sqlx::Type)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "distribution_week")]
+#[sqlx(rename_all = "lowercase")]
+pub enum WeekType {
+    First,
+    Second,
+}
+
+impl WeekType {
+    pub fn all() -> [WeekType; 2] {
+        [WeekType::First, WeekType::Second]
+    }
+
+    /// Week of the given date, counting from the Monday of a first week.
+    pub fn at(distribution_start: NaiveDate, date: NaiveDate) -> Self {
+        if (date - distribution_start).num_days() / 7 % 2 == 0 {
+            WeekType::First
+        } else {
+            WeekType::Second
+        }
+    }
+
+    /// The week after this one.
+    pub fn next(&self) -> Self {
+        match self {
+            WeekType::First => WeekType::Second,
+            WeekType::Second => WeekType::First,
+        }
+    }
+
+    /// Offset in weeks from the start of the distribution.
+    pub fn offset(&self) -> i64 {
+        match self {
+            WeekType::First => 0,
+            WeekType::Second => 1,
+        }
+    }
+}
+
+impl FromStr for WeekType {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match s {
+            "1" => Ok(WeekType::First),
+            "2" => Ok(WeekType::Second),
+            _ => Err("Введите 1 или 2 после команды"),
+        }
+    }
+}
+
+impl Localize for WeekType {
+    fn localize(&self, language: Language) -> String {
+        let id = match self {
+            WeekType::First => "week-first",
+            WeekType::Second => "week-second",
+        };
+        locale::global().get(language, id)
+    }
+}
+
+impl fmt::Display for WeekType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+/// Weeks on which a lesson takes place.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "distribution_week")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Distribution {
+    First,
+    Second,
+    All,
+}
+
+impl Distribution {
+    pub fn all() -> [Distribution; 3] {
+        [Distribution::All, Distribution::First, Distribution::Second]
+    }
+
+    /// Name of the distribution as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Distribution::First => "first",
+            Distribution::Second => "second",
+            Distribution::All => "all",
+        }
+    }
+
+    pub fn includes(&self, week: WeekType) -> bool {
+        matches!(
+            (self, week),
+            (Distribution::All, _)
+                | (Distribution::First, WeekType::First)
+                | (Distribution::Second, WeekType::Second)
+        )
+    }
+
+    pub fn overlaps(&self, other: Distribution) -> bool {
+        *self == Distribution::All || other == Distribution::All || *self == other
+    }
+}
+
+impl Localize for Distribution {
+    fn localize(&self, language: Language) -> String {
+        locale::global().get(language, &format!("distribution-{}", self.as_str()))
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
@@ -0,0 +1,371 @@
+//! Complete timetable in a stable JSON/YAML interchange format, used for backups and for
+//! moving a semester between databases.
+//!
+//! ```yaml
+//! version: 1
+//! groups:
+//!   - { id: 1, name: ФІ-91 }
+//! subjects:
+//!   - { id: 1, name: Матан, choice: false, info: null }
+//! teachers:
+//!   - { id: 1, last_name: Іванов, first_name: Іван, patronymic_name: Іванович,
+//!       telegram: null, email: null, phone_number: null, telegram_id: 123456789 }
+//! lessons:
+//!   - { id: 1, subject: 1, lesson_type: lecture, teacher: 1, day: monday, time: "08:30:00",
+//!       distribution: all, info: "", room: "305", groups: [1] }
+//! ```
+//!
+//! Lessons refer to groups, subjects and teachers by the ids used in the same file, and every
+//! row keeps its id so that an export imported into an empty database exports back unchanged.
+//! `telegram_id` is the Telegram user linked to the teacher with `/link_teacher`; a teacher
+//! without it keeps the link they have in the database.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, Row, Transaction};
+use std::path::Path;
+
+use crate::database::admin::LESSON_ENTRY_QUERY;
+use crate::database::group::Group;
+use crate::database::lesson::LessonEntry;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::Database;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimetableExport {
+    pub version: u32,
+    pub groups: Vec<Group>,
+    pub subjects: Vec<Subject>,
+    pub teachers: Vec<ExportTeacher>,
+    pub lessons: Vec<LessonEntry>,
+}
+
+/// A teacher with the Telegram user linked to them, which the API doesn't show.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportTeacher {
+    #[serde(flatten)]
+    pub teacher: Teacher,
+    #[serde(default)]
+    pub telegram_id: Option<i64>,
+}
+
+impl TimetableExport {
+    pub fn serialize(&self, format: Format) -> Result<String> {
+        match format {
+            Format::Json => {
+                serde_json::to_string_pretty(self).map_err(|error| anyhow::anyhow!(error))
+            }
+            Format::Yaml => serde_yaml::to_string(self).map_err(|error| anyhow::anyhow!(error)),
+        }
+    }
+
+    /// Parses and validates an export.
+    pub fn parse(data: &str, format: Format) -> Result<Self> {
+        let export: TimetableExport = match format {
+            Format::Json => serde_json::from_str(data)?,
+            Format::Yaml => serde_yaml::from_str(data)?,
+        };
+        export.validate()?;
+        Ok(export)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let format = Format::from_path(path)
+            .ok_or_else(|| anyhow::anyhow!("Ожидается файл .json, .yaml или .yml"))?;
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        Self::parse(&data, format)
+    }
+
+    /// Checks the format version and that lessons refer only to rows present in the export.
+    pub fn validate(&self) -> Result<()> {
+        if self.version != FORMAT_VERSION {
+            return Err(anyhow::anyhow!(
+                "Неподдерживаемая версия формата: {}",
+                self.version
+            ));
+        }
+        for lesson in &self.lessons {
+            if !self
+                .subjects
+                .iter()
+                .any(|subject| subject.id == lesson.subject)
+            {
+                return Err(anyhow::anyhow!(
+                    "Пара {} ссылается на неизвестный предмет {}",
+                    lesson.id,
+                    lesson.subject
+                ));
+            }
+            if let Some(teacher) = lesson.teacher {
+                if !self
+                    .teachers
+                    .iter()
+                    .any(|other| other.teacher.id == teacher)
+                {
+                    return Err(anyhow::anyhow!(
+                        "Пара {} ссылается на неизвестного преподавателя {}",
+                        lesson.id,
+                        teacher
+                    ));
+                }
+            }
+            if let Some(group) = lesson
+                .groups
+                .iter()
+                .find(|group| !self.groups.iter().any(|other| other.id == **group))
+            {
+                return Err(anyhow::anyhow!(
+                    "Пара {} ссылается на неизвестную группу {}",
+                    lesson.id,
+                    group
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Database {
+    pub async fn export_timetable(&self) -> Result<TimetableExport> {
+        let groups = sqlx::query_as::<_, Group>(
+            "SELECT id, COALESCE(name, '') AS name FROM groups ORDER BY id",
+        )
+        .fetch_all(self.pool())
+        .await?;
+        let subjects = sqlx::query_as::<_, Subject>(
+            r#"
+            SELECT id, COALESCE(name, '') AS name, COALESCE(choice, FALSE) AS choice, info
+            FROM subjects ORDER BY id
+            "#,
+        )
+        .fetch_all(self.pool())
+        .await?;
+        let teachers = sqlx::query(
+            r#"
+            SELECT id, COALESCE(last_name, '') AS last_name, COALESCE(first_name, '') AS first_name,
+                   COALESCE(patronymic_name, '') AS patronymic_name, telegram, email, phone_number,
+                   telegram_id
+            FROM teachers ORDER BY id
+            "#,
+        )
+        .fetch_all(self.pool())
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(ExportTeacher {
+                teacher: Teacher::from_row(row)?,
+                telegram_id: row.get("telegram_id"),
+            })
+        })
+        .collect::<Result<_>>()?;
+        let lessons = sqlx::query_as::<_, LessonEntry>(&format!(
+            "{} GROUP BY schedule.id ORDER BY schedule.id",
+            LESSON_ENTRY_QUERY
+        ))
+        .fetch_all(self.pool())
+        .await?;
+        Ok(TimetableExport {
+            version: FORMAT_VERSION,
+            groups,
+            subjects,
+            teachers,
+            lessons,
+        })
+    }
+
+    /// Replaces all lessons with those of the export and upserts its groups, subjects and
+    /// teachers by id. Lessons are upserted too, so the lab queues of the lessons kept survive;
+    /// rows missing from the export other than lessons are kept, except teachers
+    /// with the name of an exported teacher. Those are returned, and their Telegram link moves
+    /// to the exported teacher unless the export links it.
+    pub async fn restore_timetable(
+        &self,
+        export: &TimetableExport,
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        export.validate()?;
+        let mut transaction = self.pool().begin().await?;
+        let lessons: Vec<i32> = export.lessons.iter().map(|lesson| lesson.id).collect();
+        sqlx::query("DELETE FROM lessons_groups")
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query("DELETE FROM schedule WHERE id <> ALL($1)")
+            .bind(&lessons)
+            .execute(&mut transaction)
+            .await?;
+        for group in &export.groups {
+            sqlx::query(
+                "INSERT INTO groups(id, name) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name",
+            )
+            .bind(group.id)
+            .bind(&group.name)
+            .execute(&mut transaction)
+            .await?;
+        }
+        for subject in &export.subjects {
+            sqlx::query(
+                r#"
+                INSERT INTO subjects(id, name, choice, info) VALUES ($1, $2, $3, $4)
+                ON CONFLICT (id) DO UPDATE
+                SET name = EXCLUDED.name, choice = EXCLUDED.choice, info = EXCLUDED.info
+                "#,
+            )
+            .bind(subject.id)
+            .bind(&subject.name)
+            .bind(subject.choice)
+            .bind(&subject.info)
+            .execute(&mut transaction)
+            .await?;
+        }
+        let mut replaced = vec![];
+        for ExportTeacher {
+            teacher,
+            telegram_id,
+        } in &export.teachers
+        {
+            if telegram_id.is_some() {
+                sqlx::query(
+                    "UPDATE teachers SET telegram_id = NULL WHERE telegram_id = $1 AND id <> $2",
+                )
+                .bind(telegram_id)
+                .bind(teacher.id)
+                .execute(&mut transaction)
+                .await?;
+            }
+            sqlx::query(
+                r#"
+                UPDATE schedule SET teacher = NULL WHERE teacher IN (
+                    SELECT id FROM teachers
+                    WHERE id <> $1 AND last_name = $2 AND first_name = $3 AND patronymic_name = $4
+                )
+                "#,
+            )
+            .bind(teacher.id)
+            .bind(&teacher.last_name)
+            .bind(&teacher.first_name)
+            .bind(&teacher.patronymic_name)
+            .execute(&mut transaction)
+            .await?;
+            let same_name = sqlx::query(
+                r#"
+                DELETE FROM teachers
+                WHERE id <> $1 AND last_name = $2 AND first_name = $3 AND patronymic_name = $4
+                RETURNING id, telegram_id
+                "#,
+            )
+            .bind(teacher.id)
+            .bind(&teacher.last_name)
+            .bind(&teacher.first_name)
+            .bind(&teacher.patronymic_name)
+            .fetch_optional(&mut transaction)
+            .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO teachers(id, last_name, first_name, patronymic_name, telegram, email, phone_number, telegram_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (id) DO UPDATE
+                SET last_name = EXCLUDED.last_name, first_name = EXCLUDED.first_name,
+                    patronymic_name = EXCLUDED.patronymic_name, telegram = EXCLUDED.telegram,
+                    email = EXCLUDED.email, phone_number = EXCLUDED.phone_number,
+                    telegram_id = COALESCE(EXCLUDED.telegram_id, teachers.telegram_id)
+                "#,
+            )
+            .bind(teacher.id)
+            .bind(&teacher.last_name)
+            .bind(&teacher.first_name)
+            .bind(&teacher.patronymic_name)
+            .bind(&teacher.telegram)
+            .bind(&teacher.email)
+            .bind(&teacher.phone_number)
+            .bind(telegram_id)
+            .execute(&mut transaction)
+            .await?;
+            if let Some(row) = same_name {
+                let id: i32 = row.get(0);
+                sqlx::query(
+                    "UPDATE teachers SET telegram_id = COALESCE(telegram_id, $2) WHERE id = $1",
+                )
+                .bind(teacher.id)
+                .bind(row.get::<Option<i64>, _>(1))
+                .execute(&mut transaction)
+                .await?;
+                replaced.push(format!(
+                    "{} (id {} → {})",
+                    teacher.full_name(),
+                    id,
+                    teacher.id
+                ));
+            }
+        }
+
+        for lesson in &export.lessons {
+            sqlx::query(
+                r#"
+                INSERT INTO schedule(id, subject, type, teacher, day_of_week, time, distribution, info, room)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (id) DO UPDATE
+                SET subject = EXCLUDED.subject, type = EXCLUDED.type, teacher = EXCLUDED.teacher,
+                    day_of_week = EXCLUDED.day_of_week, time = EXCLUDED.time,
+                    distribution = EXCLUDED.distribution, info = EXCLUDED.info, room = EXCLUDED.room
+                "#,
+            )
+            .bind(lesson.id)
+            .bind(lesson.subject)
+            .bind(lesson.lesson_type)
+            .bind(lesson.teacher)
+            .bind(lesson.day)
+            .bind(lesson.time)
+            .bind(lesson.distribution)
+            .bind(&lesson.info)
+            .bind(&lesson.room)
+            .execute(&mut transaction)
+            .await?;
+            for group in &lesson.groups {
+                sqlx::query(r#"INSERT INTO lessons_groups(lesson, "group") VALUES ($1, $2)"#)
+                    .bind(lesson.id)
+                    .bind(group)
+                    .execute(&mut transaction)
+                    .await?;
+            }
+        }
+        for table in ["groups", "subjects", "teachers", "schedule"].iter() {
+            reset_sequence(&mut transaction, table).await?;
+        }
+
+        if !dry_run {
+            transaction.commit().await?;
+        }
+        Ok(replaced)
+    }
+}
+
+/// Moves the id sequence past the ids inserted explicitly.
+async fn reset_sequence(transaction: &mut Transaction<'_, Postgres>, table: &str) -> Result<()> {
+    sqlx::query(&format!(
+        "SELECT setval(pg_get_serial_sequence('{0}', 'id'), COALESCE((SELECT max(id) FROM {0}), 0) + 1, false)",
+        table
+    ))
+    .execute(&mut *transaction)
+    .await?;
+    Ok(())
+}
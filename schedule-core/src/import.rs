@@ -0,0 +1,624 @@
+//! Bulk import of a timetable from a CSV or XLSX spreadsheet, or from the pages of the
+//! university's published timetable (see [`rozklad`]).
+//!
+//! The first row of the file (or of the first worksheet) is a header naming the columns,
+//! in any order:
+//!
+//! | column         | required | value                                                             |
+//! |----------------|----------|-------------------------------------------------------------------|
+//! | `groups`       | yes      | group names separated by `,` or `;`                               |
+//! | `day`          | yes      | `monday`…`sunday`, or the short or full name in any bot language  |
+//! | `time`         | yes      | start of the lesson, `HH:MM`                                      |
+//! | `subject`      | yes      | subject name                                                      |
+//! | `type`         | yes      | `lecture`, `practice`, `laboratory_work` or the name in any bot language |
+//! | `teacher`      | no       | `Прізвище Ім'я По-батькові`                                       |
+//! | `distribution` | no       | `all`, `first`, `second`, `1`, `2` or the name in any bot language; all weeks if empty |
+//! | `room`         | no       | room of the lesson                                                |
+//! | `info`         | no       | free-form note                                                    |
+//! | `choice`       | no       | `1`/`true`/`да` for elective subjects, used when the subject is created |
+//!
+//! The file is the complete timetable of every group it mentions: lessons of these groups that
+//! are missing from the file are removed, identical ones are kept and the rest are added.
+//! Groups, subjects and teachers are matched by name (teachers by their full name triple) and
+//! created when missing.
+
+use anyhow::{Context, Result};
+use calamine::{Reader, Xlsx};
+use chrono::NaiveTime;
+use sqlx::{Postgres, Row, Transaction};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::database::admin::{insert_lesson_entry, update_lesson_entry, LESSON_ENTRY_QUERY};
+use crate::database::day::Day;
+use crate::database::lesson::{LessonEntry, LessonType};
+use crate::database::week::Distribution;
+use crate::database::Database;
+use crate::locale::{Language, Localize};
+
+pub mod rozklad;
+
+const REQUIRED_COLUMNS: [&str; 5] = ["groups", "day", "time", "subject", "type"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TeacherName {
+    pub last_name: String,
+    pub first_name: String,
+    pub patronymic_name: String,
+}
+
+impl fmt::Display for TeacherName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.last_name, self.first_name)?;
+        if !self.patronymic_name.is_empty() {
+            write!(f, " {}", self.patronymic_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lesson read from the spreadsheet, referring to everything by name.
+#[derive(Debug, Clone)]
+pub struct ImportLesson {
+    pub subject: String,
+    pub choice: bool,
+    pub lesson_type: LessonType,
+    pub teacher: Option<TeacherName>,
+    pub day: Day,
+    pub time: NaiveTime,
+    pub distribution: Distribution,
+    pub room: String,
+    pub info: String,
+    pub groups: BTreeSet<String>,
+}
+
+impl ImportLesson {
+    fn same_lesson(&self, other: &ImportLesson) -> bool {
+        self.subject == other.subject
+            && self.lesson_type == other.lesson_type
+            && self.teacher == other.teacher
+            && self.day == other.day
+            && self.time == other.time
+            && self.distribution == other.distribution
+            && self.room == other.room
+            && self.info == other.info
+    }
+}
+
+pub struct Timetable {
+    pub lessons: Vec<ImportLesson>,
+}
+
+impl Timetable {
+    /// Reads a `.csv`, `.xlsx` or `.html` file depending on its extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        let name = path.to_string_lossy();
+        Self::from_named(&name, &data)
+    }
+
+    /// Reads an uploaded file, choosing the format by its name.
+    pub fn from_named(name: &str, data: &[u8]) -> Result<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".xlsx") {
+            Self::from_xlsx(data)
+        } else if name.ends_with(".csv") {
+            Self::from_csv(data)
+        } else if name.ends_with(".html") || name.ends_with(".htm") {
+            Self::from_html(&String::from_utf8_lossy(data))
+        } else {
+            Err(anyhow::anyhow!(
+                "Неизвестный формат файла, ожидается .csv, .xlsx или .html"
+            ))
+        }
+    }
+
+    pub fn from_csv(data: &[u8]) -> Result<Self> {
+        let data = data.strip_prefix("\u{feff}".as_bytes()).unwrap_or(data);
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(data);
+        let records = reader
+            .records()
+            .map(|record| {
+                record
+                    .map(|record| record.iter().map(ToString::to_string).collect())
+                    .map_err(|error| anyhow::anyhow!(error))
+            })
+            .collect::<Result<Vec<Vec<String>>>>()?;
+        Self::from_records(records)
+    }
+
+    /// Reads the first worksheet of the workbook.
+    pub fn from_xlsx(data: &[u8]) -> Result<Self> {
+        let mut workbook = Xlsx::new(Cursor::new(data))?;
+        let range = workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| anyhow::anyhow!("В книге нет листов"))??;
+        let records = range
+            .rows()
+            .map(|row| row.iter().map(cell_to_string).collect())
+            .collect();
+        Self::from_records(records)
+    }
+
+    fn from_records(records: Vec<Vec<String>>) -> Result<Self> {
+        let mut records = records.into_iter().enumerate();
+        let header: HashMap<String, usize> = match records.next() {
+            Some((_, header)) => header
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.trim().to_lowercase(), index))
+                .collect(),
+            None => return Err(anyhow::anyhow!("Файл пуст")),
+        };
+        if let Some(column) = REQUIRED_COLUMNS
+            .iter()
+            .find(|column| !header.contains_key(**column))
+        {
+            return Err(anyhow::anyhow!("Нет обязательного столбца {}", column));
+        }
+
+        let mut timetable = Timetable { lessons: vec![] };
+        for (index, record) in records {
+            if record.iter().all(|value| value.trim().is_empty()) {
+                continue;
+            }
+            let field = |name: &str| -> &str {
+                header
+                    .get(name)
+                    .and_then(|column| record.get(*column))
+                    .map(|value| value.trim())
+                    .unwrap_or_default()
+            };
+            let lesson = parse_lesson(&field).with_context(|| format!("Строка {}", index + 1))?;
+            timetable.push(lesson);
+        }
+        Ok(timetable)
+    }
+
+    /// Adds a lesson, merging it with an identical one taught to other groups.
+    pub fn push(&mut self, lesson: ImportLesson) {
+        match self
+            .lessons
+            .iter_mut()
+            .find(|other| other.same_lesson(&lesson))
+        {
+            Some(other) => other.groups.extend(lesson.groups),
+            None => self.lessons.push(lesson),
+        }
+    }
+
+    /// Combines timetables read from several files.
+    pub fn extend(&mut self, other: Timetable) {
+        for lesson in other.lessons {
+            self.push(lesson);
+        }
+    }
+
+    fn groups(&self) -> BTreeSet<&str> {
+        self.lessons
+            .iter()
+            .flat_map(|lesson| lesson.groups.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+fn parse_lesson<'a>(field: &impl Fn(&str) -> &'a str) -> Result<ImportLesson> {
+    let groups: BTreeSet<String> = field("groups")
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|group| !group.is_empty())
+        .map(ToString::to_string)
+        .collect();
+    if groups.is_empty() {
+        return Err(anyhow::anyhow!("Не указаны группы"));
+    }
+    let subject = field("subject");
+    if subject.is_empty() {
+        return Err(anyhow::anyhow!("Не указан предмет"));
+    }
+    let time = field("time");
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .map_err(|_| anyhow::anyhow!("Неверное время: {}", time))?;
+
+    Ok(ImportLesson {
+        subject: subject.to_string(),
+        choice: matches!(
+            field("choice").to_lowercase().as_str(),
+            "1" | "true" | "да" | "так"
+        ),
+        lesson_type: parse_lesson_type(field("type"))?,
+        teacher: parse_teacher(field("teacher"))?,
+        day: parse_day(field("day"))?,
+        time,
+        distribution: parse_distribution(field("distribution"))?,
+        room: field("room").to_string(),
+        info: field("info").to_string(),
+        groups,
+    })
+}
+
+fn parse_day(value: &str) -> Result<Day> {
+    if let Ok(day) = value.parse() {
+        return Ok(day);
+    }
+    let value = value.to_lowercase();
+    Day::all()
+        .iter()
+        .copied()
+        .find(|day| is_named(day, day.as_str(), &value))
+        .ok_or_else(|| anyhow::anyhow!("Неверный день недели: {}", value))
+}
+
+fn parse_lesson_type(value: &str) -> Result<LessonType> {
+    let value = value.to_lowercase();
+    LessonType::all()
+        .iter()
+        .copied()
+        .find(|lesson_type| is_named(lesson_type, lesson_type.as_str(), &value))
+        .ok_or_else(|| anyhow::anyhow!("Неверный тип занятия: {}", value))
+}
+
+fn parse_distribution(value: &str) -> Result<Distribution> {
+    let value = value.to_lowercase();
+    match value.as_str() {
+        "" => return Ok(Distribution::All),
+        "1" => return Ok(Distribution::First),
+        "2" => return Ok(Distribution::Second),
+        _ => {}
+    }
+    Distribution::all()
+        .iter()
+        .copied()
+        .find(|distribution| is_named(distribution, distribution.as_str(), &value))
+        .ok_or_else(|| anyhow::anyhow!("Неверная неделя: {}", value))
+}
+
+/// Whether the lowercase `value` is the stored name of `item` or its name in any language.
+fn is_named(item: &impl Localize, stored: &str, value: &str) -> bool {
+    stored == value
+        || Language::all()
+            .iter()
+            .any(|language| item.localize(*language).to_lowercase() == value)
+}
+
+fn parse_teacher(value: &str) -> Result<Option<TeacherName>> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    match parts.as_slice() {
+        [] => Ok(None),
+        [last_name, first_name] => Ok(Some(TeacherName {
+            last_name: last_name.to_string(),
+            first_name: first_name.to_string(),
+            patronymic_name: String::new(),
+        })),
+        [last_name, first_name, patronymic_name] => Ok(Some(TeacherName {
+            last_name: last_name.to_string(),
+            first_name: first_name.to_string(),
+            patronymic_name: patronymic_name.to_string(),
+        })),
+        _ => Err(anyhow::anyhow!("Неверное имя преподавателя: {}", value)),
+    }
+}
+
+/// Times are stored by spreadsheets as a fraction of a day.
+fn cell_to_string(cell: &calamine::Data) -> String {
+    let fraction = match cell {
+        calamine::Data::DateTime(value) => value.as_f64(),
+        calamine::Data::Float(value) if *value > 0.0 && *value < 1.0 => *value,
+        cell => return cell.to_string(),
+    };
+    let minutes = (fraction.fract() * 24.0 * 60.0).round() as u32;
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Changes made (or, on a dry run, to be made) by an import.
+#[derive(Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub groups: Vec<String>,
+    pub subjects: Vec<String>,
+    pub teachers: Vec<String>,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl ImportReport {
+    /// Whether applying the import would add, change or remove any lesson.
+    pub fn has_changes(&self) -> bool {
+        !(self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty())
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.dry_run {
+            writeln!(f, "Пробный запуск, изменения не сохранены.")?;
+        }
+        let sections = [
+            ("Новые группы", '+', &self.groups),
+            ("Новые предметы", '+', &self.subjects),
+            ("Новые преподаватели", '+', &self.teachers),
+            ("Добавленные пары", '+', &self.added),
+            ("Изменённые пары", '~', &self.changed),
+            ("Удалённые пары", '-', &self.removed),
+        ];
+        for (title, sign, lines) in sections.iter() {
+            if !lines.is_empty() {
+                writeln!(f, "{} ({}):", title, lines.len())?;
+                lines
+                    .iter()
+                    .try_for_each(|line| writeln!(f, "  {} {}", sign, line))?;
+            }
+        }
+        writeln!(f, "Без изменений: {}", self.unchanged)
+    }
+}
+
+/// Names of the rows referenced by lessons, filled as the import goes.
+#[derive(Default)]
+struct Names {
+    groups: HashMap<String, i32>,
+    subjects: HashMap<String, i32>,
+    teachers: HashMap<TeacherName, i32>,
+}
+
+impl Names {
+    fn describe(&self, entry: &LessonEntry) -> String {
+        let name = |names: &HashMap<String, i32>, id: i32| {
+            names
+                .iter()
+                .find(|(_, other)| **other == id)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_default()
+        };
+        let mut description = format!(
+            "{}: {} {} {} ({})",
+            entry
+                .groups
+                .iter()
+                .map(|group| name(&self.groups, *group))
+                .collect::<Vec<_>>()
+                .join(", "),
+            entry.day,
+            entry.time.format("%H:%M"),
+            name(&self.subjects, entry.subject),
+            entry.lesson_type
+        );
+        if let Some(teacher) = entry.teacher.and_then(|teacher| {
+            self.teachers
+                .iter()
+                .find(|(_, other)| **other == teacher)
+                .map(|(name, _)| name)
+        }) {
+            description.push_str(&format!(", {}", teacher));
+        }
+        description.push_str(&format!(", неделя: {}", entry.distribution));
+        if !entry.room.is_empty() {
+            description.push_str(&format!(", ауд. {}", entry.room));
+        }
+        description
+    }
+}
+
+impl Database {
+    /// Replaces the timetable of the groups in `timetable`, rolling everything back on a dry run.
+    pub async fn import_timetable(
+        &self,
+        timetable: &Timetable,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let mut transaction = self.pool().begin().await?;
+        let mut report = ImportReport {
+            dry_run,
+            ..ImportReport::default()
+        };
+        let names = resolve_names(&mut transaction, timetable, &mut report).await?;
+
+        let affected: Vec<i32> = timetable
+            .groups()
+            .iter()
+            .map(|group| names.groups[*group])
+            .collect();
+        let existing = sqlx::query_as::<_, LessonEntry>(&format!(
+            r#"{}
+            WHERE EXISTS(SELECT * FROM lessons_groups WHERE lesson = schedule.id AND "group" = ANY($1))
+            GROUP BY schedule.id
+            ORDER BY schedule.day_of_week, schedule.time"#,
+            LESSON_ENTRY_QUERY
+        ))
+        .bind(&affected)
+        .fetch_all(&mut transaction)
+        .await?;
+
+        let mut imported: Vec<LessonEntry> = timetable
+            .lessons
+            .iter()
+            .map(|lesson| LessonEntry {
+                id: 0,
+                subject: names.subjects[&lesson.subject],
+                lesson_type: lesson.lesson_type,
+                teacher: lesson
+                    .teacher
+                    .as_ref()
+                    .map(|teacher| names.teachers[teacher]),
+                day: lesson.day,
+                time: lesson.time,
+                distribution: lesson.distribution,
+                info: lesson.info.clone(),
+                room: lesson.room.clone(),
+                groups: lesson
+                    .groups
+                    .iter()
+                    .map(|group| names.groups[group])
+                    .collect(),
+            })
+            .collect();
+
+        let mut unmatched = vec![];
+        for mut entry in existing {
+            let all_groups = entry.groups.clone();
+            entry.groups.retain(|group| affected.contains(group));
+            match imported.iter().position(|other| same_entry(&entry, other)) {
+                Some(index) => {
+                    imported.remove(index);
+                    report.unchanged += 1;
+                }
+                None => unmatched.push((entry, all_groups)),
+            }
+        }
+        for (entry, all_groups) in unmatched {
+            let owned = all_groups.iter().all(|group| affected.contains(group));
+            let replacement = imported
+                .iter()
+                .position(|other| owned && same_slot(&entry, other));
+            if let Some(index) = replacement {
+                let mut changed = imported.remove(index);
+                changed.id = entry.id;
+                update_lesson_entry(&mut transaction, &changed).await?;
+                report.changed.push(format!(
+                    "{}\n    → {}",
+                    names.describe(&entry),
+                    names.describe(&changed)
+                ));
+            } else if owned {
+                sqlx::query("DELETE FROM lessons_groups WHERE lesson = $1")
+                    .bind(entry.id)
+                    .execute(&mut transaction)
+                    .await?;
+                sqlx::query("DELETE FROM schedule WHERE id = $1")
+                    .bind(entry.id)
+                    .execute(&mut transaction)
+                    .await?;
+                report.removed.push(names.describe(&entry));
+            } else {
+                sqlx::query(
+                    r#"DELETE FROM lessons_groups WHERE lesson = $1 AND "group" = ANY($2)"#,
+                )
+                .bind(entry.id)
+                .bind(&affected)
+                .execute(&mut transaction)
+                .await?;
+                report.removed.push(names.describe(&entry));
+            }
+        }
+        for entry in &imported {
+            insert_lesson_entry(&mut transaction, entry).await?;
+            report.added.push(names.describe(entry));
+        }
+
+        if !dry_run {
+            transaction.commit().await?;
+        }
+        Ok(report)
+    }
+}
+
+/// Compares lessons ignoring their ids and the order of groups.
+fn same_entry(existing: &LessonEntry, imported: &LessonEntry) -> bool {
+    same_slot(existing, imported)
+        && existing.subject == imported.subject
+        && existing.lesson_type == imported.lesson_type
+        && existing.teacher == imported.teacher
+        && existing.info == imported.info
+        && existing.room == imported.room
+}
+
+/// Lessons of the same groups at the same time are treated as a change of one lesson.
+fn same_slot(existing: &LessonEntry, imported: &LessonEntry) -> bool {
+    let mut groups = existing.groups.clone();
+    groups.sort_unstable();
+    let mut other_groups = imported.groups.clone();
+    other_groups.sort_unstable();
+    existing.day == imported.day
+        && existing.time == imported.time
+        && existing.distribution == imported.distribution
+        && groups == other_groups
+}
+
+/// Looks up groups, subjects and teachers of the timetable, creating the missing ones.
+async fn resolve_names(
+    transaction: &mut Transaction<'_, Postgres>,
+    timetable: &Timetable,
+    report: &mut ImportReport,
+) -> Result<Names> {
+    let mut names = Names::default();
+    for row in sqlx::query("SELECT id, COALESCE(name, '') FROM groups")
+        .fetch_all(&mut *transaction)
+        .await?
+    {
+        names.groups.insert(row.get(1), row.get(0));
+    }
+    for row in sqlx::query("SELECT id, COALESCE(name, '') FROM subjects")
+        .fetch_all(&mut *transaction)
+        .await?
+    {
+        names.subjects.insert(row.get(1), row.get(0));
+    }
+    for row in sqlx::query(
+        r#"
+        SELECT id, COALESCE(last_name, ''), COALESCE(first_name, ''), COALESCE(patronymic_name, '')
+        FROM teachers
+        "#,
+    )
+    .fetch_all(&mut *transaction)
+    .await?
+    {
+        let teacher = TeacherName {
+            last_name: row.get(1),
+            first_name: row.get(2),
+            patronymic_name: row.get(3),
+        };
+        names.teachers.insert(teacher, row.get(0));
+    }
+
+    for group in timetable.groups() {
+        if !names.groups.contains_key(group) {
+            let id = sqlx::query("INSERT INTO groups(name) VALUES ($1) RETURNING id")
+                .bind(group)
+                .fetch_one(&mut *transaction)
+                .await?
+                .get(0);
+            names.groups.insert(group.to_string(), id);
+            report.groups.push(group.to_string());
+        }
+    }
+    for lesson in &timetable.lessons {
+        if !names.subjects.contains_key(&lesson.subject) {
+            let id = sqlx::query("INSERT INTO subjects(name, choice) VALUES ($1, $2) RETURNING id")
+                .bind(&lesson.subject)
+                .bind(lesson.choice)
+                .fetch_one(&mut *transaction)
+                .await?
+                .get(0);
+            names.subjects.insert(lesson.subject.clone(), id);
+            report.subjects.push(lesson.subject.clone());
+        }
+        if let Some(teacher) = &lesson.teacher {
+            if !names.teachers.contains_key(teacher) {
+                let id = sqlx::query(
+                    r#"
+                    INSERT INTO teachers(last_name, first_name, patronymic_name)
+                    VALUES ($1, $2, $3) RETURNING id
+                    "#,
+                )
+                .bind(&teacher.last_name)
+                .bind(&teacher.first_name)
+                .bind(&teacher.patronymic_name)
+                .fetch_one(&mut *transaction)
+                .await?
+                .get(0);
+                names.teachers.insert(teacher.clone(), id);
+                report.teachers.push(teacher.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
@@ -0,0 +1,250 @@
+//! Group pages of the university's published timetable.
+//!
+//! The group name is in `#ctl00_MainContent_lblHeader` ("Розклад занять для ФІ-91") and each
+//! week is a table, `#ctl00_MainContent_FirstScheduleTable` and
+//! `#ctl00_MainContent_SecondScheduleTable`. The first row of a table names the days and the
+//! first column holds the lesson number and its start time. A cell lists the subjects (links
+//! titled with the full subject name), the teachers (`a.plainLink` to their own timetable, titled
+//! with the rank and full name) and the places (`a.plainLink` to the map, e.g. "305-18 Лек"),
+//! matched to the subjects in order. Lessons without a place are taken as lectures.
+
+use anyhow::Result;
+use chrono::NaiveTime;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::BTreeSet;
+
+use crate::database::day::Day;
+use crate::database::lesson::LessonType;
+use crate::database::week::Distribution;
+use crate::import::{ImportLesson, TeacherName, Timetable};
+
+const HEADER: &str = "#ctl00_MainContent_lblHeader";
+const WEEKS: [(&str, Distribution); 2] = [
+    ("#ctl00_MainContent_FirstScheduleTable", Distribution::First),
+    (
+        "#ctl00_MainContent_SecondScheduleTable",
+        Distribution::Second,
+    ),
+];
+
+/// Lesson of a single cell before it is bound to a group, day and time.
+struct CellLesson {
+    subject: String,
+    lesson_type: LessonType,
+    teacher: Option<TeacherName>,
+    room: String,
+}
+
+impl Timetable {
+    /// Parses the timetable page of one group.
+    pub fn from_html(html: &str) -> Result<Self> {
+        let document = Html::parse_document(html);
+        let header = document
+            .select(&selector(HEADER))
+            .next()
+            .map(|header| text(&header))
+            .ok_or_else(|| anyhow::anyhow!("На странице нет названия группы"))?;
+        let group = header
+            .rsplit("для ")
+            .next()
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Не удалось прочитать группу: {}", header))?
+            .to_string();
+
+        let mut lessons: Vec<ImportLesson> = vec![];
+        for (id, distribution) in WEEKS.iter() {
+            let table = document
+                .select(&selector(id))
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("На странице нет таблицы {}", id))?;
+            for lesson in parse_table(&table, *distribution)? {
+                lessons.push(ImportLesson {
+                    groups: std::iter::once(group.clone()).collect(),
+                    ..lesson
+                });
+            }
+        }
+
+        let mut timetable = Timetable { lessons: vec![] };
+        while let Some(mut lesson) = lessons.pop() {
+            let other_week = lessons.iter().position(|other| {
+                other.distribution != lesson.distribution
+                    && other.same_lesson(&ImportLesson {
+                        distribution: other.distribution,
+                        ..lesson.clone()
+                    })
+            });
+            if let Some(index) = other_week {
+                lessons.remove(index);
+                lesson.distribution = Distribution::All;
+            }
+            timetable.push(lesson);
+        }
+        timetable
+            .lessons
+            .sort_by_key(|lesson| (lesson.day, lesson.time));
+        Ok(timetable)
+    }
+}
+
+fn parse_table(table: &ElementRef, distribution: Distribution) -> Result<Vec<ImportLesson>> {
+    let row_selector = selector("tr");
+    let cell_selector = selector("td");
+    let mut rows = table.select(&row_selector);
+    let days: Vec<Option<Day>> = match rows.next() {
+        Some(header) => header
+            .select(&cell_selector)
+            .skip(1)
+            .map(|cell| parse_day(&text(&cell)))
+            .collect(),
+        None => return Ok(vec![]),
+    };
+
+    let mut lessons = vec![];
+    for row in rows {
+        let mut cells = row.select(&cell_selector);
+        let time = match cells.next().and_then(|cell| parse_time(&cell)) {
+            Some(time) => time,
+            None => continue,
+        };
+        for (cell, day) in cells.zip(days.iter()) {
+            let day = match day {
+                Some(day) => *day,
+                None => continue,
+            };
+            for lesson in parse_cell(&cell) {
+                lessons.push(ImportLesson {
+                    subject: lesson.subject,
+                    choice: false,
+                    lesson_type: lesson.lesson_type,
+                    teacher: lesson.teacher,
+                    day,
+                    time,
+                    distribution,
+                    room: lesson.room,
+                    info: String::new(),
+                    groups: BTreeSet::new(),
+                });
+            }
+        }
+    }
+    Ok(lessons)
+}
+
+fn parse_cell(cell: &ElementRef) -> Vec<CellLesson> {
+    let subjects: Vec<String> = cell
+        .select(&selector("a:not(.plainLink)"))
+        .map(|link| {
+            link.value()
+                .attr("title")
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| text(&link))
+        })
+        .filter(|subject| !subject.is_empty())
+        .collect();
+    let teachers: Vec<Option<TeacherName>> = cell
+        .select(&selector("a.plainLink[href*=\"ViewSchedule\"]"))
+        .map(|link| parse_teacher(link.value().attr("title").unwrap_or_default()))
+        .collect();
+    let places: Vec<(String, LessonType)> = cell
+        .select(&selector("a.plainLink:not([href*=\"ViewSchedule\"])"))
+        .map(|link| parse_place(&text(&link)))
+        .collect();
+
+    subjects
+        .into_iter()
+        .enumerate()
+        .map(|(index, subject)| {
+            let (room, lesson_type) = nth_or_only(&places, index)
+                .cloned()
+                .unwrap_or((String::new(), LessonType::Lecture));
+            CellLesson {
+                subject,
+                lesson_type,
+                teacher: nth_or_only(&teachers, index).cloned().flatten(),
+                room,
+            }
+        })
+        .collect()
+}
+
+/// Cells with several subjects either list a teacher and a place for each or share one.
+fn nth_or_only<T>(items: &[T], index: usize) -> Option<&T> {
+    items.get(index).or_else(|| {
+        if items.len() == 1 {
+            items.first()
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_day(name: &str) -> Option<Day> {
+    let name = name.trim().to_lowercase().replace(['’', 'ʼ', '`'], "'");
+    match name.as_str() {
+        "понеділок" => Some(Day::Monday),
+        "вівторок" => Some(Day::Tuesday),
+        "середа" => Some(Day::Wednesday),
+        "четвер" => Some(Day::Thursday),
+        "п'ятниця" => Some(Day::Friday),
+        "субота" => Some(Day::Saturday),
+        "неділя" => Some(Day::Sunday),
+        _ => None,
+    }
+}
+
+/// The first column reads "1<br>8:30": the lesson number and its start time.
+fn parse_time(cell: &ElementRef) -> Option<NaiveTime> {
+    cell.text()
+        .flat_map(str::split_whitespace)
+        .find_map(|part| NaiveTime::parse_from_str(part, "%H:%M").ok())
+}
+
+/// Teachers are titled with their rank before the full name, e.g. "доцент Іванов Іван Іванович".
+fn parse_teacher(title: &str) -> Option<TeacherName> {
+    let words: Vec<&str> = title.split_whitespace().collect();
+    match words.len() {
+        0 | 1 => None,
+        2 => Some(TeacherName {
+            last_name: words[0].to_string(),
+            first_name: words[1].to_string(),
+            patronymic_name: String::new(),
+        }),
+        length => Some(TeacherName {
+            last_name: words[length - 3].to_string(),
+            first_name: words[length - 2].to_string(),
+            patronymic_name: words[length - 1].to_string(),
+        }),
+    }
+}
+
+/// Places read "305-18 Лек": the room followed by the type of the lesson.
+fn parse_place(place: &str) -> (String, LessonType) {
+    let mut words: Vec<&str> = place.split_whitespace().collect();
+    let lesson_type = match words.last().map(|word| word.to_lowercase()) {
+        Some(word) if word.starts_with("лек") => Some(LessonType::Lecture),
+        Some(word) if word.starts_with("прак") => Some(LessonType::Practice),
+        Some(word) if word.starts_with("лаб") => Some(LessonType::LaboratoryWork),
+        _ => None,
+    };
+    if lesson_type.is_some() {
+        words.pop();
+    }
+    (words.join(" "), lesson_type.unwrap_or(LessonType::Lecture))
+}
+
+fn text(element: &ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn selector(selector: &str) -> Selector {
+    Selector::parse(selector).expect("Invalid selector")
+}
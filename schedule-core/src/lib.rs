@@ -0,0 +1,11 @@
+pub mod bell;
+pub mod config;
+pub mod database;
+#[cfg(feature = "exchange")]
+pub mod exchange;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod locale;
+pub mod render;
+pub mod repository;
+pub mod selection;
@@ -0,0 +1,160 @@
+//! Translations of user-facing texts. Messages are [Fluent](https://projectfluent.org) sources in
+//! `locales/`, one file per language; [`Localize`] renders schedule types in the reader's
+//! language and their `Display` impls use the default one.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+pub use fluent_bundle::FluentValue;
+
+static MESSAGES: Lazy<Messages> = Lazy::new(|| Messages::new(&[]));
+
+const RESOURCES: [(Language, &str); 3] = [
+    (Language::Uk, include_str!("../locales/uk.ftl")),
+    (Language::Ru, include_str!("../locales/ru.ftl")),
+    (Language::En, include_str!("../locales/en.ftl")),
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "language")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Language {
+    Uk,
+    /// The language of `Display` impls and of users who haven't chosen one.
+    #[default]
+    Ru,
+    En,
+}
+
+impl Language {
+    pub fn all() -> [Language; 3] {
+        [Language::Uk, Language::Ru, Language::En]
+    }
+
+    /// Code of the language as stored in the database and typed after `/lang`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Uk => "uk",
+            Language::Ru => "ru",
+            Language::En => "en",
+        }
+    }
+
+    /// Name of the language in itself, for choosing one.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Uk => "Українська",
+            Language::Ru => "Русский",
+            Language::En => "English",
+        }
+    }
+
+    /// Language of an IETF tag such as Telegram's `language_code`, e.g. `uk` or `en-US`.
+    pub fn from_code(code: &str) -> Option<Language> {
+        let primary = code.split(['-', '_']).next()?;
+        Language::all()
+            .iter()
+            .copied()
+            .find(|language| language.as_str().eq_ignore_ascii_case(primary))
+    }
+}
+
+impl FromStr for Language {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Language::all()
+            .iter()
+            .copied()
+            .find(|language| language.as_str() == s)
+            .ok_or("Expected uk, ru or en")
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Text of a value in the given language.
+pub trait Localize {
+    fn localize(&self, language: Language) -> String;
+}
+
+/// Fluent bundles of every language.
+pub struct Messages {
+    bundles: Vec<(Language, FluentBundle<FluentResource>)>,
+}
+
+impl Messages {
+    /// Messages of this crate together with `resources`, Fluent sources of the caller. The
+    /// sources are compiled in, so a syntax error or a duplicate message is a bug and panics.
+    pub fn new(resources: &[(Language, &str)]) -> Self {
+        let bundles = Language::all()
+            .iter()
+            .map(|&language| {
+                let identifier = language
+                    .as_str()
+                    .parse()
+                    .expect("Language codes are valid identifiers");
+                let mut bundle = FluentBundle::new_concurrent(vec![identifier]);
+                bundle.set_use_isolating(false);
+                for (_, source) in RESOURCES
+                    .iter()
+                    .chain(resources)
+                    .filter(|(resource_language, _)| *resource_language == language)
+                {
+                    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(
+                        |(_, errors)| panic!("Invalid {} messages: {:?}", language, errors),
+                    );
+                    bundle.add_resource(resource).unwrap_or_else(|errors| {
+                        panic!("Invalid {} messages: {:?}", language, errors)
+                    });
+                }
+                (language, bundle)
+            })
+            .collect();
+        Messages { bundles }
+    }
+
+    pub fn get(&self, language: Language, id: &str) -> String {
+        self.format(language, id, Vec::new())
+    }
+
+    /// Message with the given arguments. A message missing in the language is taken from the
+    /// default one, and an unknown message is shown as its id.
+    pub fn format<'a, I>(&self, language: Language, id: &str, args: I) -> String
+    where
+        I: IntoIterator<Item = (&'a str, FluentValue<'a>)>,
+    {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(name, value);
+        }
+        let pattern = [language, Language::default()].iter().find_map(|language| {
+            let (_, bundle) = self.bundles.iter().find(|(l, _)| l == language)?;
+            let pattern = bundle.get_message(id)?.value()?;
+            Some((bundle, pattern))
+        });
+        match pattern {
+            Some((bundle, pattern)) => {
+                let mut errors = vec![];
+                bundle
+                    .format_pattern(pattern, Some(&fluent_args), &mut errors)
+                    .into_owned()
+            }
+            None => id.to_string(),
+        }
+    }
+}
+
+/// Messages of this crate.
+pub fn global() -> &'static Messages {
+    &MESSAGES
+}
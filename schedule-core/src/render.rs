@@ -0,0 +1,351 @@
+//! Telegram HTML of schedules, sent by the bot with `ParseMode::Html`. The plain text of
+//! [`Localize`] stays for the web, the API and tests.
+
+use crate::database::homework::{Homework, LessonsHomework};
+use crate::database::lesson::{Lesson, LessonType, Lessons, LessonsWeek};
+use crate::database::settings::{LessonFormat, UserSettings};
+use crate::locale::{self, Language, Localize};
+
+#[cfg(any(feature = "image", feature = "pdf"))]
+mod grid;
+#[cfg(feature = "image")]
+pub mod image;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+
+/// Longest message Telegram accepts, in UTF-16 code units.
+pub const MESSAGE_LIMIT: usize = 4096;
+
+/// How schedules look to a user: the language and the [`UserSettings`] on showing lessons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub language: Language,
+    pub format: LessonFormat,
+    pub show_teacher: bool,
+}
+
+impl Style {
+    pub fn new(language: Language, settings: &UserSettings) -> Self {
+        Style {
+            language,
+            format: settings.lesson_format,
+            show_teacher: settings.show_teacher,
+        }
+    }
+}
+
+/// The default settings in the language.
+impl From<Language> for Style {
+    fn from(language: Language) -> Self {
+        Style::new(language, &UserSettings::default())
+    }
+}
+
+/// Text of a value in the given style as Telegram HTML.
+pub trait Html {
+    /// Parts of the text in order; each may start a new message when the text is [`split`].
+    fn html_parts(&self, style: Style) -> Vec<String>;
+
+    fn html(&self, style: Style) -> String {
+        self.html_parts(style).join("\n\n")
+    }
+}
+
+/// Escapes the characters Telegram HTML gives a meaning to.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn emoji(lesson_type: LessonType) -> &'static str {
+    match lesson_type {
+        LessonType::Lecture => "📖",
+        LessonType::Practice => "✏️",
+        LessonType::LaboratoryWork => "🔬",
+    }
+}
+
+impl Html for Lesson {
+    fn html_parts(&self, style: Style) -> Vec<String> {
+        match style.format {
+            LessonFormat::Compact => vec![compact(self, style)],
+            LessonFormat::Standard | LessonFormat::Verbose => vec![detailed(self, style)],
+        }
+    }
+}
+
+/// A line like `10:25 <b>Матан</b> (лекц.) · 305 — Іванов І.І.`
+fn compact(lesson: &Lesson, style: Style) -> String {
+    let mut line = format!(
+        "{} <b>{}</b> ({})",
+        lesson.time.format("%R"),
+        escape(&lesson.subject_name),
+        escape(&lesson.lesson_type.short_name(style.language))
+    );
+    if !lesson.room.is_empty() {
+        line += &format!(" · {}", escape(&lesson.room));
+    }
+    if style.show_teacher && !lesson.teacher_name.is_empty() {
+        line += &format!(" — {}", escape(&initials(&lesson.teacher_name)));
+    }
+    line
+}
+
+/// A line per field; the empty ones are shown as a dash in the verbose format and left out
+/// otherwise.
+fn detailed(lesson: &Lesson, style: Style) -> String {
+    let language = style.language;
+    let verbose = style.format == LessonFormat::Verbose;
+    let messages = locale::global();
+    let mut lines = vec![
+        format!(
+            "{} <b>{}</b> ({})",
+            emoji(lesson.lesson_type),
+            escape(&lesson.subject_name),
+            escape(&lesson.lesson_type.localize(language))
+        ),
+        format!("🕒 {}", lesson.time.format("%R")),
+    ];
+    if verbose || !lesson.room.is_empty() {
+        lines[1] += &format!(
+            " · {}",
+            escape(&messages.format(
+                language,
+                "lesson-room",
+                vec![("room", or_dash(&lesson.room).into())]
+            ))
+        );
+    }
+    if style.show_teacher && (verbose || !lesson.teacher_name.is_empty()) {
+        lines.push(escape(&messages.format(
+            language,
+            "lesson-teacher",
+            vec![("teacher", or_dash(&lesson.teacher_name).into())],
+        )));
+    }
+    if verbose || !lesson.info.trim().is_empty() {
+        lines.push(format!("<i>{}</i>", escape(or_dash(&lesson.info))));
+    }
+    lines.join("\n")
+}
+
+fn or_dash(value: &str) -> &str {
+    match value.trim() {
+        "" => "—",
+        value => value,
+    }
+}
+
+/// `Іванов І.І.` of `Іванов Іван Іванович`.
+fn initials(full_name: &str) -> String {
+    let mut words = full_name.split_whitespace();
+    let mut name = words.next().unwrap_or_default().to_owned();
+    for word in words {
+        if let Some(initial) = word.chars().next() {
+            if !name.ends_with('.') {
+                name.push(' ');
+            }
+            name.push(initial);
+            name.push('.');
+        }
+    }
+    name
+}
+
+impl Html for Lessons {
+    /// A part per lesson, or a single one of all in the compact format.
+    fn html_parts(&self, style: Style) -> Vec<String> {
+        lesson_parts(self, style, |_| vec![])
+    }
+}
+
+impl Html for LessonsHomework {
+    /// Homework of a subject with no lesson on the date is left out.
+    fn html_parts(&self, style: Style) -> Vec<String> {
+        let mut shown: Vec<&str> = vec![];
+        lesson_parts(&self.lessons, style, |lesson| {
+            if shown.contains(&lesson.subject_name.as_str()) {
+                return vec![];
+            }
+            shown.push(&lesson.subject_name);
+            self.homework
+                .iter()
+                .filter(|homework| homework.subject_name == lesson.subject_name)
+                .map(homework_line)
+                .collect()
+        })
+    }
+}
+
+/// Parts of the lessons as for [`Lessons`], with the lines `extra` gives under each lesson.
+fn lesson_parts<'a, F>(lessons: &'a Lessons, style: Style, mut extra: F) -> Vec<String>
+where
+    F: FnMut(&'a Lesson) -> Vec<String>,
+{
+    if lessons.0.is_empty() {
+        return vec![escape(&locale::global().get(style.language, "day-off"))];
+    }
+    let parts = lessons.0.iter().map(|lesson| {
+        let mut lines = lesson.html_parts(style);
+        lines.extend(extra(lesson));
+        lines.join("\n")
+    });
+    match style.format {
+        LessonFormat::Compact => vec![parts.collect::<Vec<_>>().join("\n")],
+        LessonFormat::Standard | LessonFormat::Verbose => parts.collect(),
+    }
+}
+
+/// A line like `📝 Задачі 1–5 📎 2`, with the number of attached files.
+fn homework_line(homework: &Homework) -> String {
+    let mut line = format!("📝 {}", escape(homework.text.trim()));
+    if !homework.files.is_empty() {
+        line += &format!(" 📎 {}", homework.files.len());
+    }
+    line
+}
+
+impl Html for LessonsWeek {
+    /// The heading of a day stays with its first lesson.
+    fn html_parts(&self, style: Style) -> Vec<String> {
+        if self.0.is_empty() {
+            return vec![escape(&locale::global().get(style.language, "week-off"))];
+        }
+        let separator = match style.format {
+            LessonFormat::Compact => "\n",
+            LessonFormat::Standard | LessonFormat::Verbose => "\n\n",
+        };
+        let mut parts = vec![];
+        for (day, lessons) in &self.0 {
+            let mut lessons = lessons.html_parts(style).into_iter();
+            parts.push(format!(
+                "<b><u>{}</u></b>{}{}",
+                escape(&day.localize(style.language)),
+                separator,
+                lessons.next().unwrap_or_default()
+            ));
+            parts.extend(lessons);
+        }
+        parts
+    }
+}
+
+/// Joins the parts into as few messages of at most `limit` UTF-16 code units as possible. A part
+/// too long for one message is cut between lines and, failing that, between characters, closing
+/// the open tags at the cut and opening them again in the next message.
+pub fn split(parts: Vec<String>, limit: usize) -> Vec<String> {
+    let mut messages = vec![];
+    let mut message = String::new();
+    for part in parts {
+        let pieces = if length(&part) > limit {
+            pack(
+                part.split('\n').flat_map(|line| cut(line, limit)),
+                "\n",
+                limit,
+            )
+        } else {
+            vec![part]
+        };
+        for piece in pieces {
+            if !message.is_empty() && length(&message) + 2 + length(&piece) > limit {
+                messages.push(std::mem::take(&mut message));
+            }
+            if !message.is_empty() {
+                message.push_str("\n\n");
+            }
+            message.push_str(&piece);
+        }
+    }
+    if !message.is_empty() {
+        messages.push(message);
+    }
+    messages
+}
+
+/// Length as Telegram counts it.
+fn length(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Joins pieces no longer than `limit` with the separator, starting a new one when it would
+/// grow over the limit.
+fn pack<I>(pieces: I, separator: &str, limit: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut packed: Vec<String> = vec![];
+    for piece in pieces {
+        match packed.last_mut() {
+            Some(last) if length(last) + length(separator) + length(&piece) <= limit => {
+                last.push_str(separator);
+                last.push_str(&piece);
+            }
+            _ => packed.push(piece),
+        }
+    }
+    packed
+}
+
+/// Cuts a line of balanced HTML into pieces of at most `limit`, never inside a tag or an entity.
+fn cut(line: &str, limit: usize) -> Vec<String> {
+    let mut pieces = vec![];
+    let mut piece = String::new();
+    let mut piece_length = 0;
+    let mut open: Vec<&str> = vec![];
+    let mut rest = line;
+    while let Some(first) = rest.chars().next() {
+        let token_length = match first {
+            '<' => rest.find('>').map_or(rest.len(), |end| end + 1),
+            '&' => rest
+                .find(';')
+                .filter(|end| *end < 10)
+                .map_or(1, |end| end + 1),
+            c => c.len_utf8(),
+        };
+        let (token, tail) = rest.split_at(token_length);
+        rest = tail;
+        if token.starts_with("</") {
+            open.pop();
+        } else {
+            let opening = token.starts_with('<');
+            let closing: usize = open
+                .iter()
+                .chain(if opening { Some(&token) } else { None })
+                .map(|tag| length(&closing_tag(tag)))
+                .sum();
+            let reopened = open.concat();
+            if piece_length + length(token) + closing > limit && piece.len() > reopened.len() {
+                for tag in open.iter().rev() {
+                    piece.push_str(&closing_tag(tag));
+                }
+                piece_length = length(&reopened);
+                pieces.push(std::mem::replace(&mut piece, reopened));
+            }
+            if opening {
+                open.push(token);
+            }
+        }
+        piece.push_str(token);
+        piece_length += length(token);
+    }
+    pieces.push(piece);
+    pieces
+}
+
+/// `</b>` for `<b>`, `</a>` for `<a href="...">`.
+fn closing_tag(tag: &str) -> String {
+    let name = tag[1..]
+        .split(|c: char| c.is_whitespace() || c == '>')
+        .next()
+        .unwrap_or_default();
+    format!("</{}>", name)
+}
@@ -0,0 +1,348 @@
+//! Layout of a week as a grid: days as columns, pairs of [`bell`](crate::bell) as rows and lessons
+//! colored by type, drawn on any [`Surface`]. Text is set in the bundled DejaVu Sans, which covers
+//! Cyrillic (see `fonts/LICENSE`).
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use chrono::{Duration, NaiveTime};
+use once_cell::sync::Lazy;
+
+use crate::bell::{self, LESSON_DURATION};
+use crate::database::day::Day;
+use crate::database::lesson::{Lesson, LessonType, LessonsWeek};
+use crate::database::settings::LessonFormat;
+use crate::locale::Localize;
+use crate::render::{initials, Style};
+
+const REGULAR: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+const BOLD: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+static REGULAR_FONT: Lazy<FontRef<'static>> =
+    Lazy::new(|| FontRef::try_from_slice(REGULAR).expect("The bundled font is valid"));
+static BOLD_FONT: Lazy<FontRef<'static>> =
+    Lazy::new(|| FontRef::try_from_slice(BOLD).expect("The bundled font is valid"));
+
+const MARGIN: f32 = 16.0;
+const TITLE_HEIGHT: f32 = 44.0;
+const HEADER_HEIGHT: f32 = 34.0;
+const TIME_WIDTH: f32 = 84.0;
+const DAY_WIDTH: f32 = 196.0;
+const ROW_HEIGHT: f32 = 108.0;
+const PADDING: f32 = 6.0;
+const SUBJECT_SIZE: f32 = 13.0;
+const SUBJECT_LINE: f32 = 16.0;
+const DETAIL_SIZE: f32 = 11.0;
+const DETAIL_LINE: f32 = 14.0;
+
+pub type Rgb = (u8, u8, u8);
+
+pub const BACKGROUND: Rgb = (255, 255, 255);
+const HEADER: Rgb = (238, 241, 245);
+const GRID: Rgb = (208, 213, 221);
+const TEXT: Rgb = (31, 41, 55);
+const MUTED: Rgb = (75, 85, 99);
+
+/// Fill and accent of the lessons of a type.
+fn colors(lesson_type: LessonType) -> (Rgb, Rgb) {
+    match lesson_type {
+        LessonType::Lecture => ((219, 234, 254), (59, 130, 246)),
+        LessonType::Practice => ((220, 252, 231), (34, 197, 94)),
+        LessonType::LaboratoryWork => ((254, 243, 199), (245, 158, 11)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weight {
+    Regular,
+    Bold,
+}
+
+impl Weight {
+    #[cfg(feature = "pdf")]
+    pub fn all() -> [Weight; 2] {
+        [Weight::Regular, Weight::Bold]
+    }
+
+    /// The TrueType file of the font.
+    #[cfg(feature = "pdf")]
+    pub fn data(self) -> &'static [u8] {
+        match self {
+            Weight::Regular => REGULAR,
+            Weight::Bold => BOLD,
+        }
+    }
+
+    pub fn font(self) -> &'static FontRef<'static> {
+        match self {
+            Weight::Regular => &REGULAR_FONT,
+            Weight::Bold => &BOLD_FONT,
+        }
+    }
+}
+
+/// Something to draw on, with the origin at the top left and `y` growing down.
+pub trait Surface {
+    fn fill(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgb);
+
+    fn fill_rounded(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, color: Rgb);
+
+    /// Text starting at `x` on the baseline; glyphs are placed by [`measure`]'s advances.
+    fn text(&mut self, weight: Weight, size: f32, x: f32, baseline: f32, text: &str, color: Rgb);
+}
+
+/// A week placed on the grid. Sunday gets a column only if it has lessons; a lesson off the bells
+/// goes to the pair starting closest to it and shows its own time.
+pub struct Grid<'a> {
+    week: &'a LessonsWeek,
+    days: Vec<Day>,
+    bells: Vec<NaiveTime>,
+}
+
+impl<'a> Grid<'a> {
+    pub fn new(week: &'a LessonsWeek) -> Self {
+        let days = Day::all()
+            .iter()
+            .copied()
+            .filter(|day| *day != Day::Sunday || week.0.contains_key(day))
+            .collect();
+        Grid {
+            week,
+            days,
+            bells: bell::bells(),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        MARGIN * 2.0 + TIME_WIDTH + DAY_WIDTH * self.days.len() as f32
+    }
+
+    pub fn height(&self) -> f32 {
+        MARGIN * 2.0 + TITLE_HEIGHT + HEADER_HEIGHT + ROW_HEIGHT * self.bells.len() as f32
+    }
+
+    pub fn draw<S: Surface>(&self, surface: &mut S, title: &str, style: Style) {
+        let (width, height) = (self.width(), self.height());
+        let title = fit(Weight::Bold, 20.0, title, width - MARGIN * 2.0);
+        surface.text(Weight::Bold, 20.0, MARGIN, MARGIN + 26.0, &title, TEXT);
+
+        let top = MARGIN + TITLE_HEIGHT;
+        let left = MARGIN + TIME_WIDTH;
+        let grid_top = top + HEADER_HEIGHT;
+        surface.fill(MARGIN, top, width - MARGIN * 2.0, HEADER_HEIGHT, HEADER);
+        for (column, day) in self.days.iter().enumerate() {
+            let x = left + DAY_WIDTH * column as f32;
+            let name = day.localize(style.language);
+            centered(surface, Weight::Bold, 15.0, x, DAY_WIDTH, top + 23.0, &name);
+        }
+        for (row, start) in self.bells.iter().enumerate() {
+            let y = grid_top + ROW_HEIGHT * row as f32;
+            let end = *start + Duration::minutes(LESSON_DURATION);
+            let number = (row + 1).to_string();
+            centered(
+                surface,
+                Weight::Bold,
+                18.0,
+                MARGIN,
+                TIME_WIDTH,
+                y + 34.0,
+                &number,
+            );
+            for (time, baseline) in [(*start, y + 58.0), (end, y + 74.0)] {
+                let time = time.format("%R").to_string();
+                let x = MARGIN + (TIME_WIDTH - measure(Weight::Regular, 12.0, &time)) / 2.0;
+                surface.text(Weight::Regular, 12.0, x, baseline, &time, MUTED);
+            }
+        }
+
+        let right = width - MARGIN;
+        let bottom = height - MARGIN;
+        for row in 0..=self.bells.len() {
+            let y = grid_top + ROW_HEIGHT * row as f32;
+            surface.fill(MARGIN, y, right - MARGIN, 1.0, GRID);
+        }
+        surface.fill(MARGIN, top, right - MARGIN, 1.0, GRID);
+        surface.fill(MARGIN, top, 1.0, bottom - top, GRID);
+        for column in 0..=self.days.len() {
+            let x = left + DAY_WIDTH * column as f32;
+            surface.fill(x.min(right - 1.0), top, 1.0, bottom - top, GRID);
+        }
+
+        for (column, day) in self.days.iter().enumerate() {
+            let lessons = match self.week.0.get(day) {
+                Some(lessons) => lessons,
+                None => continue,
+            };
+            let mut cells: Vec<Vec<&Lesson>> = vec![vec![]; self.bells.len()];
+            for lesson in &lessons.0 {
+                cells[nearest(&self.bells, lesson.time)].push(lesson);
+            }
+            for (row, cell) in cells.iter().enumerate() {
+                let x = left + DAY_WIDTH * column as f32 + 4.0;
+                let y = grid_top + ROW_HEIGHT * row as f32 + 4.0;
+                let height = (ROW_HEIGHT - 4.0) / cell.len() as f32;
+                for (index, lesson) in cell.iter().enumerate() {
+                    let y = y + height * index as f32;
+                    let rect = (x, y, DAY_WIDTH - 7.0, height - 4.0);
+                    draw_lesson(surface, lesson, self.bells[row], rect, style);
+                }
+            }
+        }
+    }
+}
+
+/// Index of the bell starting closest to the time.
+fn nearest(bells: &[NaiveTime], time: NaiveTime) -> usize {
+    bells
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, bell)| (time - **bell).num_minutes().abs())
+        .map_or(0, |(index, _)| index)
+}
+
+/// Text centered in the column from `x` of the given width.
+fn centered<S: Surface>(
+    surface: &mut S,
+    weight: Weight,
+    size: f32,
+    x: f32,
+    width: f32,
+    baseline: f32,
+    text: &str,
+) {
+    let text = fit(weight, size, text, width);
+    let x = x + ((width - measure(weight, size, &text)) / 2.0).max(0.0);
+    surface.text(weight, size, x, baseline, &text, TEXT);
+}
+
+/// A lesson in its box: the subject in bold as far as it fits, then the type, time and room,
+/// then the teacher. The compact format leaves the type to the color. Details are dropped first
+/// when the box is too low.
+fn draw_lesson<S: Surface>(
+    surface: &mut S,
+    lesson: &Lesson,
+    bell: NaiveTime,
+    (x, y, width, height): (f32, f32, f32, f32),
+    style: Style,
+) {
+    let (fill, accent) = colors(lesson.lesson_type);
+    surface.fill_rounded(x, y, width, height, 6.0, accent);
+    surface.fill_rounded(x + 4.0, y, width - 4.0, height, 6.0, fill);
+
+    let text_x = x + 10.0;
+    let text_width = width - 16.0;
+    let mut kind = vec![];
+    if lesson.time != bell {
+        kind.push(lesson.time.format("%R").to_string());
+    }
+    let compact = style.format == LessonFormat::Compact;
+    kind.push(if compact {
+        lesson.lesson_type.short_name(style.language)
+    } else {
+        lesson.lesson_type.localize(style.language)
+    });
+    if !lesson.room.is_empty() {
+        kind.push(lesson.room.clone());
+    }
+    let mut details = vec![];
+    if !kind.is_empty() {
+        details.push(kind.join(" · "));
+    }
+    if style.show_teacher && !lesson.teacher_name.is_empty() {
+        details.push(if compact {
+            initials(&lesson.teacher_name)
+        } else {
+            lesson.teacher_name.clone()
+        });
+    }
+    let available = height - PADDING * 2.0;
+    while !details.is_empty() && available - DETAIL_LINE * (details.len() as f32) < SUBJECT_LINE {
+        details.pop();
+    }
+    let subject_lines = ((available - DETAIL_LINE * details.len() as f32) / SUBJECT_LINE)
+        .floor()
+        .max(1.0) as usize;
+
+    let mut baseline = y + PADDING + SUBJECT_SIZE;
+    let subject = wrap(
+        Weight::Bold,
+        SUBJECT_SIZE,
+        &lesson.subject_name,
+        text_width,
+        subject_lines,
+    );
+    for line in subject {
+        surface.text(Weight::Bold, SUBJECT_SIZE, text_x, baseline, &line, TEXT);
+        baseline += SUBJECT_LINE;
+    }
+    baseline += DETAIL_LINE - SUBJECT_LINE + 2.0;
+    for detail in details {
+        let line = fit(Weight::Regular, DETAIL_SIZE, &detail, text_width);
+        surface.text(Weight::Regular, DETAIL_SIZE, text_x, baseline, &line, MUTED);
+        baseline += DETAIL_LINE;
+    }
+}
+
+/// Width of the text with kerning.
+pub fn measure(weight: Weight, size: f32, text: &str) -> f32 {
+    let font = weight.font().as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern(previous, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// The text on one line of at most `width`, cut off with an ellipsis.
+fn fit(weight: Weight, size: f32, text: &str, width: f32) -> String {
+    wrap(weight, size, text, width, 1).pop().unwrap_or_default()
+}
+
+/// Lines of at most `width`, broken between words and inside words too long for a line. Text
+/// beyond `max_lines` is cut off with an ellipsis.
+fn wrap(weight: Weight, size: f32, text: &str, width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if measure(weight, size, &candidate) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            line.push(c);
+            if line.chars().count() > 1 && measure(weight, size, &line) > width {
+                line.pop();
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            loop {
+                let candidate = format!("{}…", last.trim_end());
+                if last.is_empty() || measure(weight, size, &candidate) <= width {
+                    *last = candidate;
+                    break;
+                }
+                last.pop();
+            }
+        }
+    }
+    lines
+}
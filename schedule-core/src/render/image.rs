@@ -0,0 +1,127 @@
+//! The week [`Grid`] as a PNG, drawn on the CPU with tiny-skia.
+
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+use anyhow::Result;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, PremultipliedColorU8, Rect, Transform};
+
+use crate::database::lesson::LessonsWeek;
+use crate::database::week::WeekType;
+use crate::locale::{self, Localize};
+use crate::render::grid::{Grid, Rgb, Surface, Weight, BACKGROUND};
+use crate::render::Style;
+
+/// PNG of the week titled with its type.
+pub fn week_png(week: &LessonsWeek, week_type: WeekType, style: Style) -> Result<Vec<u8>> {
+    let grid = Grid::new(week);
+    let (width, height) = (grid.width() as u32, grid.height() as u32);
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| anyhow::anyhow!("Invalid image size {}x{}", width, height))?;
+    let (r, g, b) = BACKGROUND;
+    pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, 255));
+
+    let title = locale::global().format(
+        style.language,
+        "week-title",
+        vec![("week", week_type.localize(style.language).into())],
+    );
+    grid.draw(&mut Canvas(pixmap.as_mut()), &title, style);
+    pixmap
+        .encode_png()
+        .map_err(|error| anyhow::anyhow!("Failed to encode the image: {}", error))
+}
+
+struct Canvas<'a>(tiny_skia::PixmapMut<'a>);
+
+impl Surface for Canvas<'_> {
+    fn fill(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgb) {
+        if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+            self.0
+                .fill_rect(rect, &paint(color), Transform::identity(), None);
+        }
+    }
+
+    fn fill_rounded(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, color: Rgb) {
+        if let Some(path) =
+            Rect::from_xywh(x, y, width, height).and_then(|rect| rounded(rect, radius))
+        {
+            self.0.fill_path(
+                &path,
+                &paint(color),
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn text(&mut self, weight: Weight, size: f32, x: f32, baseline: f32, text: &str, color: Rgb) {
+        let font = weight.font();
+        let scaled = font.as_scaled(PxScale::from(size));
+        let mut caret = x;
+        let mut previous = None;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            previous = Some(id);
+            let glyph = id.with_scale_and_position(size, point(caret, baseline));
+            caret += scaled.h_advance(id);
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    let x = bounds.min.x as i32 + gx as i32;
+                    let y = bounds.min.y as i32 + gy as i32;
+                    blend(&mut self.0, x, y, color, coverage);
+                });
+            }
+        }
+    }
+}
+
+fn paint(color: Rgb) -> Paint<'static> {
+    let (r, g, b) = color;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, 255);
+    paint.anti_alias = true;
+    paint
+}
+
+fn rounded(rect: Rect, radius: f32) -> Option<tiny_skia::Path> {
+    let radius = radius.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let mut path = PathBuilder::new();
+    path.move_to(left + radius, top);
+    path.line_to(right - radius, top);
+    path.quad_to(right, top, right, top + radius);
+    path.line_to(right, bottom - radius);
+    path.quad_to(right, bottom, right - radius, bottom);
+    path.line_to(left + radius, bottom);
+    path.quad_to(left, bottom, left, bottom - radius);
+    path.line_to(left, top + radius);
+    path.quad_to(left, top, left + radius, top);
+    path.close();
+    path.finish()
+}
+
+/// Draws a pixel of text over the opaque image.
+fn blend(pixmap: &mut tiny_skia::PixmapMut, x: i32, y: i32, color: Rgb, coverage: f32) {
+    if x < 0 || y < 0 || x >= pixmap.width() as i32 || y >= pixmap.height() as i32 {
+        return;
+    }
+    let index = y as usize * pixmap.width() as usize + x as usize;
+    let pixel = &mut pixmap.pixels_mut()[index];
+    let alpha = coverage.clamp(0.0, 1.0);
+    let mix = |source: u8, target: u8| {
+        (source as f32 * alpha + target as f32 * (1.0 - alpha)).round() as u8
+    };
+    let (r, g, b) = color;
+    if let Some(mixed) = PremultipliedColorU8::from_rgba(
+        mix(r, pixel.red()),
+        mix(g, pixel.green()),
+        mix(b, pixel.blue()),
+        255,
+    ) {
+        *pixel = mixed;
+    }
+}
@@ -0,0 +1,312 @@
+//! Printable timetables: the week [`Grid`] on A4 landscape pages, written with pdf-writer. Only
+//! the glyphs in use are embedded, so a file stays small despite the Cyrillic fonts.
+
+use ab_glyph::{Font, GlyphId, PxScale, ScaleFont};
+use anyhow::Result;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::BTreeMap;
+
+use crate::database::lesson::LessonsWeek;
+use crate::database::week::WeekType;
+use crate::locale::{self, Localize};
+use crate::render::grid::{Grid, Rgb, Surface, Weight};
+use crate::render::Style;
+
+const PAGE_WIDTH: f32 = 841.89;
+const PAGE_HEIGHT: f32 = 595.28;
+const PAGE_MARGIN: f32 = 24.0;
+
+const SYSTEM_INFO: SystemInfo = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+/// PDF with a page for each of the weeks, titled `title` and the week.
+pub fn timetable_pdf(
+    title: &str,
+    weeks: &[(WeekType, LessonsWeek)],
+    style: Style,
+) -> Result<Vec<u8>> {
+    let mut pdf = Pdf::new();
+    let mut next = Ref::new(1);
+    let catalog_id = next.bump();
+    let pages_id = next.bump();
+    let info_id = next.bump();
+    let font_ids: Vec<Ref> = Weight::all().iter().map(|_| next.bump()).collect();
+
+    let mut glyphs = Glyphs::default();
+    let mut page_ids = vec![];
+    for (week_type, week) in weeks {
+        let grid = Grid::new(week);
+        let scale = ((PAGE_WIDTH - PAGE_MARGIN * 2.0) / grid.width())
+            .min((PAGE_HEIGHT - PAGE_MARGIN * 2.0) / grid.height());
+        let mut page = Page {
+            content: Content::new(),
+            glyphs: &mut glyphs,
+        };
+        // The grid is laid out from the top left with `y` growing down.
+        page.content.transform([
+            scale,
+            0.0,
+            0.0,
+            -scale,
+            (PAGE_WIDTH - grid.width() * scale) / 2.0,
+            PAGE_HEIGHT - PAGE_MARGIN,
+        ]);
+        let week_title = locale::global().format(
+            style.language,
+            "week-title",
+            vec![("week", week_type.localize(style.language).into())],
+        );
+        grid.draw(&mut page, &format!("{} · {}", title, week_title), style);
+
+        let page_id = next.bump();
+        let content_id = next.bump();
+        let content = miniz_oxide::deflate::compress_to_vec_zlib(&page.content.finish(), 6);
+        pdf.stream(content_id, &content).filter(Filter::FlateDecode);
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(pages_id)
+            .contents(content_id);
+        let mut resources = page.resources();
+        let mut fonts = resources.fonts();
+        for (weight, font_id) in Weight::all().iter().zip(&font_ids) {
+            fonts.pair(font_name(*weight), *font_id);
+        }
+        fonts.finish();
+        resources.finish();
+        page.finish();
+        page_ids.push(page_id);
+    }
+
+    for (weight, font_id) in Weight::all().iter().zip(&font_ids) {
+        write_font(&mut pdf, &mut next, *font_id, *weight, glyphs.get(*weight))?;
+    }
+    pdf.catalog(catalog_id).pages(pages_id);
+    pdf.pages(pages_id)
+        .count(page_ids.len() as i32)
+        .kids(page_ids);
+    pdf.document_info(info_id)
+        .title(TextStr(title))
+        .producer(TextStr("schedule"));
+    Ok(pdf.finish())
+}
+
+fn font_name(weight: Weight) -> Name<'static> {
+    match weight {
+        Weight::Regular => Name(b"F1"),
+        Weight::Bold => Name(b"F2"),
+    }
+}
+
+/// Glyphs of each font in use and the characters they show.
+#[derive(Default)]
+struct Glyphs {
+    regular: BTreeMap<u16, char>,
+    bold: BTreeMap<u16, char>,
+}
+
+impl Glyphs {
+    fn get(&self, weight: Weight) -> &BTreeMap<u16, char> {
+        match weight {
+            Weight::Regular => &self.regular,
+            Weight::Bold => &self.bold,
+        }
+    }
+
+    fn get_mut(&mut self, weight: Weight) -> &mut BTreeMap<u16, char> {
+        match weight {
+            Weight::Regular => &mut self.regular,
+            Weight::Bold => &mut self.bold,
+        }
+    }
+}
+
+struct Page<'a> {
+    content: Content,
+    glyphs: &'a mut Glyphs,
+}
+
+impl Page<'_> {
+    fn set_fill(&mut self, (r, g, b): Rgb) {
+        self.content
+            .set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    }
+}
+
+impl Surface for Page<'_> {
+    fn fill(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgb) {
+        self.set_fill(color);
+        self.content.rect(x, y, width, height).fill_nonzero();
+    }
+
+    fn fill_rounded(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, color: Rgb) {
+        let radius = radius.min(width / 2.0).min(height / 2.0);
+        // Distance of the Bézier control points approximating a quarter circle.
+        let control = radius * 0.552_284_8;
+        let (right, bottom) = (x + width, y + height);
+        self.set_fill(color);
+        self.content
+            .move_to(x + radius, y)
+            .line_to(right - radius, y)
+            .cubic_to(
+                right - radius + control,
+                y,
+                right,
+                y + radius - control,
+                right,
+                y + radius,
+            )
+            .line_to(right, bottom - radius)
+            .cubic_to(
+                right,
+                bottom - radius + control,
+                right - radius + control,
+                bottom,
+                right - radius,
+                bottom,
+            )
+            .line_to(x + radius, bottom)
+            .cubic_to(
+                x + radius - control,
+                bottom,
+                x,
+                bottom - radius + control,
+                x,
+                bottom - radius,
+            )
+            .line_to(x, y + radius)
+            .cubic_to(
+                x,
+                y + radius - control,
+                x + radius - control,
+                y,
+                x + radius,
+                y,
+            )
+            .close_path()
+            .fill_nonzero();
+    }
+
+    fn text(&mut self, weight: Weight, size: f32, x: f32, baseline: f32, text: &str, color: Rgb) {
+        let font = weight.font();
+        let scaled = font.as_scaled(PxScale::from(size));
+        // A scale is the height of the font, PDF sizes are of its em square.
+        let em = size * units_per_em(weight) / font.height_unscaled();
+        let glyphs = self.glyphs.get_mut(weight);
+        let mut runs: Vec<(Vec<u8>, f32)> = vec![];
+        let mut previous: Option<GlyphId> = None;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            glyphs.entry(id.0).or_insert(c);
+            let kern = previous.map_or(0.0, |previous| scaled.kern(previous, id));
+            previous = Some(id);
+            match runs.last_mut() {
+                Some((run, _)) if kern == 0.0 => run.extend_from_slice(&id.0.to_be_bytes()),
+                _ => runs.push((id.0.to_be_bytes().to_vec(), -kern / em * 1000.0)),
+            }
+        }
+
+        self.set_fill(color);
+        self.content
+            .begin_text()
+            .set_font(font_name(weight), em)
+            .set_text_matrix([1.0, 0.0, 0.0, -1.0, x, baseline]);
+        let mut shown = self.content.show_positioned();
+        let mut items = shown.items();
+        for (run, adjustment) in &runs {
+            if *adjustment != 0.0 {
+                items.adjust(*adjustment);
+            }
+            items.show(Str(run));
+        }
+        items.finish();
+        shown.finish();
+        self.content.end_text();
+    }
+}
+
+fn units_per_em(weight: Weight) -> f32 {
+    weight.font().units_per_em().unwrap_or(1000.0)
+}
+
+/// Writes the font as a CID font with identity mapping, so the glyph ids of the text are its CIDs.
+fn write_font(
+    pdf: &mut Pdf,
+    next: &mut Ref,
+    font_id: Ref,
+    weight: Weight,
+    glyphs: &BTreeMap<u16, char>,
+) -> Result<()> {
+    let data = weight.data();
+    let face = ttf_parser::Face::parse(data, 0)?;
+    let to_pdf = |units: f32| units / face.units_per_em() as f32 * 1000.0;
+    let base_font = match weight {
+        Weight::Regular => Name(b"AAAAAA+DejaVuSans"),
+        Weight::Bold => Name(b"AAAAAB+DejaVuSans-Bold"),
+    };
+    let (cid_id, descriptor_id, file_id, cmap_id) =
+        (next.bump(), next.bump(), next.bump(), next.bump());
+
+    pdf.type0_font(font_id)
+        .base_font(base_font)
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_id)
+        .to_unicode(cmap_id);
+
+    let mut cid = pdf.cid_font(cid_id);
+    cid.subtype(CidFontType::Type2)
+        .base_font(base_font)
+        .system_info(SYSTEM_INFO)
+        .font_descriptor(descriptor_id)
+        .default_width(0.0)
+        .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid.widths();
+    for &id in glyphs.keys() {
+        let advance = face.glyph_hor_advance(ttf_parser::GlyphId(id)).unwrap_or(0);
+        widths.consecutive(id, [to_pdf(advance as f32)]);
+    }
+    widths.finish();
+    cid.finish();
+
+    let bbox = face.global_bounding_box();
+    let flags = match weight {
+        Weight::Regular => FontFlags::NON_SYMBOLIC,
+        Weight::Bold => FontFlags::NON_SYMBOLIC | FontFlags::FORCE_BOLD,
+    };
+    pdf.font_descriptor(descriptor_id)
+        .name(base_font)
+        .flags(flags)
+        .bbox(Rect::new(
+            to_pdf(bbox.x_min as f32),
+            to_pdf(bbox.y_min as f32),
+            to_pdf(bbox.x_max as f32),
+            to_pdf(bbox.y_max as f32),
+        ))
+        .italic_angle(0.0)
+        .ascent(to_pdf(face.ascender() as f32))
+        .descent(to_pdf(face.descender() as f32))
+        .cap_height(to_pdf(
+            face.capital_height().unwrap_or(face.ascender()) as f32
+        ))
+        .stem_v(match weight {
+            Weight::Regular => 80.0,
+            Weight::Bold => 140.0,
+        })
+        .font_file2(file_id);
+
+    let ids: Vec<u16> = glyphs.keys().copied().collect();
+    let subset = subsetter::subset(data, 0, subsetter::Profile::pdf(&ids))
+        .map_err(|error| anyhow::anyhow!("Failed to subset the font: {}", error))?;
+    let subset = miniz_oxide::deflate::compress_to_vec_zlib(&subset, 6);
+    pdf.stream(file_id, &subset).filter(Filter::FlateDecode);
+
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), SYSTEM_INFO);
+    for (&id, &c) in glyphs {
+        cmap.pair(id, c);
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+    Ok(())
+}
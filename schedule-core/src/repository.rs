@@ -0,0 +1,371 @@
+//! Storage used by the bot: schedule queries and dialogue state. [`Database`] serves it from
+//! PostgreSQL and [`MemoryRepository`] from memory, for running the bot locally and in tests.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use once_cell::sync::OnceCell;
+
+use crate::database::announcement::{Announcement, Audience, TaughtGroup};
+use crate::database::day::Day;
+use crate::database::group::Group;
+use crate::database::homework::{Homework, HomeworkFile};
+use crate::database::lesson::{Lessons, LessonsWeek};
+use crate::database::queue::{LabLesson, QueueEntry};
+use crate::database::role::Role;
+use crate::database::settings::UserSettings;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::week::WeekType;
+use crate::database::Database;
+use crate::locale::Language;
+
+pub use memory::MemoryRepository;
+
+pub mod memory;
+
+static INSTANCE: OnceCell<&'static dyn Repository> = OnceCell::new();
+
+pub trait Repository: Send + Sync {
+    fn get_schedule_by_day(&self, day: Day, user_id: i64) -> BoxFuture<'_, Result<Lessons>>;
+
+    fn get_today_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>>;
+
+    /// The lesson in progress, if any, and the next one.
+    fn get_current_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>>;
+
+    fn get_week_schedule(&self, user_id: i64, week: WeekType)
+        -> BoxFuture<'_, Result<LessonsWeek>>;
+
+    fn get_distribution_week(&self) -> BoxFuture<'_, Result<WeekType>>;
+
+    /// Whether the Telegram user is registered as a student.
+    fn is_student(&self, user_id: i64) -> BoxFuture<'_, Result<bool>>;
+
+    /// Token of the student's calendar feed, created on first use; `None` for unknown users.
+    fn get_calendar_token(&self, user_id: i64) -> BoxFuture<'_, Result<Option<String>>>;
+
+    /// Language the user has chosen for the bot, if any.
+    fn get_language(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Language>>>;
+
+    fn set_language(&self, user_id: i64, language: Language) -> BoxFuture<'_, Result<()>>;
+
+    /// Settings of the user, the defaults for one who hasn't changed any.
+    fn get_settings(&self, user_id: i64) -> BoxFuture<'_, Result<UserSettings>>;
+
+    fn set_settings(&self, user_id: i64, settings: UserSettings) -> BoxFuture<'_, Result<()>>;
+
+    /// Students who chose reminders or a digest, with their settings, by Telegram id.
+    fn get_subscribers(&self) -> BoxFuture<'_, Result<Vec<(i64, UserSettings)>>>;
+
+    /// Roles granted to the user, and `Student` for a registered student, in order.
+    fn get_roles(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<Role>>>;
+
+    fn grant_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<()>>;
+
+    /// Takes the role away; `false` if the user didn't have it.
+    fn revoke_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<bool>>;
+
+    fn get_groups(&self) -> BoxFuture<'_, Result<Vec<Group>>>;
+
+    /// Subjects only the enrolled students attend.
+    fn get_elective_subjects(&self) -> BoxFuture<'_, Result<Vec<Subject>>>;
+
+    /// Group of the registered student, if they have one.
+    fn get_student_group(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Group>>>;
+
+    /// Telegram ids of the audience in ascending order.
+    fn get_recipients(&self, audience: Audience) -> BoxFuture<'_, Result<Vec<i64>>>;
+
+    /// Teacher linked to the Telegram user with `/link_teacher`.
+    fn get_linked_teacher(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Teacher>>>;
+
+    /// Links the teacher to the Telegram user instead of any other; `false` if there is no such
+    /// teacher.
+    fn link_teacher(&self, user_id: i64, teacher_id: i32) -> BoxFuture<'_, Result<bool>>;
+
+    /// Groups the teacher has lessons with, by subject and group name.
+    fn get_taught_groups(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<TaughtGroup>>>;
+
+    /// Logs the announcement for its recipients.
+    fn save_announcement(
+        &self,
+        author: i64,
+        author_name: String,
+        text: String,
+        recipients: Vec<i64>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// The last `limit` announcements sent to the user, newest first.
+    fn get_announcements(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> BoxFuture<'_, Result<Vec<Announcement>>>;
+
+    /// Laboratory works of the student, soonest first.
+    fn get_lab_lessons(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<LabLesson>>>;
+
+    /// Laboratory works of the teacher, soonest first.
+    fn get_taught_lab_lessons(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<LabLesson>>>;
+
+    /// The queue of the lesson on the date: the called students, then the waiting ones.
+    fn get_queue(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<QueueEntry>>>;
+
+    /// Puts the user at the end of the queue; `false` if they are in it already. Queues of the
+    /// lesson's earlier dates are removed.
+    fn join_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+        name: String,
+    ) -> BoxFuture<'_, Result<bool>>;
+
+    /// Takes the user out of the queue; `false` if they weren't in it.
+    fn leave_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+    ) -> BoxFuture<'_, Result<bool>>;
+
+    /// Calls the first waiting student, if any.
+    fn call_next(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Option<i64>>>;
+
+    /// Takes the called students out of the queue, returning them in ascending order.
+    fn finish_called(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<i64>>>;
+
+    /// Subjects the group has lessons of, by name.
+    fn get_group_subjects(&self, group: i32) -> BoxFuture<'_, Result<Vec<Subject>>>;
+
+    /// Dates of the group's lessons of the subject from today on, within two weeks.
+    fn get_lesson_dates(&self, group: i32, subject: i32) -> BoxFuture<'_, Result<Vec<NaiveDate>>>;
+
+    /// Gives the group homework due at the lessons of the subject on the date.
+    fn add_homework(
+        &self,
+        author: i64,
+        subject: i32,
+        group: i32,
+        date: NaiveDate,
+        text: String,
+        files: Vec<HomeworkFile>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Homework of the student's group due on the date, in the order given.
+    fn get_homework(&self, user_id: i64, date: NaiveDate) -> BoxFuture<'_, Result<Vec<Homework>>>;
+
+    fn get_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<Option<Vec<u8>>>>;
+
+    fn update_dialogue(&self, chat_id: i64, dialogue: Vec<u8>) -> BoxFuture<'_, Result<()>>;
+
+    fn remove_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<()>>;
+}
+
+pub fn initialize(repository: &'static dyn Repository) -> Result<()> {
+    INSTANCE
+        .set(repository)
+        .map_err(|_| anyhow::anyhow!("Failed to initialize repository!"))
+}
+
+pub fn global() -> &'static dyn Repository {
+    *INSTANCE.get().expect("Repository is not initialized")
+}
+
+impl Repository for Database {
+    fn get_schedule_by_day(&self, day: Day, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        Box::pin(Database::get_schedule_by_day(self, day, user_id))
+    }
+
+    fn get_today_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        Box::pin(Database::get_today_schedule(self, user_id))
+    }
+
+    fn get_current_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        Box::pin(Database::get_current_schedule(self, user_id))
+    }
+
+    fn get_week_schedule(
+        &self,
+        user_id: i64,
+        week: WeekType,
+    ) -> BoxFuture<'_, Result<LessonsWeek>> {
+        Box::pin(async move { Database::get_week_schedule(self, user_id, &week).await })
+    }
+
+    fn get_distribution_week(&self) -> BoxFuture<'_, Result<WeekType>> {
+        Box::pin(Database::get_distribution_week(self))
+    }
+
+    fn is_student(&self, user_id: i64) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(Database::is_student(self, user_id))
+    }
+
+    fn get_calendar_token(&self, user_id: i64) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(Database::get_calendar_token(self, user_id))
+    }
+
+    fn get_language(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Language>>> {
+        Box::pin(Database::get_language(self, user_id))
+    }
+
+    fn set_language(&self, user_id: i64, language: Language) -> BoxFuture<'_, Result<()>> {
+        Box::pin(Database::set_language(self, user_id, language))
+    }
+
+    fn get_settings(&self, user_id: i64) -> BoxFuture<'_, Result<UserSettings>> {
+        Box::pin(Database::get_settings(self, user_id))
+    }
+
+    fn set_settings(&self, user_id: i64, settings: UserSettings) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move { Database::set_settings(self, user_id, &settings).await })
+    }
+
+    fn get_subscribers(&self) -> BoxFuture<'_, Result<Vec<(i64, UserSettings)>>> {
+        Box::pin(Database::get_subscribers(self))
+    }
+
+    fn get_roles(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<Role>>> {
+        Box::pin(Database::get_roles(self, user_id))
+    }
+
+    fn grant_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<()>> {
+        Box::pin(Database::grant_role(self, user_id, role))
+    }
+
+    fn revoke_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(Database::revoke_role(self, user_id, role))
+    }
+
+    fn get_groups(&self) -> BoxFuture<'_, Result<Vec<Group>>> {
+        Box::pin(Database::get_groups(self))
+    }
+
+    fn get_elective_subjects(&self) -> BoxFuture<'_, Result<Vec<Subject>>> {
+        Box::pin(async move {
+            let subjects = Database::get_subjects(self).await?;
+            Ok(subjects
+                .into_iter()
+                .filter(|subject| subject.choice)
+                .collect())
+        })
+    }
+
+    fn get_student_group(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Group>>> {
+        Box::pin(Database::get_student_group(self, user_id))
+    }
+
+    fn get_recipients(&self, audience: Audience) -> BoxFuture<'_, Result<Vec<i64>>> {
+        Box::pin(Database::get_recipients(self, audience))
+    }
+
+    fn get_linked_teacher(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Teacher>>> {
+        Box::pin(Database::get_linked_teacher(self, user_id))
+    }
+
+    fn link_teacher(&self, user_id: i64, teacher_id: i32) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(Database::link_teacher(self, user_id, teacher_id))
+    }
+
+    fn get_taught_groups(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<TaughtGroup>>> {
+        Box::pin(Database::get_taught_groups(self, teacher_id))
+    }
+
+    fn save_announcement(
+        &self,
+        author: i64,
+        author_name: String,
+        text: String,
+        recipients: Vec<i64>,
+    ) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            Database::save_announcement(self, author, &author_name, &text, &recipients).await
+        })
+    }
+
+    fn get_announcements(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> BoxFuture<'_, Result<Vec<Announcement>>> {
+        Box::pin(Database::get_announcements(self, user_id, limit))
+    }
+
+    fn get_lab_lessons(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<LabLesson>>> {
+        Box::pin(Database::get_lab_lessons(self, user_id))
+    }
+
+    fn get_taught_lab_lessons(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<LabLesson>>> {
+        Box::pin(Database::get_taught_lab_lessons(self, teacher_id))
+    }
+
+    fn get_queue(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<QueueEntry>>> {
+        Box::pin(Database::get_queue(self, lesson, date))
+    }
+
+    fn join_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+        name: String,
+    ) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move { Database::join_queue(self, lesson, date, user_id, &name).await })
+    }
+
+    fn leave_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+    ) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(Database::leave_queue(self, lesson, date, user_id))
+    }
+
+    fn call_next(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Option<i64>>> {
+        Box::pin(Database::call_next(self, lesson, date))
+    }
+
+    fn finish_called(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<i64>>> {
+        Box::pin(Database::finish_called(self, lesson, date))
+    }
+
+    fn get_group_subjects(&self, group: i32) -> BoxFuture<'_, Result<Vec<Subject>>> {
+        Box::pin(Database::get_group_subjects(self, group))
+    }
+
+    fn get_lesson_dates(&self, group: i32, subject: i32) -> BoxFuture<'_, Result<Vec<NaiveDate>>> {
+        Box::pin(Database::get_lesson_dates(self, group, subject))
+    }
+
+    fn add_homework(
+        &self,
+        author: i64,
+        subject: i32,
+        group: i32,
+        date: NaiveDate,
+        text: String,
+        files: Vec<HomeworkFile>,
+    ) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            Database::add_homework(self, author, subject, group, date, &text, &files).await
+        })
+    }
+
+    fn get_homework(&self, user_id: i64, date: NaiveDate) -> BoxFuture<'_, Result<Vec<Homework>>> {
+        Box::pin(Database::get_homework(self, user_id, date))
+    }
+
+    fn get_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<Option<Vec<u8>>>> {
+        Box::pin(Database::get_dialogue(self, chat_id))
+    }
+
+    fn update_dialogue(&self, chat_id: i64, dialogue: Vec<u8>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move { Database::update_dialogue(self, chat_id, &dialogue).await })
+    }
+
+    fn remove_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<()>> {
+        Box::pin(Database::remove_dialogue(self, chat_id))
+    }
+}
@@ -0,0 +1,687 @@
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use crate::database;
+use crate::database::announcement::{Announcement, Audience, TaughtGroup};
+use crate::database::day::Day;
+use crate::database::group::Group;
+use crate::database::homework::{Homework, HomeworkFile};
+use crate::database::lesson::{LessonDay, LessonEntry, Lessons, LessonsWeek};
+use crate::database::queue::{LabLesson, QueueEntry};
+use crate::database::role::Role;
+use crate::database::settings::UserSettings;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::week::WeekType;
+use crate::locale::Language;
+use crate::repository::Repository;
+use crate::selection::{self, TimetableRows};
+
+/// Student of a group together with the elective subjects they are enrolled in.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MemoryStudent {
+    pub telegram_id: i64,
+    pub group: i32,
+    #[serde(default)]
+    pub subjects: Vec<i32>,
+}
+
+/// Timetable held in memory; nothing survives a restart.
+pub struct MemoryRepository {
+    rows: TimetableRows,
+    groups: Vec<Group>,
+    students: Vec<MemoryStudent>,
+    distribution_start: NaiveDate,
+    calendar_tokens: Mutex<HashMap<i64, String>>,
+    settings: Mutex<HashMap<i64, UserSettings>>,
+    roles: Mutex<HashMap<i64, BTreeSet<Role>>>,
+    /// Teachers by the Telegram users linked to them.
+    teacher_links: Mutex<HashMap<i64, i32>>,
+    /// Announcements in the order sent, with their recipients.
+    announcements: Mutex<Vec<(Announcement, Vec<i64>)>>,
+    /// Queues by lesson and date, the called students first.
+    queues: Mutex<HashMap<(i32, NaiveDate), Vec<QueueEntry>>>,
+    /// Homework in the order given, by group and date.
+    homework: Mutex<Vec<(i32, NaiveDate, Homework)>>,
+    dialogues: Mutex<HashMap<i64, Vec<u8>>>,
+}
+
+impl MemoryRepository {
+    pub fn new(
+        subjects: Vec<Subject>,
+        teachers: Vec<Teacher>,
+        lessons: Vec<LessonEntry>,
+        students: Vec<MemoryStudent>,
+    ) -> Self {
+        MemoryRepository {
+            rows: TimetableRows {
+                subjects,
+                teachers,
+                lessons,
+            },
+            groups: vec![],
+            students,
+            distribution_start: NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+            calendar_tokens: Mutex::new(HashMap::new()),
+            settings: Mutex::new(HashMap::new()),
+            roles: Mutex::new(HashMap::new()),
+            teacher_links: Mutex::new(HashMap::new()),
+            announcements: Mutex::new(vec![]),
+            queues: Mutex::new(HashMap::new()),
+            homework: Mutex::new(vec![]),
+            dialogues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Monday of a first week, `get_distribution_start()` of the database.
+    pub fn with_distribution_start(mut self, distribution_start: NaiveDate) -> Self {
+        self.distribution_start = distribution_start;
+        self
+    }
+
+    /// Groups to choose from when announcing; the timetable needs only their ids.
+    pub fn with_groups(mut self, groups: Vec<Group>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Reads a timetable export (see [`crate::exchange`]) with an additional `students` list.
+    #[cfg(feature = "exchange")]
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        use crate::exchange::{Format, TimetableExport};
+        use anyhow::Context;
+
+        #[derive(Deserialize)]
+        struct Seed {
+            #[serde(flatten)]
+            timetable: TimetableExport,
+            #[serde(default)]
+            students: Vec<MemoryStudent>,
+        }
+
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        let seed: Seed = match Format::from_path(path) {
+            Some(Format::Json) => serde_json::from_str(&data)?,
+            Some(Format::Yaml) => serde_yaml::from_str(&data)?,
+            None => return Err(anyhow::anyhow!("Ожидается файл .json, .yaml или .yml")),
+        };
+        seed.timetable.validate()?;
+        let links = seed
+            .timetable
+            .teachers
+            .iter()
+            .filter_map(|teacher| Some((teacher.telegram_id?, teacher.teacher.id)))
+            .collect();
+        let repository = MemoryRepository::new(
+            seed.timetable.subjects,
+            seed.timetable
+                .teachers
+                .into_iter()
+                .map(|teacher| teacher.teacher)
+                .collect(),
+            seed.timetable.lessons,
+            seed.students,
+        )
+        .with_groups(seed.timetable.groups);
+        *repository.teacher_links.lock().unwrap() = links;
+        Ok(repository)
+    }
+
+    fn student_week(&self, user_id: i64, week: WeekType) -> Vec<LessonDay> {
+        match self.find_student(user_id) {
+            Some(student) => self
+                .rows
+                .student_week(student.group, &student.subjects, week),
+            None => vec![],
+        }
+    }
+
+    fn find_student(&self, user_id: i64) -> Option<&MemoryStudent> {
+        self.students
+            .iter()
+            .find(|student| student.telegram_id == user_id)
+    }
+
+    fn has_student(&self, user_id: i64) -> bool {
+        self.students
+            .iter()
+            .any(|student| student.telegram_id == user_id)
+    }
+
+    fn today(&self) -> (Day, WeekType, NaiveTime) {
+        let now = Local::now().naive_local();
+        (
+            Day::from(now.weekday()),
+            WeekType::at(self.distribution_start, now.date()),
+            now.time(),
+        )
+    }
+}
+
+impl Repository for MemoryRepository {
+    fn get_schedule_by_day(&self, day: Day, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        let (_, week, _) = self.today();
+        Box::pin(async move { Ok(selection::day(self.student_week(user_id, week), day)) })
+    }
+
+    fn get_today_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        let (day, week, _) = self.today();
+        Box::pin(async move { Ok(selection::day(self.student_week(user_id, week), day)) })
+    }
+
+    fn get_current_schedule(&self, user_id: i64) -> BoxFuture<'_, Result<Lessons>> {
+        let (day, week, now) = self.today();
+        Box::pin(async move {
+            let today = selection::day(self.student_week(user_id, week), day);
+            Ok(selection::current(today, now))
+        })
+    }
+
+    fn get_week_schedule(
+        &self,
+        user_id: i64,
+        week: WeekType,
+    ) -> BoxFuture<'_, Result<LessonsWeek>> {
+        Box::pin(async move { Ok(selection::by_day(self.student_week(user_id, week))) })
+    }
+
+    fn get_distribution_week(&self) -> BoxFuture<'_, Result<WeekType>> {
+        let (_, week, _) = self.today();
+        Box::pin(async move { Ok(week) })
+    }
+
+    fn is_student(&self, user_id: i64) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move { Ok(self.has_student(user_id)) })
+    }
+
+    fn get_calendar_token(&self, user_id: i64) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move {
+            if !self.has_student(user_id) {
+                return Ok(None);
+            }
+            let mut tokens = self.calendar_tokens.lock().unwrap();
+            if let Some(token) = tokens.get(&user_id) {
+                return Ok(Some(token.clone()));
+            }
+            let token = database::token()?;
+            tokens.insert(user_id, token.clone());
+            Ok(Some(token))
+        })
+    }
+
+    fn get_language(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Language>>> {
+        Box::pin(async move {
+            let settings = self.settings.lock().unwrap();
+            Ok(settings
+                .get(&user_id)
+                .and_then(|settings| settings.language))
+        })
+    }
+
+    fn set_language(&self, user_id: i64, language: Language) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let mut settings = self.settings.lock().unwrap();
+            settings.entry(user_id).or_default().language = Some(language);
+            Ok(())
+        })
+    }
+
+    fn get_settings(&self, user_id: i64) -> BoxFuture<'_, Result<UserSettings>> {
+        Box::pin(async move {
+            let settings = self.settings.lock().unwrap();
+            Ok(settings.get(&user_id).cloned().unwrap_or_default())
+        })
+    }
+
+    fn set_settings(&self, user_id: i64, settings: UserSettings) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.settings.lock().unwrap().insert(user_id, settings);
+            Ok(())
+        })
+    }
+
+    fn get_subscribers(&self) -> BoxFuture<'_, Result<Vec<(i64, UserSettings)>>> {
+        Box::pin(async move {
+            let settings = self.settings.lock().unwrap();
+            let mut subscribers: Vec<(i64, UserSettings)> = settings
+                .iter()
+                .filter(|(user_id, settings)| {
+                    (settings.reminder_minutes.is_some() || settings.digest_time.is_some())
+                        && self.has_student(**user_id)
+                })
+                .map(|(user_id, settings)| (*user_id, settings.clone()))
+                .collect();
+            subscribers.sort_by_key(|(user_id, _)| *user_id);
+            Ok(subscribers)
+        })
+    }
+
+    fn get_roles(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<Role>>> {
+        Box::pin(async move {
+            let mut roles = self
+                .roles
+                .lock()
+                .unwrap()
+                .get(&user_id)
+                .cloned()
+                .unwrap_or_default();
+            if self.has_student(user_id) {
+                roles.insert(Role::Student);
+            }
+            Ok(roles.into_iter().collect())
+        })
+    }
+
+    fn grant_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.roles
+                .lock()
+                .unwrap()
+                .entry(user_id)
+                .or_default()
+                .insert(role);
+            Ok(())
+        })
+    }
+
+    fn revoke_role(&self, user_id: i64, role: Role) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            let mut roles = self.roles.lock().unwrap();
+            Ok(roles
+                .get_mut(&user_id)
+                .is_some_and(|roles| roles.remove(&role)))
+        })
+    }
+
+    fn get_groups(&self) -> BoxFuture<'_, Result<Vec<Group>>> {
+        Box::pin(async move {
+            let mut groups = self.groups.clone();
+            groups.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(groups)
+        })
+    }
+
+    fn get_elective_subjects(&self) -> BoxFuture<'_, Result<Vec<Subject>>> {
+        Box::pin(async move {
+            let mut subjects: Vec<Subject> = self
+                .rows
+                .subjects
+                .iter()
+                .filter(|subject| subject.choice)
+                .cloned()
+                .collect();
+            subjects.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(subjects)
+        })
+    }
+
+    fn get_student_group(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Group>>> {
+        Box::pin(async move {
+            let group = self
+                .students
+                .iter()
+                .find(|student| student.telegram_id == user_id)
+                .and_then(|student| self.groups.iter().find(|group| group.id == student.group));
+            Ok(group.cloned())
+        })
+    }
+
+    fn get_recipients(&self, audience: Audience) -> BoxFuture<'_, Result<Vec<i64>>> {
+        Box::pin(async move {
+            let students = self.students.iter();
+            let recipients: BTreeSet<i64> = match audience {
+                Audience::Everyone => students
+                    .map(|student| student.telegram_id)
+                    .chain(self.settings.lock().unwrap().keys().copied())
+                    .chain(
+                        self.roles
+                            .lock()
+                            .unwrap()
+                            .iter()
+                            .filter(|(_, roles)| !roles.is_empty())
+                            .map(|(user_id, _)| *user_id),
+                    )
+                    .collect(),
+                Audience::Group(group) => students
+                    .filter(|student| student.group == group)
+                    .map(|student| student.telegram_id)
+                    .collect(),
+                Audience::Subject(subject) => students
+                    .filter(|student| student.subjects.contains(&subject))
+                    .map(|student| student.telegram_id)
+                    .collect(),
+                Audience::Taught {
+                    teacher,
+                    subject,
+                    group,
+                } => {
+                    let groups: BTreeSet<i32> = self
+                        .rows
+                        .lessons
+                        .iter()
+                        .filter(|lesson| {
+                            lesson.teacher == Some(teacher) && lesson.subject == subject
+                        })
+                        .flat_map(|lesson| lesson.groups.iter().copied())
+                        .filter(|id| group.is_none_or(|group| group == *id))
+                        .collect();
+                    let elective = self
+                        .rows
+                        .subjects
+                        .iter()
+                        .any(|other| other.id == subject && other.choice);
+                    students
+                        .filter(|student| groups.contains(&student.group))
+                        .filter(|student| !elective || student.subjects.contains(&subject))
+                        .map(|student| student.telegram_id)
+                        .collect()
+                }
+            };
+            Ok(recipients.into_iter().collect())
+        })
+    }
+
+    fn get_linked_teacher(&self, user_id: i64) -> BoxFuture<'_, Result<Option<Teacher>>> {
+        Box::pin(async move {
+            let links = self.teacher_links.lock().unwrap();
+            Ok(links.get(&user_id).and_then(|id| {
+                self.rows
+                    .teachers
+                    .iter()
+                    .find(|teacher| teacher.id == *id)
+                    .cloned()
+            }))
+        })
+    }
+
+    fn link_teacher(&self, user_id: i64, teacher_id: i32) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            if !self
+                .rows
+                .teachers
+                .iter()
+                .any(|teacher| teacher.id == teacher_id)
+            {
+                return Ok(false);
+            }
+            let mut links = self.teacher_links.lock().unwrap();
+            links.retain(|_, id| *id != teacher_id);
+            links.insert(user_id, teacher_id);
+            Ok(true)
+        })
+    }
+
+    fn get_taught_groups(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<TaughtGroup>>> {
+        Box::pin(async move {
+            let mut taught: Vec<TaughtGroup> = self
+                .rows
+                .lessons
+                .iter()
+                .filter(|lesson| lesson.teacher == Some(teacher_id))
+                .filter_map(|lesson| {
+                    let subject = self
+                        .rows
+                        .subjects
+                        .iter()
+                        .find(|subject| subject.id == lesson.subject)?;
+                    Some(lesson.groups.iter().filter_map(move |id| {
+                        let group = self.groups.iter().find(|group| group.id == *id)?;
+                        Some(TaughtGroup {
+                            subject: subject.id,
+                            subject_name: subject.name.clone(),
+                            group: group.id,
+                            group_name: group.name.clone(),
+                        })
+                    }))
+                })
+                .flatten()
+                .collect();
+            taught.sort_by(|a, b| {
+                (&a.subject_name, a.subject, &a.group_name, a.group).cmp(&(
+                    &b.subject_name,
+                    b.subject,
+                    &b.group_name,
+                    b.group,
+                ))
+            });
+            taught.dedup();
+            Ok(taught)
+        })
+    }
+
+    fn save_announcement(
+        &self,
+        _author: i64,
+        author_name: String,
+        text: String,
+        recipients: Vec<i64>,
+    ) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let announcement = Announcement {
+                author_name,
+                text,
+                sent_at: Local::now().naive_local(),
+            };
+            self.announcements
+                .lock()
+                .unwrap()
+                .push((announcement, recipients));
+            Ok(())
+        })
+    }
+
+    fn get_announcements(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> BoxFuture<'_, Result<Vec<Announcement>>> {
+        Box::pin(async move {
+            let announcements = self.announcements.lock().unwrap();
+            Ok(announcements
+                .iter()
+                .rev()
+                .filter(|(_, recipients)| recipients.contains(&user_id))
+                .map(|(announcement, _)| announcement.clone())
+                .take(limit.max(0) as usize)
+                .collect())
+        })
+    }
+
+    fn get_lab_lessons(&self, user_id: i64) -> BoxFuture<'_, Result<Vec<LabLesson>>> {
+        let now = Local::now().naive_local();
+        Box::pin(async move {
+            Ok(match self.find_student(user_id) {
+                Some(student) => self.rows.student_labs(
+                    student.group,
+                    &student.subjects,
+                    self.distribution_start,
+                    now,
+                ),
+                None => vec![],
+            })
+        })
+    }
+
+    fn get_taught_lab_lessons(&self, teacher_id: i32) -> BoxFuture<'_, Result<Vec<LabLesson>>> {
+        let now = Local::now().naive_local();
+        Box::pin(async move {
+            Ok(self
+                .rows
+                .teacher_labs(teacher_id, self.distribution_start, now))
+        })
+    }
+
+    fn get_queue(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<QueueEntry>>> {
+        Box::pin(async move {
+            let queues = self.queues.lock().unwrap();
+            Ok(queues.get(&(lesson, date)).cloned().unwrap_or_default())
+        })
+    }
+
+    fn join_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+        name: String,
+    ) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            if !self.rows.lessons.iter().any(|entry| entry.id == lesson) {
+                return Err(anyhow::anyhow!("No lesson {}", lesson));
+            }
+            let mut queues = self.queues.lock().unwrap();
+            queues.retain(|(queue_lesson, queue_date), _| {
+                *queue_lesson != lesson || *queue_date >= date
+            });
+            let queue = queues.entry((lesson, date)).or_default();
+            if queue.iter().any(|entry| entry.telegram_id == user_id) {
+                return Ok(false);
+            }
+            queue.push(QueueEntry {
+                telegram_id: user_id,
+                name,
+                called: false,
+            });
+            Ok(true)
+        })
+    }
+
+    fn leave_queue(
+        &self,
+        lesson: i32,
+        date: NaiveDate,
+        user_id: i64,
+    ) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.entry((lesson, date)).or_default();
+            let len = queue.len();
+            queue.retain(|entry| entry.telegram_id != user_id);
+            Ok(queue.len() < len)
+        })
+    }
+
+    fn call_next(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Option<i64>>> {
+        Box::pin(async move {
+            let mut queues = self.queues.lock().unwrap();
+            // The first waiting student follows the called ones already.
+            Ok(queues
+                .entry((lesson, date))
+                .or_default()
+                .iter_mut()
+                .find(|entry| !entry.called)
+                .map(|entry| {
+                    entry.called = true;
+                    entry.telegram_id
+                }))
+        })
+    }
+
+    fn finish_called(&self, lesson: i32, date: NaiveDate) -> BoxFuture<'_, Result<Vec<i64>>> {
+        Box::pin(async move {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.entry((lesson, date)).or_default();
+            let mut done: Vec<i64> = queue
+                .iter()
+                .filter(|entry| entry.called)
+                .map(|entry| entry.telegram_id)
+                .collect();
+            queue.retain(|entry| !entry.called);
+            done.sort_unstable();
+            Ok(done)
+        })
+    }
+
+    fn get_group_subjects(&self, group: i32) -> BoxFuture<'_, Result<Vec<Subject>>> {
+        Box::pin(async move {
+            let mut subjects: Vec<Subject> =
+                self.rows
+                    .subjects
+                    .iter()
+                    .filter(|subject| {
+                        self.rows.lessons.iter().any(|entry| {
+                            entry.subject == subject.id && entry.groups.contains(&group)
+                        })
+                    })
+                    .cloned()
+                    .collect();
+            subjects.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+            Ok(subjects)
+        })
+    }
+
+    fn get_lesson_dates(&self, group: i32, subject: i32) -> BoxFuture<'_, Result<Vec<NaiveDate>>> {
+        let today = Local::now().date_naive();
+        Box::pin(async move {
+            Ok(self
+                .rows
+                .lesson_dates(group, subject, self.distribution_start, today))
+        })
+    }
+
+    fn add_homework(
+        &self,
+        _author: i64,
+        subject: i32,
+        group: i32,
+        date: NaiveDate,
+        text: String,
+        files: Vec<HomeworkFile>,
+    ) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let subject_name = self
+                .rows
+                .subjects
+                .iter()
+                .find(|other| other.id == subject)
+                .map(|subject| subject.name.clone())
+                .ok_or_else(|| anyhow::anyhow!("No subject {}", subject))?;
+            let homework = Homework {
+                subject,
+                subject_name,
+                text,
+                files,
+            };
+            self.homework.lock().unwrap().push((group, date, homework));
+            Ok(())
+        })
+    }
+
+    fn get_homework(&self, user_id: i64, date: NaiveDate) -> BoxFuture<'_, Result<Vec<Homework>>> {
+        Box::pin(async move {
+            let group = match self.find_student(user_id) {
+                Some(student) => student.group,
+                None => return Ok(vec![]),
+            };
+            let homework = self.homework.lock().unwrap();
+            Ok(homework
+                .iter()
+                .filter(|(other, due, _)| *other == group && *due == date)
+                .map(|(_, _, homework)| homework.clone())
+                .collect())
+        })
+    }
+
+    fn get_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<Option<Vec<u8>>>> {
+        Box::pin(async move { Ok(self.dialogues.lock().unwrap().get(&chat_id).cloned()) })
+    }
+
+    fn update_dialogue(&self, chat_id: i64, dialogue: Vec<u8>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.dialogues.lock().unwrap().insert(chat_id, dialogue);
+            Ok(())
+        })
+    }
+
+    fn remove_dialogue(&self, chat_id: i64) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.dialogues.lock().unwrap().remove(&chat_id);
+            Ok(())
+        })
+    }
+}
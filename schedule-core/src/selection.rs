@@ -0,0 +1,225 @@
+//! Choosing lessons out of plain table rows: week distribution, groups, electives and days. Both
+//! [`Database`](crate::database::Database) and the in-memory repository read the rows and leave
+//! the choice to this module.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::BTreeMap;
+
+use crate::database::day::Day;
+use crate::database::lesson::{Lesson, LessonDay, LessonEntry, LessonType, Lessons, LessonsWeek};
+use crate::database::queue::LabLesson;
+use crate::database::subject::Subject;
+use crate::database::teacher::Teacher;
+use crate::database::week::WeekType;
+
+/// A lesson is current from this many minutes before its start...
+const CURRENT_AHEAD: i64 = 115;
+/// ...until this many minutes after it.
+const CURRENT_BEHIND: i64 = 95;
+
+/// Lessons together with the subjects and teachers they refer to.
+#[derive(Default)]
+pub struct TimetableRows {
+    pub subjects: Vec<Subject>,
+    pub teachers: Vec<Teacher>,
+    pub lessons: Vec<LessonEntry>,
+}
+
+impl TimetableRows {
+    /// Lessons of the student's group in the given week, leaving out electives the student is
+    /// not enrolled in.
+    pub fn student_week(&self, group: i32, electives: &[i32], week: WeekType) -> Vec<LessonDay> {
+        self.select(week, |entry, subject| {
+            entry.groups.contains(&group) && (!subject.choice || electives.contains(&subject.id))
+        })
+    }
+
+    /// Every lesson of the group in the given week, electives included.
+    pub fn group_week(&self, group: i32, week: WeekType) -> Vec<LessonDay> {
+        self.select(week, |entry, _| entry.groups.contains(&group))
+    }
+
+    pub fn teacher_week(&self, teacher: i32, week: WeekType) -> Vec<LessonDay> {
+        self.select(week, |entry, _| entry.teacher == Some(teacher))
+    }
+
+    pub fn room_week(&self, room: &str, week: WeekType) -> Vec<LessonDay> {
+        self.select(week, |entry, _| entry.room == room)
+    }
+
+    /// Laboratory works of the student's group and electives, soonest first from `now`.
+    pub fn student_labs(
+        &self,
+        group: i32,
+        electives: &[i32],
+        distribution_start: NaiveDate,
+        now: NaiveDateTime,
+    ) -> Vec<LabLesson> {
+        self.labs(distribution_start, now, |entry, subject| {
+            entry.groups.contains(&group) && (!subject.choice || electives.contains(&subject.id))
+        })
+    }
+
+    pub fn teacher_labs(
+        &self,
+        teacher: i32,
+        distribution_start: NaiveDate,
+        now: NaiveDateTime,
+    ) -> Vec<LabLesson> {
+        self.labs(distribution_start, now, |entry, _| {
+            entry.teacher == Some(teacher)
+        })
+    }
+
+    /// Dates of the group's lessons of the subject from `today` on, within two weeks.
+    pub fn lesson_dates(
+        &self,
+        group: i32,
+        subject: i32,
+        distribution_start: NaiveDate,
+        today: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        (0..14)
+            .map(|days| today + Duration::days(days))
+            .filter(|date| {
+                let week = WeekType::at(distribution_start, *date);
+                self.lessons.iter().any(|entry| {
+                    entry.subject == subject
+                        && entry.groups.contains(&group)
+                        && entry.day == Day::from(date.weekday())
+                        && entry.distribution.includes(week)
+                })
+            })
+            .collect()
+    }
+
+    /// Matching laboratory works by their [`next_start`].
+    fn labs<F>(
+        &self,
+        distribution_start: NaiveDate,
+        now: NaiveDateTime,
+        filter: F,
+    ) -> Vec<LabLesson>
+    where
+        F: Fn(&LessonEntry, &Subject) -> bool,
+    {
+        let mut labs: Vec<LabLesson> = self
+            .lessons
+            .iter()
+            .filter(|entry| entry.lesson_type == LessonType::LaboratoryWork)
+            .filter_map(|entry| {
+                let subject = self
+                    .subjects
+                    .iter()
+                    .find(|subject| subject.id == entry.subject)?;
+                if !filter(entry, subject) {
+                    return None;
+                }
+                Some(LabLesson {
+                    id: entry.id,
+                    subject_name: subject.name.clone(),
+                    room: entry.room.clone(),
+                    starts_at: next_start(entry, distribution_start, now)?,
+                })
+            })
+            .collect();
+        labs.sort_by_key(|lab| (lab.starts_at, lab.id));
+        labs
+    }
+
+    /// Matching lessons of the week ordered by day and time, each shown once even if several
+    /// rows describe it.
+    fn select<F>(&self, week: WeekType, filter: F) -> Vec<LessonDay>
+    where
+        F: Fn(&LessonEntry, &Subject) -> bool,
+    {
+        let mut lessons: Vec<LessonDay> = self
+            .lessons
+            .iter()
+            .filter(|entry| entry.distribution.includes(week))
+            .filter_map(|entry| {
+                let subject = self
+                    .subjects
+                    .iter()
+                    .find(|subject| subject.id == entry.subject)?;
+                if !filter(entry, subject) {
+                    return None;
+                }
+                Some(LessonDay {
+                    day: entry.day,
+                    subject_name: subject.name.clone(),
+                    lesson_type: entry.lesson_type,
+                    time: entry.time,
+                    teacher_name: entry
+                        .teacher
+                        .and_then(|id| self.teachers.iter().find(|teacher| teacher.id == id))
+                        .map(Teacher::full_name)
+                        .unwrap_or_default(),
+                    info: entry.info.clone(),
+                    room: entry.room.clone(),
+                })
+            })
+            .collect();
+        lessons.sort_by(|a, b| {
+            (a.day, a.time, &a.subject_name).cmp(&(b.day, b.time, &b.subject_name))
+        });
+        lessons.dedup();
+        lessons
+    }
+}
+
+/// Lessons of one day of the week.
+pub fn day(lessons: Vec<LessonDay>, day: Day) -> Lessons {
+    by_day(lessons).0.remove(&day).unwrap_or(Lessons(vec![]))
+}
+
+pub fn by_day(lessons: Vec<LessonDay>) -> LessonsWeek {
+    let mut lessons_week = BTreeMap::new();
+    for lesson in lessons {
+        lessons_week
+            .entry(lesson.day)
+            .or_insert(Lessons(vec![]))
+            .0
+            .push(Lesson {
+                subject_name: lesson.subject_name,
+                lesson_type: lesson.lesson_type,
+                time: lesson.time,
+                teacher_name: lesson.teacher_name,
+                info: lesson.info,
+                room: lesson.room,
+            })
+    }
+    LessonsWeek(lessons_week)
+}
+
+/// Start of the lesson in progress at `now`, otherwise of its next one within two weeks.
+pub fn next_start(
+    entry: &LessonEntry,
+    distribution_start: NaiveDate,
+    now: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    (0..=14)
+        .map(|days| now.date() + Duration::days(days))
+        .filter(|date| {
+            Day::from(date.weekday()) == entry.day
+                && entry
+                    .distribution
+                    .includes(WeekType::at(distribution_start, *date))
+        })
+        .map(|date| date.and_time(entry.time))
+        .find(|start| (now - *start).num_minutes() <= CURRENT_BEHIND)
+}
+
+/// The lesson in progress, if any, and the next one out of the lessons of today.
+pub fn current(Lessons(lessons): Lessons, now: NaiveTime) -> Lessons {
+    Lessons(
+        lessons
+            .into_iter()
+            .filter(|lesson| {
+                let minutes = (lesson.time - now).num_minutes();
+                (-CURRENT_BEHIND..=CURRENT_AHEAD).contains(&minutes)
+            })
+            .take(2)
+            .collect(),
+    )
+}
@@ -0,0 +1,234 @@
+use chrono::{NaiveDate, NaiveTime};
+use schedule_core::database::day::Day;
+use schedule_core::database::group::Group;
+use schedule_core::database::lesson::{LessonEntry, LessonType};
+use schedule_core::database::subject::Subject;
+use schedule_core::database::teacher::Teacher;
+use schedule_core::database::week::Distribution;
+use schedule_core::database::Database;
+use schedule_core::exchange::{ExportTeacher, Format, TimetableExport, FORMAT_VERSION};
+use sqlx::{Executor, Row};
+
+fn sample() -> TimetableExport {
+    TimetableExport {
+        version: FORMAT_VERSION,
+        groups: vec![
+            Group {
+                id: 1,
+                name: "ФІ-91".to_string(),
+            },
+            Group {
+                id: 2,
+                name: "ФІ-92".to_string(),
+            },
+        ],
+        subjects: vec![
+            Subject {
+                id: 1,
+                name: "Математичний аналіз".to_string(),
+                choice: false,
+                info: None,
+            },
+            Subject {
+                id: 3,
+                name: "Криптографія".to_string(),
+                choice: true,
+                info: Some("Вибіркова: \"поглиблений\" курс".to_string()),
+            },
+        ],
+        teachers: vec![ExportTeacher {
+            teacher: Teacher {
+                id: 7,
+                last_name: "Іванов".to_string(),
+                first_name: "Іван".to_string(),
+                patronymic_name: "Іванович".to_string(),
+                telegram: Some("@ivanov".to_string()),
+                email: None,
+                phone_number: Some("380501234567".to_string()),
+            },
+            telegram_id: Some(700),
+        }],
+        lessons: vec![
+            LessonEntry {
+                id: 1,
+                subject: 1,
+                lesson_type: LessonType::Lecture,
+                teacher: Some(7),
+                day: Day::Monday,
+                time: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+                distribution: Distribution::All,
+                info: String::new(),
+                room: "305".to_string(),
+                groups: vec![1, 2],
+            },
+            LessonEntry {
+                id: 5,
+                subject: 3,
+                lesson_type: LessonType::LaboratoryWork,
+                teacher: None,
+                day: Day::Saturday,
+                time: NaiveTime::from_hms_opt(10, 25, 0).unwrap(),
+                distribution: Distribution::Second,
+                info: "онлайн, посилання в чаті".to_string(),
+                room: String::new(),
+                groups: vec![2],
+            },
+        ],
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    let export = sample();
+    let json = export.serialize(Format::Json).unwrap();
+    assert_eq!(TimetableExport::parse(&json, Format::Json).unwrap(), export);
+}
+
+#[test]
+fn yaml_round_trip() {
+    let export = sample();
+    let yaml = export.serialize(Format::Yaml).unwrap();
+    assert_eq!(TimetableExport::parse(&yaml, Format::Yaml).unwrap(), export);
+}
+
+#[test]
+fn json_and_yaml_are_interchangeable() {
+    let json = sample().serialize(Format::Json).unwrap();
+    let yaml = TimetableExport::parse(&json, Format::Json)
+        .unwrap()
+        .serialize(Format::Yaml)
+        .unwrap();
+    let back = TimetableExport::parse(&yaml, Format::Yaml)
+        .unwrap()
+        .serialize(Format::Json)
+        .unwrap();
+    assert_eq!(back, json);
+}
+
+#[test]
+fn enums_use_stable_names() {
+    let json = sample().serialize(Format::Json).unwrap();
+    for name in &[
+        "\"lesson_type\": \"laboratory_work\"",
+        "\"day\": \"saturday\"",
+        "\"distribution\": \"second\"",
+        "\"time\": \"08:30:00\"",
+    ] {
+        assert!(json.contains(name), "{} not found in {}", name, json);
+    }
+}
+
+#[test]
+fn teacher_links_are_optional() {
+    let json = sample().serialize(Format::Json).unwrap();
+    assert!(json.contains("\"telegram_id\": 700"), "{}", json);
+    let unlinked = json.replace(",\n      \"telegram_id\": 700", "");
+    assert_ne!(unlinked, json);
+    let export = TimetableExport::parse(&unlinked, Format::Json).unwrap();
+    assert_eq!(export.teachers[0].telegram_id, None);
+    assert_eq!(export.teachers[0].teacher, sample().teachers[0].teacher);
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let mut export = sample();
+    export.version = FORMAT_VERSION + 1;
+    let json = export.serialize(Format::Json).unwrap();
+    assert!(TimetableExport::parse(&json, Format::Json).is_err());
+}
+
+#[test]
+fn dangling_references_are_rejected() {
+    let mut export = sample();
+    export.lessons[0].groups.push(42);
+    assert!(export.validate().is_err());
+
+    let mut export = sample();
+    export.lessons[1].teacher = Some(8);
+    assert!(export.validate().is_err());
+
+    let mut export = sample();
+    export.subjects.remove(0);
+    assert!(export.validate().is_err());
+}
+
+/// Restores the sample into a scratch database and exports it back. The database named by
+/// `SCHEDULE_TEST_DATABASE_URL` loses its timetable, so the test is skipped when it is unset.
+#[tokio::test]
+async fn database_round_trip() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    schedule_core::database::initialize(&url, 1).await.unwrap();
+    let database = Database::global();
+    let export = sample();
+
+    database.restore_timetable(&export, false).await.unwrap();
+    let exported = database.export_timetable().await.unwrap();
+    for group in &export.groups {
+        assert!(exported.groups.contains(group));
+    }
+    for subject in &export.subjects {
+        assert!(exported.subjects.contains(subject));
+    }
+    for teacher in &export.teachers {
+        assert!(exported.teachers.contains(teacher));
+    }
+    assert_eq!(exported.lessons, export.lessons);
+
+    database.restore_timetable(&exported, false).await.unwrap();
+    assert_eq!(database.export_timetable().await.unwrap(), exported);
+
+    // Lab queues outlive a restore of their lesson and go with the lessons removed.
+    let date = NaiveDate::from_ymd_opt(2021, 9, 6).unwrap();
+    database.join_queue(5, date, 500, "Студент").await.unwrap();
+    database.restore_timetable(&exported, false).await.unwrap();
+    assert_eq!(database.get_queue(5, date).await.unwrap().len(), 1);
+    let mut without_lab = sample();
+    without_lab.lessons.retain(|lesson| lesson.id != 5);
+    database
+        .restore_timetable(&without_lab, false)
+        .await
+        .unwrap();
+    assert!(database.get_queue(5, date).await.unwrap().is_empty());
+
+    // A teacher of the same name under another id is replaced, keeping the Telegram link
+    // unless the export has one.
+    let mut export = sample();
+    export.teachers[0].telegram_id = None;
+    database
+        .pool()
+        .execute(
+            r#"
+            DELETE FROM lessons_groups;
+            DELETE FROM schedule;
+            UPDATE teachers SET id = 70, telegram_id = 7007 WHERE id = 7;
+            "#,
+        )
+        .await
+        .unwrap();
+    let teacher = |id: i32| {
+        sqlx::query("SELECT telegram_id FROM teachers WHERE id = $1")
+            .bind(id)
+            .fetch_optional(database.pool())
+    };
+    let replaced = vec!["Іванов Іван Іванович (id 70 → 7)".to_string()];
+    assert_eq!(
+        database.restore_timetable(&export, true).await.unwrap(),
+        replaced
+    );
+    assert!(teacher(70).await.unwrap().is_some());
+    assert_eq!(
+        database.restore_timetable(&export, false).await.unwrap(),
+        replaced
+    );
+    assert!(teacher(70).await.unwrap().is_none());
+    let telegram_id: Option<i64> = teacher(7).await.unwrap().unwrap().get(0);
+    assert_eq!(telegram_id, Some(7007));
+    assert!(database
+        .restore_timetable(&export, false)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -1,41 +1,28 @@
-CREATE TABLE IF NOT EXISTS teloxide_dialogues(
-    chat_id BIGINT PRIMARY KEY,
-    dialogue BYTEA NOT NULL
-);
+-- The schema of the old migrations/script.sql, with statements reordered so that it runs on an
+-- empty database; databases of the bot before versioned migrations look like this.
 
-CREATE TABLE IF NOT EXISTS groups(
-    id SERIAL PRIMARY KEY,
-    name VARCHAR (255)
-);
+CREATE TYPE days_of_week AS ENUM ('monday', 'tuesday', 'wednesday', 'thursday', 'friday', 'saturday', 'sunday');
 
-CREATE TABLE IF NOT EXISTS lessons_groups(
-    lesson INTEGER REFERENCES schedule(id),
-    "group" INTEGER REFERENCES groups(id),
-    PRIMARY KEY(lesson, "group")
-);
+CREATE TYPE distribution_week AS ENUM ('first', 'second', 'all');
 
-CREATE TABLE IF NOT EXISTS schedule(
-    id SERIAL PRIMARY KEY,
-    subject INTEGER REFERENCES subjects(id),
-    type lesson_types,
-    teacher INTEGER REFERENCES teachers(id),
-    day_of_week  days_of_week,
+CREATE TYPE lesson_types AS ENUM ('lecture', 'practice', 'laboratory work');
+
+CREATE TYPE lesson AS(
+    subject_name VARCHAR(255),
+    lesson_type lesson_types,
     time TIME,
-    distribution distribution_week,
+    teacher_name TEXT,
     info VARCHAR(255)
 );
 
-CREATE TABLE IF NOT EXISTS students(
-    id SERIAL PRIMARY KEY,
-    telegram_id INTEGER UNIQUE,
-    "group" INTEGER REFERENCES groups(id),
-    last_name VARCHAR(255)
+CREATE TABLE IF NOT EXISTS teloxide_dialogues(
+    chat_id BIGINT PRIMARY KEY,
+    dialogue BYTEA NOT NULL
 );
 
-CREATE TABLE IF NOT EXISTS students_subjects(
-    student INTEGER REFERENCES students(id),
-    subject INTEGER REFERENCES subjects(id),
-    PRIMARY KEY(student, subject)
+CREATE TABLE IF NOT EXISTS groups(
+    id SERIAL PRIMARY KEY,
+    name VARCHAR (255)
 );
 
 CREATE TABLE IF NOT EXISTS subjects(
@@ -56,29 +43,35 @@ CREATE TABLE IF NOT EXISTS teachers(
     UNIQUE(last_name, first_name, patronymic_name)
 );
 
-CREATE TYPE days_of_week AS ENUM ('monday', 'tuesday', 'wednesday', 'thursday', 'friday', 'saturday', 'sunday');
-
-CREATE TYPE distribution_week AS ENUM ('first', 'second', 'all');
-
-CREATE TYPE lesson AS(
-    subject_name VARCHAR(255),
-    lesson_type lesson_types,
+CREATE TABLE IF NOT EXISTS schedule(
+    id SERIAL PRIMARY KEY,
+    subject INTEGER REFERENCES subjects(id),
+    type lesson_types,
+    teacher INTEGER REFERENCES teachers(id),
+    day_of_week  days_of_week,
     time TIME,
-    teacher_name TEXT,
+    distribution distribution_week,
     info VARCHAR(255)
 );
 
-CREATE TYPE lesson_types AS ENUM ('lecture', 'practice', 'laboratory work');
+CREATE TABLE IF NOT EXISTS lessons_groups(
+    lesson INTEGER REFERENCES schedule(id),
+    "group" INTEGER REFERENCES groups(id),
+    PRIMARY KEY(lesson, "group")
+);
 
+CREATE TABLE IF NOT EXISTS students(
+    id SERIAL PRIMARY KEY,
+    telegram_id INTEGER UNIQUE,
+    "group" INTEGER REFERENCES groups(id),
+    last_name VARCHAR(255)
+);
 
-CREATE FUNCTION get_current_schedule(user_id INTEGER) RETURNS SETOF lesson
-    LANGUAGE SQL
-AS
-$$
-SELECT * FROM get_today_schedule(user_id)
-WHERE time <= (CURRENT_TIME AT TIME ZONE 'EETDST')::time + INTERVAL '1 hours 55 minutes' AND time + INTERVAL '1 hours 35 minutes' >= (CURRENT_TIME AT TIME ZONE 'EET')::time
-LIMIT 2
-$$;
+CREATE TABLE IF NOT EXISTS students_subjects(
+    student INTEGER REFERENCES students(id),
+    subject INTEGER REFERENCES subjects(id),
+    PRIMARY KEY(student, subject)
+);
 
 CREATE FUNCTION get_distribution_week() RETURNS distribution_week
     LANGUAGE SQL
@@ -125,6 +118,15 @@ $$
 SELECT * FROM get_schedule(trim(to_char(now(), 'day'))::days_of_week, user_id)
 $$;
 
+CREATE FUNCTION get_current_schedule(user_id INTEGER) RETURNS SETOF lesson
+    LANGUAGE SQL
+AS
+$$
+SELECT * FROM get_today_schedule(user_id)
+WHERE time <= (CURRENT_TIME AT TIME ZONE 'EETDST')::time + INTERVAL '1 hours 55 minutes' AND time + INTERVAL '1 hours 35 minutes' >= (CURRENT_TIME AT TIME ZONE 'EET')::time
+LIMIT 2
+$$;
+
 CREATE FUNCTION get_week_schedule(user_id INTEGER, week distribution_week)
     RETURNS TABLE(day_of_week days_of_week, subject_name CHARACTER VARYING, lesson_type lesson_types, "time" TIME WITHOUT TIME ZONE, teacher_name TEXT, info CHARACTER VARYING)
     LANGUAGE plpgsql
@@ -0,0 +1,103 @@
+use chrono::NaiveTime;
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::{Lesson, LessonType, Lessons, LessonsWeek};
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::Language;
+use schedule_core::render::image::week_png;
+use std::collections::BTreeMap;
+use tiny_skia::Pixmap;
+
+const GOLDEN: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/golden/week.png"
+);
+
+fn lesson(subject: &str, lesson_type: LessonType, hour: u32, minute: u32) -> Lesson {
+    Lesson {
+        subject_name: subject.to_string(),
+        lesson_type,
+        time: NaiveTime::from_hms_opt(hour, minute, 0).unwrap(),
+        teacher_name: "Іванов Іван Іванович".to_string(),
+        info: String::new(),
+        room: "305".to_string(),
+    }
+}
+
+fn week() -> LessonsWeek {
+    let mut days = BTreeMap::new();
+    days.insert(
+        Day::Monday,
+        Lessons(vec![
+            lesson("Математичний аналіз", LessonType::Lecture, 8, 30),
+            lesson("Програмування", LessonType::LaboratoryWork, 10, 25),
+        ]),
+    );
+    days.insert(
+        Day::Wednesday,
+        Lessons(vec![
+            lesson(
+                "Теорія ймовірностей та математична статистика з елементами випадкових процесів",
+                LessonType::Practice,
+                12,
+                20,
+            ),
+            lesson("Фізика", LessonType::Lecture, 12, 20),
+            lesson("Англійська мова", LessonType::Practice, 15, 0),
+        ]),
+    );
+    LessonsWeek(days)
+}
+
+#[test]
+fn matches_golden_image() {
+    let png = week_png(&week(), WeekType::First, Language::Uk.into()).unwrap();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(GOLDEN, &png).unwrap();
+    }
+    let actual = Pixmap::decode_png(&png).unwrap();
+    let expected = Pixmap::load_png(GOLDEN).unwrap();
+    assert_eq!(
+        (actual.width(), actual.height()),
+        (expected.width(), expected.height())
+    );
+    // Antialiasing may differ by a step between platforms.
+    let different = actual
+        .pixels()
+        .iter()
+        .zip(expected.pixels())
+        .filter(|(a, e)| {
+            [
+                (a.red(), e.red()),
+                (a.green(), e.green()),
+                (a.blue(), e.blue()),
+            ]
+            .iter()
+            .any(|(a, e)| (*a as i16 - *e as i16).abs() > 8)
+        })
+        .count();
+    assert!(
+        different * 1000 <= actual.pixels().len(),
+        "{} pixels differ from {}; run with UPDATE_GOLDEN=1 after an intended change",
+        different,
+        GOLDEN
+    );
+}
+
+#[test]
+fn sunday_column_only_with_lessons() {
+    let size = |week: &LessonsWeek| {
+        let png = week_png(week, WeekType::Second, Language::En.into()).unwrap();
+        let pixmap = Pixmap::decode_png(&png).unwrap();
+        (pixmap.width(), pixmap.height())
+    };
+    let mut week = week();
+    let (width, height) = size(&week);
+    week.0.insert(
+        Day::Sunday,
+        Lessons(vec![lesson("Фізика", LessonType::Lecture, 8, 30)]),
+    );
+    let (sunday_width, sunday_height) = size(&week);
+    assert!(sunday_width > width);
+    assert_eq!(sunday_height, height);
+    assert_eq!(size(&LessonsWeek(BTreeMap::new())), (width, height));
+}
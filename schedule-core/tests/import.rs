@@ -0,0 +1,248 @@
+use chrono::NaiveTime;
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::LessonType;
+use schedule_core::database::week::Distribution;
+use schedule_core::database::Database;
+use schedule_core::import::{ImportLesson, Timetable};
+use sqlx::{Executor, Row};
+use std::path::Path;
+
+fn fixture(name: &str) -> Timetable {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/import")
+        .join(name);
+    Timetable::from_file(&path).unwrap()
+}
+
+fn find<'a>(timetable: &'a Timetable, day: Day, time: &str, subject: &str) -> &'a ImportLesson {
+    let time = NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+    timetable
+        .lessons
+        .iter()
+        .find(|lesson| lesson.day == day && lesson.time == time && lesson.subject == subject)
+        .unwrap_or_else(|| panic!("{} at {} {} not found", subject, day, time))
+}
+
+fn error(csv: &str) -> String {
+    match Timetable::from_csv(csv.as_bytes()) {
+        Ok(_) => panic!("{} is accepted", csv),
+        Err(error) => format!("{:#}", error),
+    }
+}
+
+#[test]
+fn parses_csv() {
+    let timetable = fixture("timetable.csv");
+    assert_eq!(timetable.lessons.len(), 4);
+
+    let lecture = find(&timetable, Day::Monday, "08:30", "Дискретна математика");
+    assert_eq!(lecture.lesson_type, LessonType::Lecture);
+    assert_eq!(lecture.distribution, Distribution::All);
+    assert_eq!(lecture.room, "301");
+    assert!(lecture.groups.iter().eq(["ІМ-01", "ІМ-02"].iter()));
+    assert_eq!(
+        lecture.teacher.as_ref().unwrap().to_string(),
+        "Коваль Олена Петрівна"
+    );
+    assert!(!lecture.choice);
+
+    let second = timetable
+        .lessons
+        .iter()
+        .find(|lesson| lesson.distribution == Distribution::Second)
+        .unwrap();
+    assert_eq!(second.day, Day::Tuesday);
+    assert!(second.groups.iter().eq(["ІМ-02"].iter()));
+}
+
+#[test]
+fn merges_rows_of_several_groups() {
+    let timetable = fixture("timetable.csv");
+    let lab = find(&timetable, Day::Wednesday, "12:20", "Машинне навчання");
+    assert!(lab.groups.iter().eq(["ІМ-01", "ІМ-02"].iter()));
+    assert_eq!(lab.lesson_type, LessonType::LaboratoryWork);
+    assert!(lab.teacher.is_none());
+    assert_eq!(lab.info, "онлайн");
+    assert!(lab.choice);
+}
+
+#[test]
+fn parses_xlsx_times_and_numbers() {
+    let timetable = fixture("timetable.xlsx");
+    assert_eq!(timetable.lessons.len(), 2);
+    let lecture = find(&timetable, Day::Thursday, "08:30", "Теорія графів");
+    assert_eq!(lecture.room, "305");
+    let practice = find(&timetable, Day::Friday, "15:15", "Теорія графів");
+    assert!(practice.teacher.is_none());
+    assert!(practice.room.is_empty());
+}
+
+#[test]
+fn accepts_names_in_every_language() {
+    let timetable = Timetable::from_csv(
+        "groups,day,time,subject,type,distribution
+ІМ-01,Понеділок,08:30,Матан,Лекція,перший
+ІМ-01,Нд,10:25,Матан,лабораторна робота,Обидва
+ІМ-01,Среда,12:20,Матан,практика,вторая
+ІМ-01,Thursday,14:15,Матан,laboratory work,second
+ІМ-01,Fri,16:10,Матан,Lecture,both
+"
+        .as_bytes(),
+    )
+    .unwrap();
+    let parsed: Vec<(Day, LessonType, Distribution)> = timetable
+        .lessons
+        .iter()
+        .map(|lesson| (lesson.day, lesson.lesson_type, lesson.distribution))
+        .collect();
+    assert_eq!(
+        parsed,
+        vec![
+            (Day::Monday, LessonType::Lecture, Distribution::First),
+            (Day::Sunday, LessonType::LaboratoryWork, Distribution::All),
+            (Day::Wednesday, LessonType::Practice, Distribution::Second),
+            (
+                Day::Thursday,
+                LessonType::LaboratoryWork,
+                Distribution::Second
+            ),
+            (Day::Friday, LessonType::Lecture, Distribution::All),
+        ]
+    );
+}
+
+#[test]
+fn rejects_invalid_rows() {
+    assert!(error("groups,day,time,subject\nІМ-01,Пн,08:30,Матан\n").contains("type"));
+    assert!(error("").contains("пуст"));
+    let row = |values: &str| format!("groups,day,time,subject,type,teacher\n{}\n", values);
+    assert!(error(&row("ІМ-01,Пн,8.30,Матан,lecture,")).contains("Строка 2"));
+    assert!(error(&row(",Пн,08:30,Матан,lecture,")).contains("группы"));
+    assert!(error(&row("ІМ-01,Пн,08:30,Матан,семинар,")).contains("семинар"));
+    assert!(error(&row("ІМ-01,Пн,08:30,Матан,lecture,Іванов")).contains("Іванов"));
+    assert!(Timetable::from_named("timetable.txt", b"").is_err());
+}
+
+const SUBJECTS: &str = "'Дискретна математика', 'Машинне навчання', 'Теорія графів'";
+
+async fn count(database: &Database, query: &str) -> i64 {
+    sqlx::query(query)
+        .fetch_one(database.pool())
+        .await
+        .unwrap()
+        .get(0)
+}
+
+/// Removes what the import adds, so that the other tests see only their own rows.
+async fn clean(database: &Database) {
+    database
+        .pool()
+        .execute(
+            format!(
+                r#"
+            DELETE FROM lessons_groups WHERE lesson IN (
+                SELECT schedule.id FROM schedule JOIN subjects ON subjects.id = schedule.subject
+                WHERE subjects.name IN ({0})
+            );
+            DELETE FROM schedule WHERE subject IN (SELECT id FROM subjects WHERE name IN ({0}));
+            DELETE FROM subjects WHERE name IN ({0});
+            DELETE FROM teachers WHERE last_name = 'Коваль';
+            DELETE FROM groups WHERE name IN ('ІМ-01', 'ІМ-02');
+            "#,
+                SUBJECTS
+            )
+            .as_str(),
+        )
+        .await
+        .unwrap();
+}
+
+/// Imports the fixtures into a scratch database. The database named by
+/// `SCHEDULE_TEST_DATABASE_URL` is modified, so the test is skipped when it is unset.
+#[tokio::test]
+async fn dry_run_and_apply() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    schedule_core::database::initialize(&url, 1).await.unwrap();
+    let database = Database::global();
+    clean(database).await;
+
+    let timetable = fixture("timetable.csv");
+    let report = database.import_timetable(&timetable, true).await.unwrap();
+    assert_eq!(report.groups, vec!["ІМ-01", "ІМ-02"]);
+    assert_eq!(report.subjects.len(), 2, "{}", report);
+    assert_eq!(report.teachers, vec!["Коваль Олена Петрівна"]);
+    assert_eq!(report.added.len(), 4, "{}", report);
+    assert_eq!(
+        count(
+            database,
+            "SELECT count(*) FROM groups WHERE name IN ('ІМ-01', 'ІМ-02')"
+        )
+        .await,
+        0
+    );
+
+    let applied = database.import_timetable(&timetable, false).await.unwrap();
+    assert_eq!(applied.added.len(), 4);
+    assert_eq!(
+        count(
+            database,
+            &format!(
+                "SELECT count(*) FROM schedule JOIN subjects ON subjects.id = schedule.subject WHERE subjects.name IN ({})",
+                SUBJECTS
+            )
+        )
+        .await,
+        4
+    );
+
+    // The practice of ІМ-01 moves to another room, that of ІМ-02 is gone and a lesson is new.
+    let mut updated = Timetable::from_csv(
+        "groups,day,time,subject,type,teacher,distribution,room,info,choice
+\"ІМ-01, ІМ-02\",Пн,08:30,Дискретна математика,lecture,Коваль Олена Петрівна,,301,,
+ІМ-01,tuesday,10:25,Дискретна математика,practice,Коваль Олена Петрівна,1,305,,
+\"ІМ-01, ІМ-02\",Ср,12:20,Машинне навчання,laboratory_work,,2,,онлайн,да
+"
+        .as_bytes(),
+    )
+    .unwrap();
+    updated.extend(fixture("timetable.xlsx"));
+    let report = database.import_timetable(&updated, true).await.unwrap();
+    assert_eq!(report.changed.len(), 1, "{}", report);
+    assert!(report.changed[0].contains("302") && report.changed[0].contains("305"));
+    assert_eq!(report.removed.len(), 1, "{}", report);
+    assert!(report.removed[0].starts_with("ІМ-02"));
+    assert_eq!(report.added.len(), 2, "{}", report);
+    assert_eq!(report.subjects, vec!["Теорія графів"]);
+    assert!(report.teachers.is_empty());
+    assert_eq!(report.unchanged, 2);
+
+    database.import_timetable(&updated, false).await.unwrap();
+    let again = database.import_timetable(&updated, true).await.unwrap();
+    assert!(!again.has_changes(), "{}", again);
+    assert_eq!(again.unchanged, 5);
+
+    // A file of one group takes only that group off the lessons it shares.
+    let alone = Timetable::from_csv(
+        "groups,day,time,subject,type\nІМ-01,Чт,10:25,Теорія графів,lecture\n".as_bytes(),
+    )
+    .unwrap();
+    let report = database.import_timetable(&alone, false).await.unwrap();
+    assert_eq!(report.removed.len(), 5, "{}", report);
+    assert_eq!(report.added.len(), 1, "{}", report);
+    assert_eq!(
+        count(
+            database,
+            r#"
+            SELECT count(*) FROM lessons_groups JOIN groups ON groups.id = lessons_groups."group"
+            WHERE groups.name = 'ІМ-02'
+            "#
+        )
+        .await,
+        2
+    );
+
+    clean(database).await;
+}
@@ -0,0 +1,95 @@
+use chrono::NaiveTime;
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::{Lesson, LessonType, Lessons};
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::{self, Language, Localize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::str::FromStr;
+
+fn message_ids(language: Language) -> BTreeSet<String> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("locales")
+        .join(format!("{}.ftl", language));
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split(" =").next())
+        .filter(|id| !id.is_empty() && !id.starts_with(char::is_whitespace))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn lesson() -> Lesson {
+    Lesson {
+        subject_name: "Матан".to_string(),
+        lesson_type: LessonType::LaboratoryWork,
+        time: NaiveTime::from_hms_opt(10, 25, 0).unwrap(),
+        teacher_name: "Іванов Іван".to_string(),
+        info: String::new(),
+        room: "305".to_string(),
+    }
+}
+
+#[test]
+fn every_language_has_every_message() {
+    let expected = message_ids(Language::default());
+    assert!(!expected.is_empty());
+    for language in Language::all().iter() {
+        assert_eq!(message_ids(*language), expected, "{}", language);
+    }
+}
+
+#[test]
+fn lessons_in_each_language() {
+    assert_eq!(
+        lesson().localize(Language::Uk),
+        "Матан (лабораторна робота): [10:25]\nВикладач: Іванов Іван\n\nАудиторія: 305"
+    );
+    assert_eq!(
+        lesson().localize(Language::En),
+        "Матан (laboratory work): [10:25]\nTeacher: Іванов Іван\n\nRoom: 305"
+    );
+    assert_eq!(lesson().to_string(), lesson().localize(Language::Ru));
+    assert_eq!(Lessons(vec![]).localize(Language::En), "Day off");
+    assert_eq!(WeekType::Second.localize(Language::Uk), "другий");
+}
+
+#[test]
+fn days_are_parsed_in_any_language() {
+    for language in Language::all().iter() {
+        for day in Day::all().iter() {
+            assert_eq!(Day::from_str(&day.short_name(*language)), Ok(*day));
+        }
+    }
+    assert_eq!(Day::from_str("Нд"), Ok(Day::Sunday));
+    assert_eq!(Day::from_str("Вс"), Ok(Day::Sunday));
+    assert!(Day::from_str("Sunday").is_err());
+}
+
+#[test]
+fn language_codes() {
+    assert_eq!(Language::from_code("uk"), Some(Language::Uk));
+    assert_eq!(Language::from_code("en-US"), Some(Language::En));
+    assert_eq!(Language::from_code("RU"), Some(Language::Ru));
+    assert_eq!(Language::from_code("de"), None);
+    assert_eq!(Language::from_str("en"), Ok(Language::En));
+    assert!(Language::from_str("english").is_err());
+}
+
+#[test]
+fn missing_messages_fall_back() {
+    let messages = locale::Messages::new(&[(Language::Ru, "only-russian = Только по-русски")]);
+    assert_eq!(
+        messages.get(Language::En, "only-russian"),
+        "Только по-русски"
+    );
+    assert_eq!(
+        messages.get(Language::Uk, "no-such-message"),
+        "no-such-message"
+    );
+    assert_eq!(
+        messages.format(Language::En, "current-week", Vec::new()),
+        "current-week"
+    );
+}
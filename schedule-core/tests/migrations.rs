@@ -0,0 +1,154 @@
+use schedule_core::database::MIGRATOR;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Executor, Row};
+use std::path::Path;
+
+const SCHEMA: &str = "migrations_test";
+const BASELINE_SCHEMA: &str = "migrations_baseline_test";
+
+/// Connects with `schema` as the only schema on the search path, recreating it empty.
+async fn connect(url: &str, schema: &'static str) -> PgPool {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .after_connect(move |connection| {
+            Box::pin(async move {
+                connection
+                    .execute(format!("SET search_path TO {}", schema).as_str())
+                    .await?;
+                Ok(())
+            })
+        })
+        .connect(url)
+        .await
+        .unwrap();
+    pool.execute(
+        format!(
+            "DROP SCHEMA IF EXISTS {0} CASCADE; CREATE SCHEMA {0}",
+            schema
+        )
+        .as_str(),
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+#[test]
+fn embeds_every_migration_in_order() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../migrations");
+    let mut files: Vec<String> = std::fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    let embedded: Vec<String> = MIGRATOR
+        .iter()
+        .map(|migration| {
+            let description = migration.description.replace(' ', "_");
+            format!("{:04}_{}.sql", migration.version, description)
+        })
+        .collect();
+    assert_eq!(files, embedded);
+}
+
+/// Builds the schema from scratch in a separate schema of the database named by
+/// `SCHEDULE_TEST_DATABASE_URL`, so the test is skipped when it is unset.
+#[tokio::test]
+async fn builds_schema_from_scratch() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    let pool = connect(&url, SCHEMA).await;
+
+    MIGRATOR.run(&pool).await.unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    let applied: i64 = sqlx::query("SELECT count(*) FROM _sqlx_migrations")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(applied as usize, MIGRATOR.iter().count());
+
+    pool.execute(
+        r#"
+        INSERT INTO groups(id, name) VALUES (1, 'ФІ-91');
+        INSERT INTO students(id, telegram_id, "group", last_name) VALUES (1, 100, 1, '');
+        "#,
+    )
+    .await
+    .unwrap();
+    let group: i32 = sqlx::query(r#"SELECT "group" FROM students WHERE telegram_id = 100"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(group, 1);
+
+    pool.execute("UPDATE _sqlx_migrations SET checksum = '\\x00' WHERE version = 1")
+        .await
+        .unwrap();
+    assert!(MIGRATOR.run(&pool).await.is_err());
+
+    pool.execute(format!("DROP SCHEMA {} CASCADE", SCHEMA).as_str())
+        .await
+        .unwrap();
+}
+
+/// Migrates a database created from the old `script.sql`, in a separate schema of the database
+/// named by `SCHEDULE_TEST_DATABASE_URL`, so the test is skipped when it is unset.
+#[tokio::test]
+async fn upgrades_baseline_schema() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    let pool = connect(&url, BASELINE_SCHEMA).await;
+    let baseline = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/baseline.sql");
+    pool.execute(std::fs::read_to_string(baseline).unwrap().as_str())
+        .await
+        .unwrap();
+    pool.execute(
+        r#"
+        INSERT INTO groups(id, name) VALUES (1, 'ФІ-91');
+        INSERT INTO subjects(id, name, choice) VALUES (1, 'Матан', FALSE);
+        INSERT INTO schedule(id, subject, type, day_of_week, time, distribution, info)
+        VALUES (1, 1, 'lecture', 'monday', '08:30', 'all', '');
+        INSERT INTO lessons_groups(lesson, "group") VALUES (1, 1);
+        INSERT INTO students(id, telegram_id, "group", last_name) VALUES (1, 100, 1, '');
+        "#,
+    )
+    .await
+    .unwrap();
+
+    MIGRATOR.run(&pool).await.unwrap();
+    let functions: i64 = sqlx::query(
+        r#"
+        SELECT count(*) FROM pg_proc JOIN pg_namespace ON pg_namespace.oid = pg_proc.pronamespace
+        WHERE nspname = $1 AND proname LIKE 'get\_%schedule'
+        "#,
+    )
+    .bind(BASELINE_SCHEMA)
+    .fetch_one(&pool)
+    .await
+    .unwrap()
+    .get(0);
+    assert_eq!(functions, 0);
+    let row = sqlx::query(
+        r#"
+        SELECT students."group", schedule.room FROM students
+        JOIN lessons_groups ON lessons_groups."group" = students."group"
+        JOIN schedule ON schedule.id = lessons_groups.lesson
+        WHERE students.telegram_id = 100
+        "#,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<i32, _>(0), 1);
+    assert_eq!(row.get::<Option<String>, _>(1), None);
+
+    pool.execute(format!("DROP SCHEMA {} CASCADE", BASELINE_SCHEMA).as_str())
+        .await
+        .unwrap();
+}
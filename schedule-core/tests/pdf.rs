@@ -0,0 +1,113 @@
+use chrono::NaiveTime;
+use lopdf::content::Content;
+use lopdf::{Document, Object};
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::{Lesson, LessonType, Lessons, LessonsWeek};
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::Language;
+use schedule_core::render::pdf::timetable_pdf;
+use std::collections::BTreeMap;
+
+fn week(subject: &str, day: Day) -> LessonsWeek {
+    let mut days = BTreeMap::new();
+    days.insert(
+        day,
+        Lessons(vec![Lesson {
+            subject_name: subject.to_string(),
+            lesson_type: LessonType::Lecture,
+            time: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+            teacher_name: "Іванов Іван Іванович".to_string(),
+            info: String::new(),
+            room: "305".to_string(),
+        }]),
+    );
+    LessonsWeek(days)
+}
+
+fn weeks() -> Vec<(WeekType, LessonsWeek)> {
+    vec![
+        (WeekType::First, week("Математичний аналіз", Day::Monday)),
+        (WeekType::Second, week("Фізика", Day::Sunday)),
+    ]
+}
+
+/// Text shown on the page, decoded through the `ToUnicode` maps of its fonts; each shown string
+/// on a line.
+fn page_text(document: &Document, page: u32) -> String {
+    let page_id = document.get_pages()[&page];
+    let maps: BTreeMap<Vec<u8>, BTreeMap<u16, char>> = document
+        .get_page_fonts(page_id)
+        .unwrap()
+        .into_iter()
+        .map(|(name, font)| {
+            let id = font.get(b"ToUnicode").unwrap().as_reference().unwrap();
+            let cmap = &document
+                .get_object(id)
+                .unwrap()
+                .as_stream()
+                .unwrap()
+                .content;
+            let map = String::from_utf8_lossy(cmap)
+                .lines()
+                .filter_map(|line| {
+                    let (glyph, c) = line.strip_prefix('<')?.split_once("> <")?;
+                    let c = u32::from_str_radix(c.strip_suffix('>')?, 16).ok()?;
+                    Some((u16::from_str_radix(glyph, 16).ok()?, char::from_u32(c)?))
+                })
+                .collect();
+            (name, map)
+        })
+        .collect();
+    let content = Content::decode(&document.get_page_content(page_id).unwrap()).unwrap();
+    let mut font = vec![];
+    let mut text = String::new();
+    for operation in content.operations {
+        match operation.operator.as_str() {
+            "Tf" => font = operation.operands[0].as_name().unwrap().to_vec(),
+            "TJ" => {
+                for item in operation.operands[0].as_array().unwrap() {
+                    if let Object::String(bytes, _) = item {
+                        for glyph in bytes.chunks(2) {
+                            text.push(maps[&font][&u16::from_be_bytes([glyph[0], glyph[1]])]);
+                        }
+                    }
+                }
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+#[test]
+fn page_per_week_with_searchable_text() {
+    let pdf = timetable_pdf("Група ІП-01", &weeks(), Language::Uk.into()).unwrap();
+    let document = Document::load_mem(&pdf).unwrap();
+    let pages = document.get_pages();
+    assert_eq!(pages.len(), 2);
+
+    let first = page_text(&document, 1);
+    assert!(first.contains("Група ІП-01"), "{}", first);
+    assert!(first.contains("перший"), "{}", first);
+    assert!(first.contains("Математичний"), "{}", first);
+    assert!(!first.contains("Фізика"));
+    let second = page_text(&document, 2);
+    assert!(second.contains("Фізика"), "{}", second);
+    assert!(second.contains("Неділя"), "{}", second);
+}
+
+#[test]
+fn only_used_glyphs_are_embedded() {
+    let pdf = timetable_pdf("Група ІП-01", &weeks(), Language::Uk.into()).unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    // The two fonts are over a megabyte in full.
+    assert!(pdf.len() < 200_000, "{} bytes", pdf.len());
+}
+
+#[test]
+fn no_weeks_no_pages() {
+    let pdf = timetable_pdf("Група ІП-01", &[], Language::En.into()).unwrap();
+    let document = Document::load_mem(&pdf).unwrap();
+    assert!(document.get_pages().is_empty());
+}
@@ -0,0 +1,276 @@
+use chrono::NaiveTime;
+use schedule_core::database::day::Day;
+use schedule_core::database::homework::{Attachment, Homework, HomeworkFile, LessonsHomework};
+use schedule_core::database::lesson::{Lesson, LessonType, Lessons, LessonsWeek};
+use schedule_core::database::settings::{LessonFormat, UserSettings};
+use schedule_core::locale::Language;
+use schedule_core::render::{escape, split, Html, Style, MESSAGE_LIMIT};
+use std::collections::BTreeMap;
+
+fn lesson(lesson_type: LessonType, info: &str) -> Lesson {
+    Lesson {
+        subject_name: "Матан <основи>".to_string(),
+        lesson_type,
+        time: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+        teacher_name: "Іванов Іван".to_string(),
+        info: info.to_string(),
+        room: "305".to_string(),
+    }
+}
+
+#[test]
+fn escapes_markup() {
+    assert_eq!(
+        escape(r#"<b>"A" & B</b>"#),
+        "&lt;b&gt;&quot;A&quot; &amp; B&lt;/b&gt;"
+    );
+    assert_eq!(escape("Пн 08:30"), "Пн 08:30");
+}
+
+#[test]
+fn lesson_with_every_field() {
+    assert_eq!(
+        lesson(LessonType::Lecture, "Zoom: a&b").html(Language::Uk.into()),
+        "📖 <b>Матан &lt;основи&gt;</b> (лекція)\n\
+         🕒 08:30 · Аудиторія: 305\n\
+         Викладач: Іванов Іван\n\
+         <i>Zoom: a&amp;b</i>"
+    );
+}
+
+#[test]
+fn empty_fields_are_left_out() {
+    let mut lesson = lesson(LessonType::LaboratoryWork, "  ");
+    lesson.room.clear();
+    lesson.teacher_name.clear();
+    assert_eq!(
+        lesson.html(Language::En.into()),
+        "🔬 <b>Матан &lt;основи&gt;</b> (laboratory work)\n🕒 08:30"
+    );
+}
+
+#[test]
+fn teacher_can_be_hidden() {
+    let settings = UserSettings {
+        show_teacher: false,
+        ..UserSettings::default()
+    };
+    assert_eq!(
+        lesson(LessonType::Lecture, "").html(Style::new(Language::En, &settings)),
+        "📖 <b>Матан &lt;основи&gt;</b> (lecture)\n🕒 08:30 · Room: 305"
+    );
+}
+
+#[test]
+fn compact_lessons_take_a_line_each() {
+    let compact = UserSettings {
+        lesson_format: LessonFormat::Compact,
+        ..UserSettings::default()
+    };
+    let lessons = Lessons(vec![
+        lesson(LessonType::Lecture, "Zoom"),
+        lesson(LessonType::Practice, ""),
+    ]);
+    assert_eq!(
+        lessons.html_parts(Style::new(Language::Uk, &compact)),
+        vec![
+            "08:30 <b>Матан &lt;основи&gt;</b> (лекц.) · 305 — Іванов І.\n\
+             08:30 <b>Матан &lt;основи&gt;</b> (практ.) · 305 — Іванов І."
+        ]
+    );
+
+    let mut days = BTreeMap::new();
+    days.insert(Day::Monday, lessons);
+    let hidden = UserSettings {
+        show_teacher: false,
+        ..compact
+    };
+    assert_eq!(
+        LessonsWeek(days).html(Style::new(Language::En, &hidden)),
+        "<b><u>Monday</u></b>\n\
+         08:30 <b>Матан &lt;основи&gt;</b> (lect.) · 305\n\
+         08:30 <b>Матан &lt;основи&gt;</b> (pract.) · 305"
+    );
+}
+
+#[test]
+fn compact_teacher_has_initials() {
+    let mut lesson = lesson(LessonType::LaboratoryWork, "");
+    lesson.teacher_name = "Іванов Іван Іванович".to_string();
+    lesson.room.clear();
+    let compact = UserSettings {
+        lesson_format: LessonFormat::Compact,
+        ..UserSettings::default()
+    };
+    assert_eq!(
+        lesson.html(Style::new(Language::Ru, &compact)),
+        "08:30 <b>Матан &lt;основи&gt;</b> (лаб.) — Іванов І.І."
+    );
+}
+
+#[test]
+fn verbose_shows_empty_fields() {
+    let mut lesson = lesson(LessonType::Practice, " ");
+    lesson.room.clear();
+    lesson.teacher_name.clear();
+    let verbose = UserSettings {
+        lesson_format: LessonFormat::Verbose,
+        ..UserSettings::default()
+    };
+    assert_eq!(
+        lesson.html(Style::new(Language::En, &verbose)),
+        "✏️ <b>Матан &lt;основи&gt;</b> (practice)\n\
+         🕒 08:30 · Room: —\n\
+         Teacher: —\n\
+         <i>—</i>"
+    );
+}
+
+#[test]
+fn week_is_split_into_days() {
+    let mut days = BTreeMap::new();
+    days.insert(
+        Day::Tuesday,
+        Lessons(vec![lesson(LessonType::Practice, "")]),
+    );
+    days.insert(
+        Day::Monday,
+        Lessons(vec![
+            lesson(LessonType::Lecture, ""),
+            lesson(LessonType::Practice, ""),
+        ]),
+    );
+    let html = LessonsWeek(days).html(Language::Ru.into());
+    assert!(html.starts_with("<b><u>Понедельник</u></b>\n\n📖 <b>"));
+    assert!(html.contains("\n\n✏️ <b>"));
+    assert!(html.contains("\n\n<b><u>Вторник</u></b>\n\n✏️ <b>"));
+    assert!(!html.ends_with('\n'));
+}
+
+#[test]
+fn homework_is_under_the_first_lesson_of_its_subject() {
+    let mut other = lesson(LessonType::Practice, "");
+    other.subject_name = "Фізика".to_string();
+    let homework = LessonsHomework {
+        lessons: Lessons(vec![
+            lesson(LessonType::Lecture, ""),
+            lesson(LessonType::Practice, ""),
+            other,
+        ]),
+        homework: vec![Homework {
+            subject: 1,
+            subject_name: "Матан <основи>".to_string(),
+            text: " Задачі 1–5 ".to_string(),
+            files: vec![HomeworkFile {
+                file_id: "file".to_string(),
+                kind: Attachment::Photo,
+            }],
+        }],
+    };
+    let parts = homework.html_parts(Language::Uk.into());
+    assert_eq!(parts.len(), 3);
+    assert!(parts[0].ends_with("Викладач: Іванов Іван\n📝 Задачі 1–5 📎 1"));
+    assert!(!parts[1].contains("📝") && !parts[2].contains("📝"));
+
+    let compact = UserSettings {
+        lesson_format: LessonFormat::Compact,
+        ..UserSettings::default()
+    };
+    assert_eq!(
+        homework.html(Style::new(Language::Uk, &compact)),
+        "08:30 <b>Матан &lt;основи&gt;</b> (лекц.) · 305 — Іванов І.\n\
+         📝 Задачі 1–5 📎 1\n\
+         08:30 <b>Матан &lt;основи&gt;</b> (практ.) · 305 — Іванов І.\n\
+         08:30 <b>Фізика</b> (практ.) · 305 — Іванов І."
+    );
+}
+
+#[test]
+fn nothing_to_show() {
+    assert_eq!(Lessons(vec![]).html(Language::Uk.into()), "Вихідний день");
+    assert_eq!(
+        LessonsWeek(BTreeMap::new()).html(Language::En.into()),
+        "No lessons this week"
+    );
+}
+
+fn long_week(days: &[Day], lessons: usize) -> LessonsWeek {
+    LessonsWeek(
+        days.iter()
+            .map(|day| {
+                let info = "Посилання на конференцію та матеріали курсу. ".repeat(20);
+                let lessons = (0..lessons)
+                    .map(|_| lesson(LessonType::Lecture, &info))
+                    .collect();
+                (*day, Lessons(lessons))
+            })
+            .collect(),
+    )
+}
+
+fn utf16(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+#[test]
+fn short_text_is_one_message() {
+    let lessons = Lessons(vec![lesson(LessonType::Lecture, "")]);
+    assert_eq!(
+        split(lessons.html_parts(Language::Ru.into()), MESSAGE_LIMIT),
+        vec![lessons.html(Language::Ru.into())]
+    );
+}
+
+#[test]
+fn long_week_is_split_between_lessons() {
+    let week = long_week(&[Day::Monday, Day::Tuesday, Day::Wednesday], 6);
+    let parts = week.html_parts(Language::Uk.into());
+    assert_eq!(parts.len(), 18);
+    let messages = split(parts.clone(), MESSAGE_LIMIT);
+    assert!(messages.len() > 1);
+    assert!(messages
+        .iter()
+        .all(|message| utf16(message) <= MESSAGE_LIMIT));
+    assert_eq!(messages.join("\n\n"), week.html(Language::Uk.into()));
+    for message in &messages {
+        assert!(parts.iter().any(|part| message.starts_with(part.as_str())));
+    }
+    assert_eq!(
+        messages
+            .iter()
+            .filter(|message| message.contains("<b><u>"))
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn long_lesson_is_cut_keeping_tags_balanced() {
+    let info = "дуже довгий опис & посилання ".repeat(400);
+    let lessons = Lessons(vec![lesson(LessonType::Practice, &info)]);
+    let messages = split(lessons.html_parts(Language::En.into()), MESSAGE_LIMIT);
+    assert!(messages.len() >= 3);
+    for message in &messages {
+        assert!(utf16(message) <= MESSAGE_LIMIT);
+        assert_eq!(
+            message.matches("<i>").count(),
+            message.matches("</i>").count()
+        );
+        assert_eq!(
+            message.matches("&amp").count(),
+            message.matches("&amp;").count()
+        );
+    }
+    assert!(messages[0].starts_with("✏️ <b>"));
+    assert!(messages[1].starts_with("<i>"));
+    assert!(messages
+        .concat()
+        .replace("</i><i>", "")
+        .contains(&escape(info.trim())));
+}
+
+#[test]
+fn small_limits_still_make_progress() {
+    let messages = split(vec!["<b>abcdef</b>".to_string()], 10);
+    assert_eq!(messages, vec!["<b>abc</b>", "<b>def</b>"]);
+}
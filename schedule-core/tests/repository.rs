@@ -0,0 +1,858 @@
+use chrono::{Datelike, Duration, Local, NaiveTime};
+use schedule_core::database::announcement::{Announcement, Audience};
+use schedule_core::database::day::Day;
+use schedule_core::database::homework::{Attachment, HomeworkFile};
+use schedule_core::database::lesson::LessonType;
+use schedule_core::database::role::Role;
+use schedule_core::database::settings::{LessonFormat, UserSettings};
+use schedule_core::database::week::WeekType;
+use schedule_core::database::Database;
+use schedule_core::exchange::TimetableExport;
+use schedule_core::locale::Language;
+use schedule_core::repository::{MemoryRepository, Repository};
+use sqlx::Executor;
+use std::path::{Path, PathBuf};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/memory.yaml")
+}
+
+fn repository() -> MemoryRepository {
+    MemoryRepository::from_file(&fixture()).unwrap()
+}
+
+async fn subjects(repository: &MemoryRepository, user_id: i64, week: WeekType) -> Vec<String> {
+    repository
+        .get_week_schedule(user_id, week)
+        .await
+        .unwrap()
+        .0
+        .values()
+        .flat_map(|lessons| lessons.0.iter().map(|lesson| lesson.subject_name.clone()))
+        .collect()
+}
+
+#[tokio::test]
+async fn week_of_a_group() {
+    let repository = repository();
+    let week = repository
+        .get_week_schedule(200, WeekType::First)
+        .await
+        .unwrap();
+    let days: Vec<Day> = week.0.keys().copied().collect();
+    assert_eq!(days, vec![Day::Monday, Day::Tuesday]);
+    let practice = &week.0[&Day::Tuesday].0[0];
+    assert_eq!(practice.lesson_type, LessonType::Practice);
+    assert_eq!(practice.teacher_name, "Петренко Петро Петрович");
+    assert_eq!(practice.room, "412");
+
+    assert_eq!(
+        subjects(&repository, 300, WeekType::Second).await,
+        vec!["Математичний аналіз", "Фізика"]
+    );
+}
+
+#[tokio::test]
+async fn electives_only_for_enrolled_students() {
+    let repository = repository();
+    assert_eq!(
+        subjects(&repository, 100, WeekType::Second).await,
+        vec!["Математичний аналіз", "Теорія ігор"]
+    );
+    assert_eq!(
+        subjects(&repository, 200, WeekType::Second).await,
+        vec!["Математичний аналіз"]
+    );
+}
+
+#[tokio::test]
+async fn unknown_users_have_no_lessons() {
+    let repository = repository();
+    assert!(subjects(&repository, 999, WeekType::First).await.is_empty());
+    let lessons = repository.get_schedule_by_day(Day::Monday, 999).await;
+    assert!(lessons.unwrap().0.is_empty());
+    assert_eq!(repository.get_calendar_token(999).await.unwrap(), None);
+    assert!(!repository.is_student(999).await.unwrap());
+    assert!(repository.is_student(300).await.unwrap());
+}
+
+#[tokio::test]
+async fn calendar_token_is_kept() {
+    let repository = repository();
+    let token = repository.get_calendar_token(100).await.unwrap().unwrap();
+    assert_eq!(token.len(), 32);
+    assert_eq!(
+        repository.get_calendar_token(100).await.unwrap(),
+        Some(token.clone())
+    );
+    assert_ne!(
+        repository.get_calendar_token(200).await.unwrap(),
+        Some(token)
+    );
+}
+
+#[tokio::test]
+async fn distribution_week_follows_start() {
+    let today = Local::now().naive_local().date();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let repository = repository().with_distribution_start(monday);
+    assert!(matches!(
+        repository.get_distribution_week().await.unwrap(),
+        WeekType::First
+    ));
+    let repository = repository.with_distribution_start(monday - Duration::weeks(3));
+    assert!(matches!(
+        repository.get_distribution_week().await.unwrap(),
+        WeekType::Second
+    ));
+}
+
+#[tokio::test]
+async fn dialogues_are_stored() {
+    let repository = repository();
+    assert_eq!(repository.get_dialogue(1).await.unwrap(), None);
+    repository
+        .update_dialogue(1, b"start".to_vec())
+        .await
+        .unwrap();
+    repository
+        .update_dialogue(1, b"day".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.get_dialogue(1).await.unwrap(),
+        Some(b"day".to_vec())
+    );
+    repository.remove_dialogue(1).await.unwrap();
+    assert_eq!(repository.get_dialogue(1).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn languages_are_stored() {
+    let repository = repository();
+    assert_eq!(repository.get_language(999).await.unwrap(), None);
+    repository.set_language(999, Language::En).await.unwrap();
+    repository.set_language(999, Language::Uk).await.unwrap();
+    assert_eq!(
+        repository.get_language(999).await.unwrap(),
+        Some(Language::Uk)
+    );
+}
+
+fn settings() -> UserSettings {
+    UserSettings {
+        language: Some(Language::En),
+        reminder_minutes: Some(15),
+        digest_time: NaiveTime::from_hms_opt(20, 0, 0),
+        lesson_format: LessonFormat::Compact,
+        show_teacher: false,
+    }
+}
+
+#[tokio::test]
+async fn settings_are_stored() {
+    let repository = repository();
+    assert_eq!(
+        repository.get_settings(999).await.unwrap(),
+        UserSettings::default()
+    );
+    repository.set_settings(999, settings()).await.unwrap();
+    repository.set_language(999, Language::Uk).await.unwrap();
+    assert_eq!(
+        repository.get_settings(999).await.unwrap(),
+        UserSettings {
+            language: Some(Language::Uk),
+            ..settings()
+        }
+    );
+
+    assert_eq!(repository.get_subscribers().await.unwrap(), vec![]);
+    repository.set_settings(300, settings()).await.unwrap();
+    repository
+        .set_settings(200, UserSettings::default())
+        .await
+        .unwrap();
+    repository
+        .set_settings(
+            100,
+            UserSettings {
+                reminder_minutes: None,
+                ..settings()
+            },
+        )
+        .await
+        .unwrap();
+    let subscribers = repository.get_subscribers().await.unwrap();
+    let users: Vec<i64> = subscribers.iter().map(|(user_id, _)| *user_id).collect();
+    assert_eq!(users, vec![100, 300]);
+    assert_eq!(subscribers[1].1, settings());
+}
+
+#[tokio::test]
+async fn roles_are_stored() {
+    let repository = repository();
+    assert_eq!(repository.get_roles(999).await.unwrap(), vec![]);
+    repository.grant_role(999, Role::Teacher).await.unwrap();
+    repository.grant_role(999, Role::Admin).await.unwrap();
+    repository.grant_role(999, Role::Admin).await.unwrap();
+    assert_eq!(
+        repository.get_roles(999).await.unwrap(),
+        vec![Role::Admin, Role::Teacher]
+    );
+    assert!(repository.revoke_role(999, Role::Admin).await.unwrap());
+    assert!(!repository.revoke_role(999, Role::Admin).await.unwrap());
+    assert_eq!(
+        repository.get_roles(999).await.unwrap(),
+        vec![Role::Teacher]
+    );
+}
+
+#[tokio::test]
+async fn students_have_the_student_role() {
+    let repository = repository();
+    repository.grant_role(100, Role::GroupLeader).await.unwrap();
+    assert_eq!(
+        repository.get_roles(100).await.unwrap(),
+        vec![Role::GroupLeader, Role::Student]
+    );
+}
+
+#[tokio::test]
+async fn recipients_of_audiences() {
+    let repository = repository();
+    assert_eq!(
+        repository.get_recipients(Audience::Group(1)).await.unwrap(),
+        vec![100, 200]
+    );
+    assert_eq!(
+        repository
+            .get_recipients(Audience::Subject(2))
+            .await
+            .unwrap(),
+        vec![100]
+    );
+    repository.grant_role(50, Role::Teacher).await.unwrap();
+    repository.set_language(400, Language::En).await.unwrap();
+    assert_eq!(
+        repository.get_recipients(Audience::Everyone).await.unwrap(),
+        vec![50, 100, 200, 300, 400]
+    );
+    assert_eq!(
+        repository
+            .get_student_group(300)
+            .await
+            .unwrap()
+            .map(|group| group.name),
+        Some("ФІ-92".to_string())
+    );
+    assert_eq!(repository.get_student_group(999).await.unwrap(), None);
+    let electives = repository.get_elective_subjects().await.unwrap();
+    assert_eq!(
+        electives
+            .iter()
+            .map(|subject| subject.id)
+            .collect::<Vec<_>>(),
+        vec![2]
+    );
+}
+
+#[tokio::test]
+async fn teachers_reach_the_students_they_teach() {
+    let repository = repository();
+    assert!(!repository.link_teacher(50, 9).await.unwrap());
+    assert!(repository.link_teacher(50, 2).await.unwrap());
+    assert!(repository.link_teacher(60, 2).await.unwrap());
+    assert_eq!(repository.get_linked_teacher(50).await.unwrap(), None);
+    assert_eq!(
+        repository
+            .get_linked_teacher(10)
+            .await
+            .unwrap()
+            .map(|teacher| teacher.full_name()),
+        Some("Іванов Іван Іванович".to_string())
+    );
+    assert_eq!(
+        repository
+            .get_linked_teacher(60)
+            .await
+            .unwrap()
+            .map(|teacher| teacher.full_name()),
+        Some("Петренко Петро Петрович".to_string())
+    );
+    let taught = repository.get_taught_groups(1).await.unwrap();
+    assert_eq!(
+        taught
+            .iter()
+            .map(|taught| (taught.subject, taught.group_name.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "ФІ-91"), (1, "ФІ-92")]
+    );
+    let taught = |teacher, subject, group| Audience::Taught {
+        teacher,
+        subject,
+        group,
+    };
+    for (audience, recipients) in [
+        (taught(1, 1, None), vec![100, 200, 300]),
+        (taught(1, 1, Some(2)), vec![300]),
+        (taught(2, 2, None), vec![100]),
+        (taught(2, 3, None), vec![]),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            repository.get_recipients(*audience).await.unwrap(),
+            *recipients,
+            "{:?}",
+            audience
+        );
+    }
+}
+
+#[tokio::test]
+async fn announcements_are_logged_for_recipients() {
+    let repository = repository();
+    for text in ["Перша", "Друга"].iter() {
+        repository
+            .save_announcement(60, "Петренко".to_string(), text.to_string(), vec![100, 200])
+            .await
+            .unwrap();
+    }
+    repository
+        .save_announcement(60, "Петренко".to_string(), "Третя".to_string(), vec![300])
+        .await
+        .unwrap();
+    let texts = |announcements: Vec<_>| {
+        announcements
+            .into_iter()
+            .map(|announcement: Announcement| announcement.text)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        texts(repository.get_announcements(100, 10).await.unwrap()),
+        vec!["Друга", "Перша"]
+    );
+    assert_eq!(
+        texts(repository.get_announcements(200, 1).await.unwrap()),
+        vec!["Друга"]
+    );
+    assert!(repository
+        .get_announcements(999, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn queues_call_students_in_turn() {
+    let repository = repository();
+    let labs = repository.get_lab_lessons(300).await.unwrap();
+    assert_eq!(labs.iter().map(|lab| lab.id).collect::<Vec<_>>(), vec![4]);
+    assert!(repository.get_lab_lessons(100).await.unwrap().is_empty());
+    let date = labs[0].starts_at.date();
+    for (user_id, name) in [(300, "Третій"), (200, "Другий"), (100, "Перший")].iter()
+    {
+        assert!(repository
+            .join_queue(4, date, *user_id, name.to_string())
+            .await
+            .unwrap());
+    }
+    assert!(!repository
+        .join_queue(4, date, 300, "Третій".to_string())
+        .await
+        .unwrap());
+    assert!(repository.leave_queue(4, date, 200).await.unwrap());
+    assert!(!repository.leave_queue(4, date, 200).await.unwrap());
+    assert_eq!(repository.call_next(4, date).await.unwrap(), Some(300));
+    let queue = repository.get_queue(4, date).await.unwrap();
+    assert_eq!(
+        queue
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.called))
+            .collect::<Vec<_>>(),
+        vec![("Третій", true), ("Перший", false)]
+    );
+    assert_eq!(repository.call_next(4, date).await.unwrap(), Some(100));
+    assert_eq!(repository.call_next(4, date).await.unwrap(), None);
+    assert_eq!(
+        repository.finish_called(4, date).await.unwrap(),
+        vec![100, 300]
+    );
+    assert!(repository.get_queue(4, date).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn queues_start_over_at_every_lesson() {
+    let repository = repository();
+    let date = repository.get_lab_lessons(300).await.unwrap()[0]
+        .starts_at
+        .date();
+    let next = date + Duration::weeks(2);
+    for (user_id, name) in [(300, "Третій"), (100, "Перший")].iter() {
+        repository
+            .join_queue(4, date, *user_id, name.to_string())
+            .await
+            .unwrap();
+    }
+    repository.call_next(4, date).await.unwrap();
+    assert!(repository.get_queue(4, next).await.unwrap().is_empty());
+    assert!(repository
+        .join_queue(4, next, 200, "Другий".to_string())
+        .await
+        .unwrap());
+    assert_eq!(repository.call_next(4, next).await.unwrap(), Some(200));
+    assert!(repository.get_queue(4, date).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn homework_is_given_for_lesson_dates() {
+    let repository = repository();
+    let names = |subjects: Vec<schedule_core::database::subject::Subject>| {
+        subjects
+            .into_iter()
+            .map(|subject| subject.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(repository.get_group_subjects(1).await.unwrap()),
+        vec!["Математичний аналіз", "Теорія ігор"]
+    );
+    assert_eq!(
+        names(repository.get_group_subjects(2).await.unwrap()),
+        vec!["Математичний аналіз", "Фізика"]
+    );
+
+    let today = Local::now().date_naive();
+    let dates = repository.get_lesson_dates(2, 3).await.unwrap();
+    assert_eq!(dates.len(), 2);
+    for date in dates.iter() {
+        assert_eq!(date.weekday(), chrono::Weekday::Wed);
+        assert!(*date >= today && *date < today + Duration::days(14));
+    }
+    assert!(repository.get_lesson_dates(2, 2).await.unwrap().is_empty());
+
+    let files = vec![HomeworkFile {
+        file_id: "file".to_string(),
+        kind: Attachment::Document,
+    }];
+    repository
+        .add_homework(
+            50,
+            3,
+            2,
+            dates[0],
+            "Лабораторна 3".to_string(),
+            files.clone(),
+        )
+        .await
+        .unwrap();
+    let homework = repository.get_homework(300, dates[0]).await.unwrap();
+    assert_eq!(homework.len(), 1);
+    assert_eq!(homework[0].subject_name, "Фізика");
+    assert_eq!(homework[0].text, "Лабораторна 3");
+    assert_eq!(homework[0].files, files);
+    assert!(repository
+        .get_homework(300, dates[1])
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(repository
+        .get_homework(100, dates[0])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Compares the memory repository with PostgreSQL on the same timetable. The database named by
+/// `SCHEDULE_TEST_DATABASE_URL` loses its timetable and students, so the test is skipped when it
+/// is unset.
+#[tokio::test]
+async fn memory_matches_database() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    schedule_core::database::initialize(&url, 1).await.unwrap();
+    let database = Database::global();
+    let export = TimetableExport::from_file(&fixture()).unwrap();
+    database.restore_timetable(&export, false).await.unwrap();
+    database
+        .pool()
+        .execute(
+            r#"
+        DELETE FROM calendar_tokens;
+        DELETE FROM students_subjects;
+        DELETE FROM students;
+        INSERT INTO students(id, telegram_id, "group", last_name)
+        VALUES (1, 100, 1, ''), (2, 200, 1, ''), (3, 300, 2, '');
+        INSERT INTO students_subjects(student, subject) VALUES (1, 2);
+        "#,
+        )
+        .await
+        .unwrap();
+
+    let memory = repository();
+    for user_id in [100, 200, 300, 999].iter() {
+        for week in WeekType::all().iter() {
+            assert_eq!(
+                Repository::get_week_schedule(database, *user_id, *week)
+                    .await
+                    .unwrap(),
+                memory.get_week_schedule(*user_id, *week).await.unwrap(),
+                "user {}, {} week",
+                user_id,
+                week
+            );
+        }
+        assert_eq!(
+            Repository::is_student(database, *user_id).await.unwrap(),
+            memory.is_student(*user_id).await.unwrap()
+        );
+    }
+    let token = Repository::get_calendar_token(database, 100)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.len(), 32);
+    assert_eq!(
+        Repository::get_calendar_token(database, 100).await.unwrap(),
+        Some(token)
+    );
+    assert_eq!(
+        Repository::get_calendar_token(database, 999).await.unwrap(),
+        None
+    );
+    let dialogue = b"{}".to_vec();
+    Repository::update_dialogue(database, 1, dialogue.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        Repository::get_dialogue(database, 1).await.unwrap(),
+        Some(dialogue)
+    );
+    Repository::remove_dialogue(database, 1).await.unwrap();
+    assert_eq!(Repository::get_dialogue(database, 1).await.unwrap(), None);
+
+    database
+        .pool()
+        .execute("DELETE FROM user_settings")
+        .await
+        .unwrap();
+    assert_eq!(Repository::get_language(database, 1).await.unwrap(), None);
+    Repository::set_language(database, 1, Language::En)
+        .await
+        .unwrap();
+    Repository::set_language(database, 1, Language::Uk)
+        .await
+        .unwrap();
+    assert_eq!(
+        Repository::get_language(database, 1).await.unwrap(),
+        Some(Language::Uk)
+    );
+
+    assert_eq!(
+        Repository::get_settings(database, 2).await.unwrap(),
+        UserSettings::default()
+    );
+    Repository::set_settings(database, 1, settings())
+        .await
+        .unwrap();
+    assert_eq!(
+        Repository::get_settings(database, 1).await.unwrap(),
+        settings()
+    );
+    for user_id in [100, 200].iter() {
+        let settings = match user_id {
+            100 => settings(),
+            _ => UserSettings::default(),
+        };
+        Repository::set_settings(database, *user_id, settings.clone())
+            .await
+            .unwrap();
+        memory.set_settings(*user_id, settings).await.unwrap();
+    }
+    assert_eq!(
+        Repository::get_subscribers(database).await.unwrap(),
+        memory.get_subscribers().await.unwrap()
+    );
+    assert_eq!(
+        Repository::get_subscribers(database).await.unwrap(),
+        vec![(100, settings())]
+    );
+
+    database
+        .pool()
+        .execute("DELETE FROM user_roles")
+        .await
+        .unwrap();
+    for (user_id, role) in [(100, Role::GroupLeader), (999, Role::Admin)].iter() {
+        Repository::grant_role(database, *user_id, *role)
+            .await
+            .unwrap();
+        memory.grant_role(*user_id, *role).await.unwrap();
+    }
+    for user_id in [100, 200, 999, 1000].iter() {
+        assert_eq!(
+            Repository::get_roles(database, *user_id).await.unwrap(),
+            memory.get_roles(*user_id).await.unwrap(),
+            "user {}",
+            user_id
+        );
+    }
+    assert!(Repository::revoke_role(database, 999, Role::Admin)
+        .await
+        .unwrap());
+    assert!(!Repository::revoke_role(database, 999, Role::Admin)
+        .await
+        .unwrap());
+
+    for audience in [Audience::Group(1), Audience::Group(2), Audience::Subject(2)].iter() {
+        assert_eq!(
+            Repository::get_recipients(database, *audience)
+                .await
+                .unwrap(),
+            memory.get_recipients(*audience).await.unwrap(),
+            "{:?}",
+            audience
+        );
+    }
+    for user_id in [100, 300, 999].iter() {
+        assert_eq!(
+            Repository::get_student_group(database, *user_id)
+                .await
+                .unwrap(),
+            memory.get_student_group(*user_id).await.unwrap()
+        );
+    }
+    assert_eq!(
+        Repository::get_groups(database).await.unwrap(),
+        memory.get_groups().await.unwrap()
+    );
+    assert_eq!(
+        Repository::get_elective_subjects(database).await.unwrap(),
+        memory.get_elective_subjects().await.unwrap()
+    );
+
+    assert_eq!(
+        Repository::get_linked_teacher(database, 10).await.unwrap(),
+        memory.get_linked_teacher(10).await.unwrap()
+    );
+    assert!(memory.get_linked_teacher(10).await.unwrap().is_some());
+    database
+        .pool()
+        .execute("UPDATE teachers SET telegram_id = NULL; DELETE FROM announcements")
+        .await
+        .unwrap();
+    for (user_id, teacher_id) in [(50, 1), (50, 2), (60, 9)].iter() {
+        assert_eq!(
+            Repository::link_teacher(database, *user_id, *teacher_id)
+                .await
+                .unwrap(),
+            memory.link_teacher(*user_id, *teacher_id).await.unwrap()
+        );
+    }
+    for user_id in [50, 60].iter() {
+        assert_eq!(
+            Repository::get_linked_teacher(database, *user_id)
+                .await
+                .unwrap(),
+            memory.get_linked_teacher(*user_id).await.unwrap()
+        );
+    }
+    for teacher in [1, 2].iter() {
+        let taught = Repository::get_taught_groups(database, *teacher)
+            .await
+            .unwrap();
+        assert_eq!(taught, memory.get_taught_groups(*teacher).await.unwrap());
+        for group in taught.iter() {
+            for group_id in [None, Some(group.group)].iter() {
+                let audience = Audience::Taught {
+                    teacher: *teacher,
+                    subject: group.subject,
+                    group: *group_id,
+                };
+                assert_eq!(
+                    Repository::get_recipients(database, audience)
+                        .await
+                        .unwrap(),
+                    memory.get_recipients(audience).await.unwrap(),
+                    "{:?}",
+                    audience
+                );
+            }
+        }
+    }
+    Repository::save_announcement(
+        database,
+        50,
+        "Іванов".to_string(),
+        "Текст".to_string(),
+        vec![100, 200],
+    )
+    .await
+    .unwrap();
+    let announcements = Repository::get_announcements(database, 200, 10)
+        .await
+        .unwrap();
+    assert_eq!(announcements.len(), 1);
+    assert_eq!(announcements[0].text, "Текст");
+    assert!(Repository::get_announcements(database, 300, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    for user_id in [100, 300, 999].iter() {
+        assert_eq!(
+            Repository::get_lab_lessons(database, *user_id)
+                .await
+                .unwrap(),
+            memory.get_lab_lessons(*user_id).await.unwrap()
+        );
+    }
+    for teacher in [1, 2].iter() {
+        assert_eq!(
+            Repository::get_taught_lab_lessons(database, *teacher)
+                .await
+                .unwrap(),
+            memory.get_taught_lab_lessons(*teacher).await.unwrap()
+        );
+    }
+    database
+        .pool()
+        .execute("DELETE FROM lab_queue")
+        .await
+        .unwrap();
+    let date = Local::now().naive_local().date();
+    let next = date + Duration::weeks(2);
+    for user_id in [300, 200, 100, 300].iter() {
+        assert_eq!(
+            Repository::join_queue(database, 4, date, *user_id, user_id.to_string())
+                .await
+                .unwrap(),
+            memory
+                .join_queue(4, date, *user_id, user_id.to_string())
+                .await
+                .unwrap()
+        );
+    }
+    assert_eq!(
+        Repository::leave_queue(database, 4, date, 200)
+            .await
+            .unwrap(),
+        memory.leave_queue(4, date, 200).await.unwrap()
+    );
+    for _ in 0..3 {
+        assert_eq!(
+            Repository::call_next(database, 4, date).await.unwrap(),
+            memory.call_next(4, date).await.unwrap()
+        );
+        assert_eq!(
+            Repository::get_queue(database, 4, date).await.unwrap(),
+            memory.get_queue(4, date).await.unwrap()
+        );
+    }
+    assert_eq!(
+        Repository::finish_called(database, 4, date).await.unwrap(),
+        memory.finish_called(4, date).await.unwrap()
+    );
+    // Students left waiting are not queued at the next lesson, and the old queue goes.
+    for (queue_date, user_id) in [(date, 100), (next, 200)].iter() {
+        Repository::join_queue(database, 4, *queue_date, *user_id, user_id.to_string())
+            .await
+            .unwrap();
+        memory
+            .join_queue(4, *queue_date, *user_id, user_id.to_string())
+            .await
+            .unwrap();
+    }
+    for queue_date in [date, next].iter() {
+        assert_eq!(
+            Repository::get_queue(database, 4, *queue_date)
+                .await
+                .unwrap(),
+            memory.get_queue(4, *queue_date).await.unwrap()
+        );
+    }
+    assert!(Repository::get_queue(database, 4, date)
+        .await
+        .unwrap()
+        .is_empty());
+
+    database
+        .pool()
+        .execute("DELETE FROM homework")
+        .await
+        .unwrap();
+    for group in [1, 2].iter() {
+        assert_eq!(
+            Repository::get_group_subjects(database, *group)
+                .await
+                .unwrap(),
+            memory.get_group_subjects(*group).await.unwrap()
+        );
+    }
+    // Only lessons of every week, as the memory repository has its own distribution start.
+    for subject in [1, 3].iter() {
+        assert_eq!(
+            Repository::get_lesson_dates(database, 2, *subject)
+                .await
+                .unwrap(),
+            memory.get_lesson_dates(2, *subject).await.unwrap()
+        );
+    }
+    let date = memory.get_lesson_dates(2, 3).await.unwrap()[0];
+    let files = vec![
+        HomeworkFile {
+            file_id: "document".to_string(),
+            kind: Attachment::Document,
+        },
+        HomeworkFile {
+            file_id: "photo".to_string(),
+            kind: Attachment::Photo,
+        },
+    ];
+    for (subject, text, files) in [(3, "Перша", files), (1, "Друга", vec![])].iter() {
+        Repository::add_homework(
+            database,
+            50,
+            *subject,
+            2,
+            date,
+            text.to_string(),
+            files.clone(),
+        )
+        .await
+        .unwrap();
+        memory
+            .add_homework(50, *subject, 2, date, text.to_string(), files.clone())
+            .await
+            .unwrap();
+    }
+    for user_id in [100, 300].iter() {
+        assert_eq!(
+            Repository::get_homework(database, *user_id, date)
+                .await
+                .unwrap(),
+            memory.get_homework(*user_id, date).await.unwrap()
+        );
+    }
+
+    database
+        .pool()
+        .execute(
+            r#"
+        DELETE FROM deadlines;
+        INSERT INTO deadlines(subject, "group", description, deadline) VALUES
+            (1, 1, NULL, now() + interval '1 day'),
+            (1, NULL, 'Без групи', now() + interval '1 day'),
+            (1, 2, 'Без дати', NULL);
+        "#,
+        )
+        .await
+        .unwrap();
+    let deadlines = database.get_deadlines(None).await.unwrap();
+    assert_eq!(deadlines.len(), 1);
+    assert_eq!(deadlines[0].group, 1);
+    assert!(deadlines[0].description.is_empty());
+    assert!(database.get_deadlines(Some(2)).await.unwrap().is_empty());
+}
@@ -0,0 +1,130 @@
+use chrono::NaiveTime;
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::LessonType;
+use schedule_core::database::week::Distribution;
+use schedule_core::database::Database;
+use schedule_core::import::{ImportLesson, Timetable};
+use std::path::Path;
+
+fn fixture(name: &str) -> Timetable {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/rozklad")
+        .join(name);
+    Timetable::from_file(&path).unwrap()
+}
+
+fn find<'a>(timetable: &'a Timetable, day: Day, time: &str, subject: &str) -> &'a ImportLesson {
+    let time = NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+    timetable
+        .lessons
+        .iter()
+        .find(|lesson| lesson.day == day && lesson.time == time && lesson.subject == subject)
+        .unwrap_or_else(|| panic!("{} at {} {} not found", subject, day, time))
+}
+
+#[test]
+fn parses_group_page() {
+    let timetable = fixture("fi-91.html");
+    assert_eq!(timetable.lessons.len(), 6);
+    assert!(timetable
+        .lessons
+        .iter()
+        .all(|lesson| lesson.groups.iter().eq(["ФІ-91"].iter())));
+
+    let lecture = find(&timetable, Day::Monday, "08:30", "Математичний аналіз");
+    assert_eq!(lecture.lesson_type, LessonType::Lecture);
+    assert_eq!(lecture.room, "305-18");
+    let teacher = lecture.teacher.as_ref().unwrap();
+    assert_eq!(teacher.to_string(), "Іванов Іван Іванович");
+}
+
+#[test]
+fn strips_teacher_rank() {
+    let timetable = fixture("fi-91.html");
+    let lab = find(&timetable, Day::Wednesday, "12:20", "Криптографія");
+    assert_eq!(lab.lesson_type, LessonType::LaboratoryWork);
+    assert_eq!(
+        lab.teacher.as_ref().unwrap().to_string(),
+        "Сидоренко Сидір Сидорович"
+    );
+}
+
+#[test]
+fn merges_weeks_into_distribution() {
+    let timetable = fixture("fi-91.html");
+    let both = find(&timetable, Day::Tuesday, "10:25", "Математичний аналіз");
+    assert_eq!(both.distribution, Distribution::All);
+    assert_eq!(both.lesson_type, LessonType::Practice);
+    let second = find(&timetable, Day::Wednesday, "12:20", "Криптографія");
+    assert_eq!(second.distribution, Distribution::Second);
+    let first = find(&timetable, Day::Thursday, "12:20", "Теорія ігор");
+    assert_eq!(first.distribution, Distribution::First);
+}
+
+#[test]
+fn splits_cells_with_several_subjects() {
+    let timetable = fixture("fi-91.html");
+    let games = find(&timetable, Day::Thursday, "12:20", "Теорія ігор");
+    let quantum = find(&timetable, Day::Thursday, "12:20", "Квантові обчислення");
+    assert_eq!(games.room, "101-18");
+    assert_eq!(quantum.room, "102-18");
+    assert_eq!(
+        quantum.teacher.as_ref().unwrap().to_string(),
+        "Бондар Богдан Богданович"
+    );
+}
+
+#[test]
+fn lessons_without_teacher_or_place() {
+    let timetable = fixture("fi-91.html");
+    let sport = find(&timetable, Day::Saturday, "14:15", "Фізичне виховання");
+    assert!(sport.teacher.is_none());
+    assert!(sport.room.is_empty());
+    assert_eq!(sport.lesson_type, LessonType::Lecture);
+}
+
+#[test]
+fn combines_pages_of_several_groups() {
+    let mut timetable = fixture("fi-91.html");
+    timetable.extend(fixture("fi-92.html"));
+    assert_eq!(timetable.lessons.len(), 7);
+    let shared = find(&timetable, Day::Monday, "08:30", "Математичний аналіз");
+    assert!(shared.groups.iter().eq(["ФІ-91", "ФІ-92"].iter()));
+}
+
+#[test]
+fn rejects_other_pages() {
+    assert!(Timetable::from_html("<html><body><h1>Not found</h1></body></html>").is_err());
+}
+
+/// Reconciles the updated page against a scratch database. The database named by
+/// `SCHEDULE_TEST_DATABASE_URL` is modified, so the test is skipped when it is unset.
+#[tokio::test]
+async fn reconciles_with_database() {
+    let url = match std::env::var("SCHEDULE_TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+    schedule_core::database::initialize(&url, 1).await.unwrap();
+    let database = Database::global();
+
+    let mut timetable = fixture("fi-91.html");
+    timetable.extend(fixture("fi-92.html"));
+    database.import_timetable(&timetable, false).await.unwrap();
+
+    let updated = fixture("fi-91-updated.html");
+    let report = database.import_timetable(&updated, true).await.unwrap();
+    assert_eq!(report.added.len(), 1, "{}", report);
+    assert!(report.added[0].contains("Фізика"));
+    assert_eq!(report.changed.len(), 1, "{}", report);
+    assert!(report.changed[0].contains("412-18") && report.changed[0].contains("414-18"));
+    assert_eq!(report.removed.len(), 1, "{}", report);
+    assert!(report.removed[0].contains("Криптографія"));
+    assert_eq!(report.unchanged, 4);
+
+    let again = database.import_timetable(&updated, false).await.unwrap();
+    assert_eq!(again.added.len(), 1);
+    let applied = database.import_timetable(&updated, true).await.unwrap();
+    assert!(!applied.has_changes());
+    assert_eq!(applied.unchanged, 6);
+}
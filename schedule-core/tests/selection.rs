@@ -0,0 +1,260 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::{LessonDay, LessonEntry, LessonType};
+use schedule_core::database::subject::Subject;
+use schedule_core::database::teacher::Teacher;
+use schedule_core::database::week::{Distribution, WeekType};
+use schedule_core::selection::{self, TimetableRows};
+
+const GROUP: i32 = 1;
+const OTHER_GROUP: i32 = 2;
+const ELECTIVE: i32 = 2;
+
+fn time(time: &str) -> NaiveTime {
+    NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+}
+
+fn subject(id: i32, name: &str, choice: bool) -> Subject {
+    Subject {
+        id,
+        name: name.to_string(),
+        choice,
+        info: None,
+    }
+}
+
+fn lesson(
+    id: i32,
+    subject: i32,
+    day: Day,
+    at: &str,
+    distribution: Distribution,
+    groups: &[i32],
+) -> LessonEntry {
+    LessonEntry {
+        id,
+        subject,
+        lesson_type: LessonType::Lecture,
+        teacher: Some(1),
+        day,
+        time: time(at),
+        distribution,
+        info: String::new(),
+        room: "305".to_string(),
+        groups: groups.to_vec(),
+    }
+}
+
+fn rows() -> TimetableRows {
+    TimetableRows {
+        subjects: vec![
+            subject(1, "Матан", false),
+            subject(ELECTIVE, "Теорія ігор", true),
+            subject(3, "Фізика", false),
+        ],
+        teachers: vec![Teacher {
+            id: 1,
+            last_name: "Іванов".to_string(),
+            first_name: "Іван".to_string(),
+            patronymic_name: String::new(),
+            telegram: None,
+            email: None,
+            phone_number: None,
+        }],
+        lessons: vec![
+            lesson(
+                1,
+                1,
+                Day::Monday,
+                "08:30",
+                Distribution::All,
+                &[GROUP, OTHER_GROUP],
+            ),
+            lesson(
+                2,
+                ELECTIVE,
+                Day::Monday,
+                "10:25",
+                Distribution::First,
+                &[GROUP],
+            ),
+            lesson(3, 3, Day::Tuesday, "12:20", Distribution::Second, &[GROUP]),
+            lesson(
+                4,
+                3,
+                Day::Wednesday,
+                "08:30",
+                Distribution::All,
+                &[OTHER_GROUP],
+            ),
+        ],
+    }
+}
+
+fn names(lessons: &[LessonDay]) -> Vec<(Day, &str)> {
+    lessons
+        .iter()
+        .map(|lesson| (lesson.day, lesson.subject_name.as_str()))
+        .collect()
+}
+
+#[test]
+fn electives_need_enrollment() {
+    let rows = rows();
+    let enrolled = rows.student_week(GROUP, &[ELECTIVE], WeekType::First);
+    assert_eq!(
+        names(&enrolled),
+        vec![(Day::Monday, "Матан"), (Day::Monday, "Теорія ігор")]
+    );
+    let other = rows.student_week(GROUP, &[], WeekType::First);
+    assert_eq!(names(&other), vec![(Day::Monday, "Матан")]);
+    let group = rows.group_week(GROUP, WeekType::First);
+    assert_eq!(names(&group), names(&enrolled));
+}
+
+#[test]
+fn only_lessons_of_the_group() {
+    let rows = rows();
+    assert_eq!(
+        names(&rows.student_week(OTHER_GROUP, &[ELECTIVE], WeekType::First)),
+        vec![(Day::Monday, "Матан"), (Day::Wednesday, "Фізика")]
+    );
+    assert!(rows.student_week(3, &[], WeekType::First).is_empty());
+}
+
+#[test]
+fn distribution_selects_the_week() {
+    let rows = rows();
+    assert_eq!(
+        names(&rows.student_week(GROUP, &[ELECTIVE], WeekType::Second)),
+        vec![(Day::Monday, "Матан"), (Day::Tuesday, "Фізика")]
+    );
+}
+
+#[test]
+fn all_distribution_is_in_both_weeks() {
+    let rows = rows();
+    for week in WeekType::all().iter() {
+        let lessons = rows.student_week(OTHER_GROUP, &[], *week);
+        assert_eq!(lessons.len(), 2);
+    }
+}
+
+#[test]
+fn parity_of_weeks() {
+    let start = NaiveDate::from_ymd_opt(2021, 2, 1).unwrap();
+    let week = |date: NaiveDate| WeekType::at(start, date);
+    assert!(matches!(week(start), WeekType::First));
+    assert!(matches!(
+        week(NaiveDate::from_ymd_opt(2021, 2, 7).unwrap()),
+        WeekType::First
+    ));
+    assert!(matches!(
+        week(NaiveDate::from_ymd_opt(2021, 2, 8).unwrap()),
+        WeekType::Second
+    ));
+    assert!(matches!(
+        week(NaiveDate::from_ymd_opt(2021, 2, 15).unwrap()),
+        WeekType::First
+    ));
+    assert!(matches!(
+        week(NaiveDate::from_ymd_opt(2021, 5, 17).unwrap()),
+        WeekType::Second
+    ));
+}
+
+#[test]
+fn duplicate_rows_are_shown_once() {
+    let mut rows = rows();
+    rows.lessons.push(lesson(
+        5,
+        1,
+        Day::Monday,
+        "08:30",
+        Distribution::First,
+        &[GROUP],
+    ));
+    let lessons = rows.student_week(GROUP, &[], WeekType::First);
+    assert_eq!(names(&lessons), vec![(Day::Monday, "Матан")]);
+    assert_eq!(lessons[0].teacher_name, "Іванов Іван");
+}
+
+#[test]
+fn teacher_and_room() {
+    let mut rows = rows();
+    rows.lessons[3].teacher = None;
+    rows.lessons[3].room = "7-18".to_string();
+    assert_eq!(rows.teacher_week(1, WeekType::First).len(), 2);
+    assert_eq!(
+        names(&rows.room_week("7-18", WeekType::Second)),
+        vec![(Day::Wednesday, "Фізика")]
+    );
+}
+
+#[test]
+fn day_and_current_lessons() {
+    let rows = rows();
+    let monday = selection::day(
+        rows.student_week(GROUP, &[ELECTIVE], WeekType::First),
+        Day::Monday,
+    );
+    assert_eq!(monday.0.len(), 2);
+    assert!(
+        selection::day(rows.student_week(GROUP, &[], WeekType::First), Day::Friday)
+            .0
+            .is_empty()
+    );
+
+    let subjects = |now: &str| -> Vec<String> {
+        selection::current(monday.clone(), time(now))
+            .0
+            .into_iter()
+            .map(|lesson| lesson.subject_name)
+            .collect()
+    };
+    assert_eq!(subjects("07:00"), vec!["Матан"]);
+    assert_eq!(subjects("09:00"), vec!["Матан", "Теорія ігор"]);
+    assert_eq!(subjects("11:00"), vec!["Теорія ігор"]);
+    assert!(subjects("12:30").is_empty());
+}
+
+#[test]
+fn labs_come_soonest_first() {
+    let mut rows = rows();
+    for lesson in rows
+        .lessons
+        .iter_mut()
+        .filter(|lesson| [2, 3].contains(&lesson.id))
+    {
+        lesson.lesson_type = LessonType::LaboratoryWork;
+    }
+    let start = NaiveDate::from_ymd_opt(2021, 2, 1).unwrap();
+    let at = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+    let labs = |electives: &[i32], now: &str| -> Vec<(i32, NaiveDateTime)> {
+        rows.student_labs(GROUP, electives, start, at(now))
+            .into_iter()
+            .map(|lab| (lab.id, lab.starts_at))
+            .collect()
+    };
+    assert_eq!(
+        labs(&[ELECTIVE], "2021-02-02 13:00"),
+        vec![(3, at("2021-02-09 12:20")), (2, at("2021-02-15 10:25"))]
+    );
+    assert_eq!(
+        labs(&[], "2021-02-02 13:00"),
+        vec![(3, at("2021-02-09 12:20"))]
+    );
+    // A lesson in progress is still the nearest one.
+    assert_eq!(
+        labs(&[], "2021-02-09 13:30"),
+        vec![(3, at("2021-02-09 12:20"))]
+    );
+    assert_eq!(
+        labs(&[], "2021-02-09 14:00"),
+        vec![(3, at("2021-02-23 12:20"))]
+    );
+    assert_eq!(rows.teacher_labs(1, start, at("2021-02-02 13:00")).len(), 2);
+    assert!(rows
+        .teacher_labs(2, start, at("2021-02-02 13:00"))
+        .is_empty());
+}
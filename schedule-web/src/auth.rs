@@ -0,0 +1,88 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum_extra::extract::CookieJar;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use schedule_core::database::session::Session;
+use schedule_core::database::Database;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+pub const SESSION_COOKIE: &str = "session";
+
+/// Login data older than this is rejected to limit replay of leaked links.
+const MAX_AUTH_AGE: i64 = 24 * 60 * 60;
+
+/// Data passed by the Telegram Login Widget to the authorization url.
+pub struct TelegramLogin {
+    pub id: i64,
+    pub name: String,
+    pub username: Option<String>,
+}
+
+impl TelegramLogin {
+    /// Checks the widget signature as described in https://core.telegram.org/widgets/login.
+    /// Without a bot token anyone could sign, so nobody logs in.
+    pub fn verify(mut fields: BTreeMap<String, String>, bot_token: &str) -> Option<Self> {
+        if bot_token.is_empty() {
+            return None;
+        }
+        let hash = hex::decode(fields.remove("hash")?).ok()?;
+        let data_check_string = fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let secret_key = Sha256::digest(bot_token.as_bytes());
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret_key).ok()?;
+        mac.update(data_check_string.as_bytes());
+        mac.verify_slice(&hash).ok()?;
+
+        let auth_date: i64 = fields.get("auth_date")?.parse().ok()?;
+        if Utc::now().timestamp() - auth_date > MAX_AUTH_AGE {
+            return None;
+        }
+        let name = match fields.get("last_name") {
+            Some(last_name) => format!("{} {}", fields.get("first_name")?, last_name),
+            None => fields.get("first_name")?.clone(),
+        };
+        Some(TelegramLogin {
+            id: fields.get("id")?.parse().ok()?,
+            name,
+            username: fields.remove("username"),
+        })
+    }
+}
+
+/// Session named by the cookie, `None` when there is no cookie or the session has expired.
+pub async fn find_session(jar: &CookieJar) -> anyhow::Result<Option<Session>> {
+    match jar.get(SESSION_COOKIE) {
+        Some(cookie) => Database::global().get_session(cookie.value()).await,
+        None => Ok(None),
+    }
+}
+
+/// Session of the logged in user, redirecting to the login page when there is none.
+pub struct CurrentUser(pub Session);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_request_parts(parts, state)
+            .await
+            .map_err(|error| error.into_response())?;
+        match find_session(&jar).await {
+            Ok(Some(session)) => Ok(CurrentUser(session)),
+            Ok(None) => Err(Redirect::to("/login").into_response()),
+            Err(error) => {
+                log::error!("Failed to load session: {}", error);
+                Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+            }
+        }
+    }
+}
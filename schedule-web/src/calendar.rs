@@ -0,0 +1,158 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use schedule_core::bell::LESSON_DURATION;
+use schedule_core::database::day::Day;
+use schedule_core::database::lesson::Lesson;
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::{self, Language, Localize};
+use std::fmt;
+
+const TIMEZONE: &str = "Europe/Kiev";
+const MAX_LINE_LENGTH: usize = 75;
+
+/// `VTIMEZONE` of [`TIMEZONE`], which RFC 5545 requires for every `TZID` used: EU daylight
+/// saving time, from the last Sunday of March to the last Sunday of October.
+const TIMEZONE_DEFINITION: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:Europe/Kiev",
+    "BEGIN:STANDARD",
+    "DTSTART:19701025T040000",
+    "TZOFFSETFROM:+0300",
+    "TZOFFSETTO:+0200",
+    "TZNAME:EET",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "END:STANDARD",
+    "BEGIN:DAYLIGHT",
+    "DTSTART:19700329T030000",
+    "TZOFFSETFROM:+0200",
+    "TZOFFSETTO:+0300",
+    "TZNAME:EEST",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU",
+    "END:DAYLIGHT",
+    "END:VTIMEZONE",
+];
+
+/// Recurring event of a single lesson, repeated every distribution cycle.
+pub struct Event {
+    uid: String,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    summary: String,
+    description: String,
+    location: String,
+}
+
+impl Event {
+    pub fn new(
+        distribution_start: NaiveDate,
+        week: WeekType,
+        day: &Day,
+        index: usize,
+        lesson: &Lesson,
+        language: Language,
+    ) -> Self {
+        let monday = distribution_start
+            - Duration::days(distribution_start.weekday().num_days_from_monday() as i64);
+        let date = monday
+            + Duration::weeks(week.offset())
+            + Duration::days(day.weekday().num_days_from_monday() as i64);
+        let start = date.and_time(lesson.time);
+        let mut description = locale::global().format(
+            language,
+            "lesson-teacher",
+            vec![("teacher", lesson.teacher_name.as_str().into())],
+        );
+        if !lesson.info.is_empty() {
+            description.push('\n');
+            description.push_str(&lesson.info);
+        }
+
+        Event {
+            uid: format!(
+                "{}-{}-{}@schedule-ipt",
+                start.format("%Y%m%dT%H%M"),
+                week.offset(),
+                index
+            ),
+            start,
+            end: start + Duration::minutes(LESSON_DURATION),
+            summary: format!(
+                "{} ({})",
+                lesson.subject_name,
+                lesson.lesson_type.localize(language)
+            ),
+            description,
+            location: lesson.room.clone(),
+        }
+    }
+}
+
+pub struct Calendar {
+    pub name: String,
+    pub events: Vec<Event>,
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+        write_line(f, "BEGIN:VCALENDAR")?;
+        write_line(f, "VERSION:2.0")?;
+        write_line(f, "PRODID:-//IPT//Schedule//UK")?;
+        write_line(f, "CALSCALE:GREGORIAN")?;
+        write_line(f, "METHOD:PUBLISH")?;
+        write_line(f, &format!("X-WR-CALNAME:{}", escape(&self.name)))?;
+        write_line(f, &format!("X-WR-TIMEZONE:{}", TIMEZONE))?;
+        for line in TIMEZONE_DEFINITION {
+            write_line(f, line)?;
+        }
+        for event in &self.events {
+            write_line(f, "BEGIN:VEVENT")?;
+            write_line(f, &format!("UID:{}", event.uid))?;
+            write_line(f, &format!("DTSTAMP:{}", stamp))?;
+            write_line(
+                f,
+                &format!(
+                    "DTSTART;TZID={}:{}",
+                    TIMEZONE,
+                    event.start.format("%Y%m%dT%H%M%S")
+                ),
+            )?;
+            write_line(
+                f,
+                &format!(
+                    "DTEND;TZID={}:{}",
+                    TIMEZONE,
+                    event.end.format("%Y%m%dT%H%M%S")
+                ),
+            )?;
+            write_line(f, "RRULE:FREQ=WEEKLY;INTERVAL=2")?;
+            write_line(f, &format!("SUMMARY:{}", escape(&event.summary)))?;
+            write_line(f, &format!("DESCRIPTION:{}", escape(&event.description)))?;
+            if !event.location.is_empty() {
+                write_line(f, &format!("LOCATION:{}", escape(&event.location)))?;
+            }
+            write_line(f, "END:VEVENT")?;
+        }
+        write_line(f, "END:VCALENDAR")
+    }
+}
+
+/// Writes a content line folded at 75 octets as required by RFC 5545.
+fn write_line(f: &mut fmt::Formatter, line: &str) -> fmt::Result {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            write!(f, "\r\n ")?;
+            length = 1;
+        }
+        write!(f, "{}", c)?;
+        length += c.len_utf8();
+    }
+    write!(f, "\r\n")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+use schedule_core::config::Configurable;
+use serde::Deserialize;
+use serde::Serialize;
+
+static INSTANCE: OnceCell<Config> = OnceCell::new();
+
+#[derive(Serialize, Deserialize)]
+pub struct Config {
+    pub address: String,
+    pub database_url: String,
+    pub max_connections: u32,
+    pub admin_login: String,
+    pub admin_password: String,
+    pub bot_token: String,
+    pub bot_username: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            address: "127.0.0.1:8080".to_string(),
+            database_url: "".to_string(),
+            max_connections: 5,
+            admin_login: "admin".to_string(),
+            admin_password: "".to_string(),
+            bot_token: "".to_string(),
+            bot_username: "".to_string(),
+        }
+    }
+}
+
+impl Configurable for Config {}
+
+impl Config {
+    pub fn new() -> Result<Self> {
+        Self::get_config("config.json")
+    }
+
+    pub fn global() -> &'static Config {
+        INSTANCE.get().expect("Config is not initialized")
+    }
+}
+
+pub fn initialize() -> Result<()> {
+    INSTANCE
+        .set(Config::new()?)
+        .map_err(|_| anyhow::anyhow!("Failed to initialize config!"))
+}
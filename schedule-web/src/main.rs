@@ -1,3 +1,136 @@
-fn main() {
-    println!("Hello word!");
+use anyhow::Result;
+use config::Config;
+use schedule_core::database::Database;
+use schedule_core::exchange::{Format, TimetableExport};
+use schedule_core::import::Timetable;
+use std::path::Path;
+use tokio::net::TcpListener;
+
+mod auth;
+mod calendar;
+mod config;
+mod routes;
+
+#[tokio::main]
+async fn main() {
+    pretty_env_logger::init();
+    log::info!("Starting schedule_web...");
+
+    config::initialize().expect("Failed to initialize config!");
+    let config = Config::global();
+    schedule_core::database::initialize(&config.database_url, config.max_connections)
+        .await
+        .unwrap();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match args.first().map(String::as_str) {
+        Some("import") => Some(import(&args[1..]).await),
+        Some("export") => Some(export(&args[1..]).await),
+        _ => None,
+    };
+    if let Some(result) = command {
+        if let Err(error) = result {
+            eprintln!("{:#}", error);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if config.bot_token.is_empty() {
+        log::warn!("bot_token is not set, logging in with Telegram is disabled");
+    }
+    let listener = TcpListener::bind(&config.address)
+        .await
+        .expect("Failed to bind address!");
+    axum::serve(listener, routes::router())
+        .await
+        .expect("Something get wrong with web server");
+}
+
+/// `schedule-web import <file>... [--dry-run | --reconcile]` loads timetable spreadsheets, saved
+/// timetable pages or an export and exits. Several files are merged into one timetable, and
+/// `--reconcile` shows the changes against the database and asks before applying them.
+async fn import(args: &[String]) -> Result<()> {
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let reconcile = args.iter().any(|arg| arg == "--reconcile");
+    let paths: Vec<&Path> = args
+        .iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .map(Path::new)
+        .collect();
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Usage: schedule-web import <file.csv|file.xlsx|file.html|file.json|file.yaml>... [--dry-run|--reconcile]"
+        ));
+    }
+    let database = Database::global();
+    if let [path] = paths.as_slice() {
+        if Format::from_path(path).is_some() {
+            let export = TimetableExport::from_file(path)?;
+            let replaced = database.restore_timetable(&export, dry_run).await?;
+            println!(
+                "{}Групп: {}, предметов: {}, преподавателей: {}, пар: {}",
+                if dry_run {
+                    "Пробный запуск, изменения не сохранены.\n"
+                } else {
+                    ""
+                },
+                export.groups.len(),
+                export.subjects.len(),
+                export.teachers.len(),
+                export.lessons.len()
+            );
+            if !replaced.is_empty() {
+                println!(
+                    "Заменены преподаватели с тем же именем ({}):",
+                    replaced.len()
+                );
+                replaced
+                    .iter()
+                    .for_each(|teacher| println!("  ~ {}", teacher));
+            }
+            return Ok(());
+        }
+    }
+
+    let mut timetable = Timetable { lessons: vec![] };
+    for path in paths {
+        timetable.extend(Timetable::from_file(path)?);
+    }
+    let report = database
+        .import_timetable(&timetable, dry_run || reconcile)
+        .await?;
+    if !reconcile {
+        print!("{}", report);
+        return Ok(());
+    }
+    print!("{}", report);
+    if !report.has_changes() {
+        println!("Расписание совпадает с базой.");
+        return Ok(());
+    }
+    print!("Применить изменения? [y/N] ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if matches!(answer.trim(), "y" | "Y" | "д" | "Д") {
+        let report = database.import_timetable(&timetable, false).await?;
+        print!("{}", report);
+    } else {
+        println!("Изменения не применены.");
+    }
+    Ok(())
+}
+
+/// `schedule-web export <file.json|file.yaml>` saves the whole timetable and exits.
+async fn export(args: &[String]) -> Result<()> {
+    let path = args
+        .first()
+        .map(Path::new)
+        .ok_or_else(|| anyhow::anyhow!("Usage: schedule-web export <file.json|file.yaml>"))?;
+    let format = Format::from_path(path)
+        .ok_or_else(|| anyhow::anyhow!("Ожидается файл .json, .yaml или .yml"))?;
+    let export = Database::global().export_timetable().await?;
+    std::fs::write(path, export.serialize(format)?)?;
+    Ok(())
 }
@@ -0,0 +1,63 @@
+use askama::Template;
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use schedule_core::database::lesson::LessonsWeek;
+use schedule_core::database::week::WeekType;
+use schedule_core::locale::Language;
+use schedule_core::render::pdf::timetable_pdf;
+
+mod account;
+mod admin;
+mod api;
+mod ical;
+mod timetable;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(timetable::index))
+        .route("/groups/:id", get(timetable::group))
+        .route("/groups/:id/pdf", get(timetable::group_pdf))
+        .route("/teachers/:id", get(timetable::teacher))
+        .route("/rooms/:room", get(timetable::room))
+        .route("/login", get(account::login))
+        .route("/logout", get(account::logout))
+        .route("/auth/telegram", get(account::telegram))
+        .route("/my", get(account::my))
+        .route("/my/pdf", get(account::my_pdf))
+        .route("/ical/:file", get(ical::calendar))
+        .nest("/api/v1", api::router())
+        .nest("/admin", admin::router())
+}
+
+fn page<T: Template>(template: T) -> Response {
+    match template.render() {
+        Ok(page) => Html(page).into_response(),
+        Err(error) => {
+            log::error!("Failed to render page: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Printable timetable of the weeks as a PDF download.
+fn pdf(title: &str, weeks: &[(WeekType, LessonsWeek)], file_name: &str) -> Response {
+    match timetable_pdf(title, weeks, Language::default().into()) {
+        Ok(pdf) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file_name),
+                ),
+            ],
+            pdf,
+        )
+            .into_response(),
+        Err(error) => {
+            log::error!("Failed to render PDF: {:#}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
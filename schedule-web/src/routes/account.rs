@@ -0,0 +1,152 @@
+use anyhow::Result;
+use askama::Template;
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::CookieJar;
+use schedule_core::database::lesson::LessonsWeek;
+use schedule_core::database::week::WeekType;
+use schedule_core::database::Database;
+use std::collections::BTreeMap;
+
+use crate::auth::{CurrentUser, TelegramLogin, SESSION_COOKIE};
+use crate::config::Config;
+use crate::routes::{page, pdf};
+
+#[derive(Template)]
+#[template(path = "login.html")]
+struct LoginTemplate<'a> {
+    bot_username: &'a str,
+    error: Option<&'a str>,
+}
+
+pub async fn login() -> Response {
+    page(LoginTemplate {
+        bot_username: &Config::global().bot_username,
+        error: None,
+    })
+}
+
+/// Authorization url of the Telegram Login Widget.
+pub async fn telegram(jar: CookieJar, Query(fields): Query<BTreeMap<String, String>>) -> Response {
+    let config = Config::global();
+    let login = match TelegramLogin::verify(fields, &config.bot_token) {
+        Some(login) => login,
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                page(LoginTemplate {
+                    bot_username: &config.bot_username,
+                    error: Some("Не удалось проверить данные Telegram, попробуйте ещё раз"),
+                }),
+            )
+                .into_response()
+        }
+    };
+    match Database::global()
+        .create_session(login.id, &login.name, login.username.as_deref())
+        .await
+    {
+        Ok(token) => {
+            let cookie = Cookie::build((SESSION_COOKIE, token))
+                .path("/")
+                .http_only(true)
+                .same_site(SameSite::Lax)
+                .max_age(time::Duration::days(30));
+            (jar.add(cookie), Redirect::to("/my")).into_response()
+        }
+        Err(error) => {
+            log::error!("Failed to create session: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+pub async fn logout(jar: CookieJar) -> Response {
+    if let Some(cookie) = jar.get(SESSION_COOKIE) {
+        if let Err(error) = Database::global().delete_session(cookie.value()).await {
+            log::error!("Failed to delete session: {}", error);
+        }
+    }
+    (
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/")),
+        Redirect::to("/login"),
+    )
+        .into_response()
+}
+
+#[derive(Template)]
+#[template(path = "my.html")]
+struct MyTemplate {
+    name: String,
+    role: Option<String>,
+    current: WeekType,
+    weeks: Vec<(WeekType, LessonsWeek)>,
+}
+
+/// Schedule of the logged in student or teacher.
+pub async fn my(CurrentUser(session): CurrentUser) -> Response {
+    match my_schedule(session.telegram_id).await {
+        Ok((role, weeks, current)) => page(MyTemplate {
+            name: session.name,
+            role,
+            current,
+            weeks,
+        }),
+        Err(error) => {
+            log::error!("Failed to load schedule: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Schedule of the logged in student or teacher as a PDF.
+pub async fn my_pdf(CurrentUser(session): CurrentUser) -> Response {
+    match my_schedule(session.telegram_id).await {
+        Ok((Some(_), weeks, _)) => pdf(&session.name, &weeks, "timetable.pdf"),
+        Ok((None, _, _)) => StatusCode::NOT_FOUND.into_response(),
+        Err(error) => {
+            log::error!("Failed to load schedule: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+type Weeks = Vec<(WeekType, LessonsWeek)>;
+
+async fn my_schedule(telegram_id: i64) -> Result<(Option<String>, Weeks, WeekType)> {
+    let database = Database::global();
+    let current = database.get_distribution_week().await?;
+    let mut weeks = vec![];
+    if let Some(student) = database.get_student(telegram_id).await? {
+        let group = match student.group {
+            Some(group) => database.get_group(group).await?,
+            None => None,
+        };
+        for week in WeekType::all() {
+            weeks.push((week, database.get_week_schedule(telegram_id, &week).await?));
+        }
+        let role = match group {
+            Some(group) => format!("Студент группы {}", group.name),
+            None => "Студент".to_string(),
+        };
+        return Ok((Some(role), weeks, current));
+    }
+    if let Some(teacher) = database.get_linked_teacher(telegram_id).await? {
+        for week in WeekType::all() {
+            weeks.push((
+                week,
+                database
+                    .get_teacher_week_schedule(teacher.id, &week)
+                    .await?,
+            ));
+        }
+        return Ok((
+            Some(format!("Преподаватель {}", teacher.full_name())),
+            weeks,
+            current,
+        ));
+    }
+    Ok((None, weeks, current))
+}
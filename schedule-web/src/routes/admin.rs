@@ -0,0 +1,178 @@
+use askama::Template;
+use axum::extract::Request;
+use axum::http::header::{AUTHORIZATION, HOST, ORIGIN, REFERER, WWW_AUTHENTICATE};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use schedule_core::database::group::Group;
+use schedule_core::database::Database;
+
+use crate::config::Config;
+
+mod groups;
+mod import;
+mod lessons;
+mod subjects;
+mod teachers;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/groups", get(groups::list).post(groups::create))
+        .route("/groups/:id", post(groups::update))
+        .route("/groups/:id/delete", post(groups::delete))
+        .route("/groups/:id/schedule", get(lessons::grid))
+        .route("/subjects", get(subjects::list).post(subjects::create))
+        .route("/subjects/:id", post(subjects::update))
+        .route("/subjects/:id/delete", post(subjects::delete))
+        .route("/teachers", get(teachers::list).post(teachers::create))
+        .route("/teachers/:id", post(teachers::update))
+        .route("/teachers/:id/delete", post(teachers::delete))
+        .route("/import", get(import::form).post(import::upload))
+        .route("/lessons", post(lessons::create))
+        .route("/lessons/new", get(lessons::new))
+        .route("/lessons/:id", get(lessons::edit).post(lessons::update))
+        .route("/lessons/:id/delete", post(lessons::delete))
+        .layer(middleware::from_fn(same_origin))
+        .layer(middleware::from_fn(authorize))
+}
+
+type AdminResult = Result<Response, AdminError>;
+
+/// Error shown to the administrator on a separate page.
+pub struct AdminError {
+    status: StatusCode,
+    message: String,
+}
+
+impl AdminError {
+    fn not_found() -> Self {
+        AdminError {
+            status: StatusCode::NOT_FOUND,
+            message: "Запись не найдена".to_string(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        AdminError {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for AdminError {
+    fn from(error: anyhow::Error) -> Self {
+        log::error!("Admin request failed: {}", error);
+        AdminError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<askama::Error> for AdminError {
+    fn from(error: askama::Error) -> Self {
+        AdminError::from(anyhow::anyhow!(error))
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/error.html")]
+struct ErrorTemplate<'a> {
+    message: &'a str,
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        match (ErrorTemplate {
+            message: &self.message,
+        })
+        .render()
+        {
+            Ok(page) => (self.status, Html(page)).into_response(),
+            Err(_) => (self.status, self.message).into_response(),
+        }
+    }
+}
+
+fn render<T: Template>(template: T) -> AdminResult {
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Empty form fields are stored as `NULL`.
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+async fn authorize(request: Request, next: Next) -> Response {
+    let config = Config::global();
+    let authorized = !config.admin_password.is_empty()
+        && request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Basic "))
+            .and_then(|value| BASE64.decode(value).ok())
+            .and_then(|value| String::from_utf8(value).ok())
+            .is_some_and(|credentials| {
+                credentials == format!("{}:{}", config.admin_login, config.admin_password)
+            });
+    if authorized {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(WWW_AUTHENTICATE, "Basic realm=\"schedule admin\"")],
+        )
+            .into_response()
+    }
+}
+
+/// Rejects changes sent from other sites. Browsers attach the Basic credentials to forged
+/// cross-site forms too, but then the `Origin` (or `Referer`) is not this site.
+async fn same_origin(request: Request, next: Next) -> Response {
+    if request.method() == Method::GET || request.method() == Method::HEAD {
+        return next.run(request).await;
+    }
+    let headers = request.headers();
+    let host = header(headers, "x-forwarded-host").or_else(|| header(headers, HOST.as_str()));
+    let origin = header(headers, ORIGIN.as_str())
+        .or_else(|| header(headers, REFERER.as_str()))
+        .and_then(|origin| origin.split("://").nth(1))
+        .and_then(|origin| origin.split('/').next());
+    if host.is_some() && origin == host {
+        next.run(request).await
+    } else {
+        AdminError {
+            status: StatusCode::FORBIDDEN,
+            message: "Запрос отправлен с другого сайта".to_string(),
+        }
+        .into_response()
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+#[derive(Template)]
+#[template(path = "admin/index.html")]
+struct IndexTemplate {
+    groups: Vec<Group>,
+}
+
+async fn index() -> AdminResult {
+    render(IndexTemplate {
+        groups: Database::global().get_groups().await?,
+    })
+}
@@ -0,0 +1,60 @@
+use askama::Template;
+use axum::extract::Path;
+use axum::response::{IntoResponse, Redirect};
+use axum::Form;
+use schedule_core::database::group::Group;
+use schedule_core::database::Database;
+use serde::Deserialize;
+
+use crate::routes::admin::{render, AdminError, AdminResult};
+
+#[derive(Template)]
+#[template(path = "admin/groups.html")]
+struct GroupsTemplate {
+    groups: Vec<Group>,
+}
+
+#[derive(Deserialize)]
+pub struct GroupForm {
+    name: String,
+}
+
+impl GroupForm {
+    fn into_group(self, id: i32) -> Result<Group, AdminError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AdminError::bad_request(
+                "Название группы не может быть пустым",
+            ));
+        }
+        Ok(Group {
+            id,
+            name: name.to_string(),
+        })
+    }
+}
+
+pub async fn list() -> AdminResult {
+    render(GroupsTemplate {
+        groups: Database::global().get_groups().await?,
+    })
+}
+
+pub async fn create(Form(form): Form<GroupForm>) -> AdminResult {
+    Database::global()
+        .insert_group(&form.into_group(0)?)
+        .await?;
+    Ok(Redirect::to("/admin/groups").into_response())
+}
+
+pub async fn update(Path(id): Path<i32>, Form(form): Form<GroupForm>) -> AdminResult {
+    Database::global()
+        .update_group(&form.into_group(id)?)
+        .await?;
+    Ok(Redirect::to("/admin/groups").into_response())
+}
+
+pub async fn delete(Path(id): Path<i32>) -> AdminResult {
+    Database::global().delete_group(id).await?;
+    Ok(Redirect::to("/admin/groups").into_response())
+}
@@ -0,0 +1,67 @@
+use askama::Template;
+use axum::extract::Multipart;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse};
+use schedule_core::database::Database;
+use schedule_core::import::{ImportReport, Timetable};
+
+use crate::routes::admin::{render, AdminError, AdminResult};
+
+#[derive(Template)]
+#[template(path = "admin/import.html")]
+struct ImportTemplate {
+    error: Option<String>,
+    report: Option<ImportReport>,
+}
+
+pub async fn form() -> AdminResult {
+    render(ImportTemplate {
+        error: None,
+        report: None,
+    })
+}
+
+/// Imports an uploaded spreadsheet, or only shows the changes when `dry_run` is checked.
+pub async fn upload(mut multipart: Multipart) -> AdminResult {
+    let mut file = None;
+    let mut dry_run = false;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|error| AdminError::bad_request(error.body_text()))?
+    {
+        match field.name() {
+            Some("file") => {
+                let name = field.file_name().unwrap_or_default().to_string();
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|error| AdminError::bad_request(error.body_text()))?;
+                file = Some((name, data));
+            }
+            Some("dry_run") => dry_run = true,
+            _ => {}
+        }
+    }
+    let (name, data) = file.ok_or_else(|| AdminError::bad_request("Выберите файл"))?;
+
+    let timetable = match Timetable::from_named(&name, &data) {
+        Ok(timetable) => timetable,
+        Err(error) => {
+            let page = ImportTemplate {
+                error: Some(format!("{:#}", error)),
+                report: None,
+            }
+            .render()?;
+            return Ok((StatusCode::UNPROCESSABLE_ENTITY, Html(page)).into_response());
+        }
+    };
+    render(ImportTemplate {
+        error: None,
+        report: Some(
+            Database::global()
+                .import_timetable(&timetable, dry_run)
+                .await?,
+        ),
+    })
+}
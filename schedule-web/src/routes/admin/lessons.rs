@@ -0,0 +1,355 @@
+use askama::Template;
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect};
+use axum_extra::extract::Form;
+use chrono::NaiveTime;
+use schedule_core::bell;
+use schedule_core::database::day::Day;
+use schedule_core::database::group::Group;
+use schedule_core::database::lesson::{LessonEntry, LessonType};
+use schedule_core::database::week::Distribution;
+use schedule_core::database::Database;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::routes::admin::{render, AdminError, AdminResult};
+
+const TIME_FORMAT: &str = "%H:%M";
+
+struct GridLesson {
+    id: i32,
+    title: String,
+    teacher: String,
+    room: String,
+    distribution: Distribution,
+}
+
+struct GridCell {
+    day: Day,
+    lessons: Vec<GridLesson>,
+}
+
+struct GridRow {
+    time: String,
+    cells: Vec<GridCell>,
+}
+
+#[derive(Template)]
+#[template(path = "admin/grid.html")]
+struct GridTemplate {
+    group: Group,
+    groups: Vec<Group>,
+    days: Vec<Day>,
+    rows: Vec<GridRow>,
+}
+
+/// Weekly grid of a group: bells as rows and days as columns.
+pub async fn grid(Path(group_id): Path<i32>) -> AdminResult {
+    let database = Database::global();
+    let group = database
+        .get_group(group_id)
+        .await?
+        .ok_or_else(AdminError::not_found)?;
+    let entries = database.get_group_lesson_entries(group_id).await?;
+    let subjects: HashMap<i32, String> = database
+        .get_subjects()
+        .await?
+        .into_iter()
+        .map(|subject| (subject.id, subject.name))
+        .collect();
+    let teachers: HashMap<i32, String> = database
+        .get_teachers()
+        .await?
+        .into_iter()
+        .map(|teacher| (teacher.id, teacher.full_name()))
+        .collect();
+
+    let days: Vec<Day> = Day::all()
+        .iter()
+        .copied()
+        .filter(|day| *day != Day::Sunday || entries.iter().any(|entry| entry.day == Day::Sunday))
+        .collect();
+    let mut times = bell::bells();
+    times.extend(entries.iter().map(|entry| entry.time));
+    times.sort();
+    times.dedup();
+
+    let rows = times
+        .into_iter()
+        .map(|time| GridRow {
+            time: time.format(TIME_FORMAT).to_string(),
+            cells: days
+                .iter()
+                .map(|&day| GridCell {
+                    day,
+                    lessons: entries
+                        .iter()
+                        .filter(|entry| entry.day == day && entry.time == time)
+                        .map(|entry| GridLesson {
+                            id: entry.id,
+                            title: format!(
+                                "{} ({})",
+                                subjects
+                                    .get(&entry.subject)
+                                    .map(String::as_str)
+                                    .unwrap_or_default(),
+                                entry.lesson_type
+                            ),
+                            teacher: entry
+                                .teacher
+                                .and_then(|teacher| teachers.get(&teacher).cloned())
+                                .unwrap_or_default(),
+                            room: entry.room.clone(),
+                            distribution: entry.distribution,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    render(GridTemplate {
+        group,
+        groups: database.get_groups().await?,
+        days,
+        rows,
+    })
+}
+
+struct Choice {
+    value: String,
+    label: String,
+    selected: bool,
+}
+
+impl Choice {
+    fn new(value: impl ToString, label: impl ToString, selected: bool) -> Self {
+        Choice {
+            value: value.to_string(),
+            label: label.to_string(),
+            selected,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/lesson.html")]
+struct LessonTemplate {
+    id: Option<i32>,
+    error: Option<String>,
+    subjects: Vec<Choice>,
+    lesson_types: Vec<Choice>,
+    teachers: Vec<Choice>,
+    days: Vec<Choice>,
+    distributions: Vec<Choice>,
+    groups: Vec<Choice>,
+    time: String,
+    info: String,
+    room: String,
+}
+
+impl LessonTemplate {
+    async fn new(
+        id: Option<i32>,
+        entry: &LessonEntry,
+        error: Option<String>,
+    ) -> Result<Self, AdminError> {
+        let database = Database::global();
+        let subjects = database
+            .get_subjects()
+            .await?
+            .into_iter()
+            .map(|subject| Choice::new(subject.id, &subject.name, subject.id == entry.subject))
+            .collect();
+        let mut teachers = vec![Choice::new("", "—", entry.teacher.is_none())];
+        teachers.extend(database.get_teachers().await?.into_iter().map(|teacher| {
+            Choice::new(
+                teacher.id,
+                teacher.full_name(),
+                Some(teacher.id) == entry.teacher,
+            )
+        }));
+        let groups = database
+            .get_groups()
+            .await?
+            .into_iter()
+            .map(|group| Choice::new(group.id, &group.name, entry.groups.contains(&group.id)))
+            .collect();
+
+        Ok(LessonTemplate {
+            id,
+            error,
+            subjects,
+            lesson_types: LessonType::all()
+                .iter()
+                .map(|lesson_type| {
+                    Choice::new(
+                        lesson_type.as_str(),
+                        lesson_type,
+                        *lesson_type == entry.lesson_type,
+                    )
+                })
+                .collect(),
+            teachers,
+            days: Day::all()
+                .iter()
+                .map(|day| Choice::new(day.as_str(), day, *day == entry.day))
+                .collect(),
+            distributions: Distribution::all()
+                .iter()
+                .map(|distribution| {
+                    Choice::new(
+                        distribution.as_str(),
+                        distribution,
+                        *distribution == entry.distribution,
+                    )
+                })
+                .collect(),
+            groups,
+            time: entry.time.format(TIME_FORMAT).to_string(),
+            info: entry.info.clone(),
+            room: entry.room.clone(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewLessonQuery {
+    group: Option<i32>,
+    day: Option<Day>,
+    time: Option<String>,
+}
+
+pub async fn new(Query(query): Query<NewLessonQuery>) -> AdminResult {
+    let entry = LessonEntry {
+        id: 0,
+        subject: 0,
+        lesson_type: LessonType::Lecture,
+        teacher: None,
+        day: query.day.unwrap_or(Day::Monday),
+        time: query
+            .time
+            .as_deref()
+            .and_then(parse_time)
+            .unwrap_or_else(|| bell::bells()[0]),
+        distribution: Distribution::All,
+        info: String::new(),
+        room: String::new(),
+        groups: query.group.into_iter().collect(),
+    };
+    render(LessonTemplate::new(None, &entry, None).await?)
+}
+
+pub async fn edit(Path(id): Path<i32>) -> AdminResult {
+    let entry = Database::global()
+        .get_lesson_entry(id)
+        .await?
+        .ok_or_else(AdminError::not_found)?;
+    render(LessonTemplate::new(Some(id), &entry, None).await?)
+}
+
+#[derive(Deserialize)]
+pub struct LessonForm {
+    subject: i32,
+    lesson_type: LessonType,
+    teacher: String,
+    day: Day,
+    time: String,
+    distribution: Distribution,
+    info: String,
+    room: String,
+    #[serde(default)]
+    groups: Vec<i32>,
+}
+
+impl LessonForm {
+    fn into_entry(self, id: i32) -> Result<LessonEntry, AdminError> {
+        let time = parse_time(&self.time)
+            .ok_or_else(|| AdminError::bad_request("Неверный формат времени"))?;
+        let teacher = match self.teacher.as_str() {
+            "" => None,
+            teacher => Some(
+                teacher
+                    .parse()
+                    .map_err(|_| AdminError::bad_request("Неверный преподаватель"))?,
+            ),
+        };
+        Ok(LessonEntry {
+            id,
+            subject: self.subject,
+            lesson_type: self.lesson_type,
+            teacher,
+            day: self.day,
+            time,
+            distribution: self.distribution,
+            info: self.info.trim().to_string(),
+            room: self.room.trim().to_string(),
+            groups: self.groups,
+        })
+    }
+}
+
+pub async fn create(Form(form): Form<LessonForm>) -> AdminResult {
+    let entry = form.into_entry(0)?;
+    if let Some(error) = validate(&entry).await? {
+        return rejected(None, &entry, error).await;
+    }
+    Database::global().insert_lesson_entry(&entry).await?;
+    Ok(Redirect::to(&grid_path(&entry)).into_response())
+}
+
+pub async fn update(Path(id): Path<i32>, Form(form): Form<LessonForm>) -> AdminResult {
+    let entry = form.into_entry(id)?;
+    if let Some(error) = validate(&entry).await? {
+        return rejected(Some(id), &entry, error).await;
+    }
+    Database::global().update_lesson_entry(&entry).await?;
+    Ok(Redirect::to(&grid_path(&entry)).into_response())
+}
+
+pub async fn delete(Path(id): Path<i32>) -> AdminResult {
+    let database = Database::global();
+    let entry = database
+        .get_lesson_entry(id)
+        .await?
+        .ok_or_else(AdminError::not_found)?;
+    database.delete_lesson_entry(id).await?;
+    Ok(Redirect::to(&grid_path(&entry)).into_response())
+}
+
+/// Checks the lesson against the rest of the timetable, returning a message for the editor.
+async fn validate(entry: &LessonEntry) -> Result<Option<String>, AdminError> {
+    if entry.groups.is_empty() {
+        return Ok(Some("Выберите хотя бы одну группу".to_string()));
+    }
+    if let Some(conflict) = Database::global().find_teacher_conflict(entry).await? {
+        return Ok(Some(format!(
+            "Преподаватель уже занят в это время: {}, {} (неделя: {})",
+            conflict.day,
+            conflict.time.format(TIME_FORMAT),
+            conflict.distribution
+        )));
+    }
+    Ok(None)
+}
+
+async fn rejected(id: Option<i32>, entry: &LessonEntry, error: String) -> AdminResult {
+    let page = LessonTemplate::new(id, entry, Some(error))
+        .await?
+        .render()?;
+    Ok((StatusCode::UNPROCESSABLE_ENTITY, Html(page)).into_response())
+}
+
+fn grid_path(entry: &LessonEntry) -> String {
+    match entry.groups.first() {
+        Some(group) => format!("/admin/groups/{}/schedule", group),
+        None => "/admin".to_string(),
+    }
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, TIME_FORMAT)
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .ok()
+}
@@ -0,0 +1,65 @@
+use askama::Template;
+use axum::extract::Path;
+use axum::response::{IntoResponse, Redirect};
+use axum::Form;
+use schedule_core::database::subject::Subject;
+use schedule_core::database::Database;
+use serde::Deserialize;
+
+use crate::routes::admin::{non_empty, render, AdminError, AdminResult};
+
+#[derive(Template)]
+#[template(path = "admin/subjects.html")]
+struct SubjectsTemplate {
+    subjects: Vec<Subject>,
+}
+
+#[derive(Deserialize)]
+pub struct SubjectForm {
+    name: String,
+    /// Checkboxes are only sent when checked.
+    choice: Option<String>,
+    info: String,
+}
+
+impl SubjectForm {
+    fn into_subject(self, id: i32) -> Result<Subject, AdminError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AdminError::bad_request(
+                "Название предмета не может быть пустым",
+            ));
+        }
+        Ok(Subject {
+            id,
+            name: name.to_string(),
+            choice: self.choice.is_some(),
+            info: non_empty(self.info),
+        })
+    }
+}
+
+pub async fn list() -> AdminResult {
+    render(SubjectsTemplate {
+        subjects: Database::global().get_subjects().await?,
+    })
+}
+
+pub async fn create(Form(form): Form<SubjectForm>) -> AdminResult {
+    Database::global()
+        .insert_subject(&form.into_subject(0)?)
+        .await?;
+    Ok(Redirect::to("/admin/subjects").into_response())
+}
+
+pub async fn update(Path(id): Path<i32>, Form(form): Form<SubjectForm>) -> AdminResult {
+    Database::global()
+        .update_subject(&form.into_subject(id)?)
+        .await?;
+    Ok(Redirect::to("/admin/subjects").into_response())
+}
+
+pub async fn delete(Path(id): Path<i32>) -> AdminResult {
+    Database::global().delete_subject(id).await?;
+    Ok(Redirect::to("/admin/subjects").into_response())
+}
@@ -0,0 +1,77 @@
+use askama::Template;
+use axum::extract::Path;
+use axum::response::{IntoResponse, Redirect};
+use axum::Form;
+use schedule_core::database::teacher::Teacher;
+use schedule_core::database::Database;
+use serde::Deserialize;
+
+use crate::routes::admin::{non_empty, render, AdminError, AdminResult};
+
+#[derive(Template)]
+#[template(path = "admin/teachers.html")]
+struct TeachersTemplate {
+    teachers: Vec<Teacher>,
+}
+
+#[derive(Deserialize)]
+pub struct TeacherForm {
+    last_name: String,
+    first_name: String,
+    patronymic_name: String,
+    telegram: String,
+    email: String,
+    phone_number: String,
+}
+
+impl TeacherForm {
+    fn into_teacher(self, id: i32) -> Result<Teacher, AdminError> {
+        let last_name = self.last_name.trim();
+        let first_name = self.first_name.trim();
+        if last_name.is_empty() || first_name.is_empty() {
+            return Err(AdminError::bad_request(
+                "Фамилия и имя преподавателя не могут быть пустыми",
+            ));
+        }
+        let phone_number = non_empty(self.phone_number);
+        if phone_number.as_ref().is_some_and(|phone| phone.len() > 12) {
+            return Err(AdminError::bad_request(
+                "Номер телефона не может быть длиннее 12 символов",
+            ));
+        }
+        Ok(Teacher {
+            id,
+            last_name: last_name.to_string(),
+            first_name: first_name.to_string(),
+            patronymic_name: self.patronymic_name.trim().to_string(),
+            telegram: non_empty(self.telegram),
+            email: non_empty(self.email),
+            phone_number,
+        })
+    }
+}
+
+pub async fn list() -> AdminResult {
+    render(TeachersTemplate {
+        teachers: Database::global().get_teachers().await?,
+    })
+}
+
+pub async fn create(Form(form): Form<TeacherForm>) -> AdminResult {
+    Database::global()
+        .insert_teacher(&form.into_teacher(0)?)
+        .await?;
+    Ok(Redirect::to("/admin/teachers").into_response())
+}
+
+pub async fn update(Path(id): Path<i32>, Form(form): Form<TeacherForm>) -> AdminResult {
+    Database::global()
+        .update_teacher(&form.into_teacher(id)?)
+        .await?;
+    Ok(Redirect::to("/admin/teachers").into_response())
+}
+
+pub async fn delete(Path(id): Path<i32>) -> AdminResult {
+    Database::global().delete_teacher(id).await?;
+    Ok(Redirect::to("/admin/teachers").into_response())
+}
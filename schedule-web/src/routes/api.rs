@@ -0,0 +1,284 @@
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Path, Query};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use axum_extra::extract::CookieJar;
+use schedule_core::database::day::Day;
+use schedule_core::database::deadline::Deadline;
+use schedule_core::database::group::Group;
+use schedule_core::database::lesson::{Lesson, LessonType, Lessons, LessonsWeek};
+use schedule_core::database::session::Session;
+use schedule_core::database::subject::Subject;
+use schedule_core::database::teacher::Teacher;
+use schedule_core::database::week::WeekType;
+use schedule_core::database::Database;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::auth;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Schedule IPT API", version = "1"),
+    paths(
+        groups,
+        group_schedule,
+        subjects,
+        teachers,
+        student_schedule,
+        current_week,
+        deadlines
+    ),
+    components(schemas(
+        Day,
+        Deadline,
+        Group,
+        Lesson,
+        LessonType,
+        Lessons,
+        LessonsWeek,
+        Subject,
+        PublicTeacher,
+        WeekType,
+        CurrentWeek,
+        ErrorBody
+    ))
+)]
+struct ApiDoc;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/groups", get(groups))
+        .route("/groups/:id/schedule/:week", get(group_schedule))
+        .route("/subjects", get(subjects))
+        .route("/teachers", get(teachers))
+        .route(
+            "/students/:telegram_id/schedule/:day",
+            get(student_schedule),
+        )
+        .route("/week", get(current_week))
+        .route("/deadlines", get(deadlines))
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Error returned by the API handlers, rendered as JSON.
+pub enum ApiError {
+    Internal(anyhow::Error),
+    /// No session of the web login.
+    Unauthorized,
+    /// Data of another user.
+    Forbidden,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ErrorBody {
+    error: String,
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError::Internal(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            ApiError::Internal(error) => {
+                log::error!("API request failed: {}", error);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Log in at /login first"),
+            ApiError::Forbidden => (StatusCode::FORBIDDEN, "Only your own schedule is available"),
+        };
+        (
+            status,
+            Json(ErrorBody {
+                error: error.to_string(),
+            }),
+        )
+            .into_response()
+    }
+}
+
+/// Session of the caller. Unlike [`auth::CurrentUser`] it is rejected with an [`ApiError`]
+/// rather than a redirect to the login page.
+struct ApiUser(Session);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_request_parts(parts, state)
+            .await
+            .unwrap_or_else(|error| match error {});
+        auth::find_session(&jar)
+            .await?
+            .map(ApiUser)
+            .ok_or(ApiError::Unauthorized)
+    }
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// List all groups.
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups",
+    responses(
+        (status = 200, body = [Group]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn groups() -> ApiResult<Vec<Group>> {
+    Ok(Json(Database::global().get_groups().await?))
+}
+
+/// Week schedule of a group, including all of its elective lessons.
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{id}/schedule/{week}",
+    params(
+        ("id" = i32, Path, description = "Group id"),
+        ("week" = WeekType, Path, description = "Distribution week")
+    ),
+    responses(
+        (status = 200, body = LessonsWeek),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn group_schedule(Path((id, week)): Path<(i32, WeekType)>) -> ApiResult<LessonsWeek> {
+    Ok(Json(
+        Database::global()
+            .get_group_week_schedule(id, &week)
+            .await?,
+    ))
+}
+
+/// List all subjects.
+#[utoipa::path(
+    get,
+    path = "/api/v1/subjects",
+    responses(
+        (status = 200, body = [Subject]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn subjects() -> ApiResult<Vec<Subject>> {
+    Ok(Json(Database::global().get_subjects().await?))
+}
+
+/// Teacher as listed to anyone, without the contacts.
+#[derive(Serialize, ToSchema)]
+#[schema(as = Teacher)]
+struct PublicTeacher {
+    id: i32,
+    last_name: String,
+    first_name: String,
+    patronymic_name: String,
+}
+
+impl From<Teacher> for PublicTeacher {
+    fn from(teacher: Teacher) -> Self {
+        PublicTeacher {
+            id: teacher.id,
+            last_name: teacher.last_name,
+            first_name: teacher.first_name,
+            patronymic_name: teacher.patronymic_name,
+        }
+    }
+}
+
+/// List all teachers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/teachers",
+    responses(
+        (status = 200, body = [Teacher]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn teachers() -> ApiResult<Vec<PublicTeacher>> {
+    let teachers = Database::global().get_teachers().await?;
+    Ok(Json(
+        teachers.into_iter().map(PublicTeacher::from).collect(),
+    ))
+}
+
+/// Schedule of a student for the given day of the current distribution week. Students see only
+/// their own, logged in with the `session` cookie of `/login`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/students/{telegram_id}/schedule/{day}",
+    params(
+        ("telegram_id" = i64, Path, description = "Telegram id of the student"),
+        ("day" = Day, Path, description = "Day of week")
+    ),
+    responses(
+        (status = 200, body = Lessons),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn student_schedule(
+    ApiUser(session): ApiUser,
+    Path((telegram_id, day)): Path<(i64, Day)>,
+) -> ApiResult<Lessons> {
+    if session.telegram_id != telegram_id {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(Json(
+        Database::global()
+            .get_schedule_by_day(day, telegram_id)
+            .await?,
+    ))
+}
+
+#[derive(Serialize, ToSchema)]
+struct CurrentWeek {
+    week: WeekType,
+}
+
+/// Current distribution week.
+#[utoipa::path(
+    get,
+    path = "/api/v1/week",
+    responses(
+        (status = 200, body = CurrentWeek),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn current_week() -> ApiResult<CurrentWeek> {
+    Ok(Json(CurrentWeek {
+        week: Database::global().get_distribution_week().await?,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+struct DeadlinesQuery {
+    /// Only deadlines of this group.
+    group: Option<i32>,
+}
+
+/// Upcoming deadlines.
+#[utoipa::path(
+    get,
+    path = "/api/v1/deadlines",
+    params(DeadlinesQuery),
+    responses(
+        (status = 200, body = [Deadline]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn deadlines(Query(query): Query<DeadlinesQuery>) -> ApiResult<Vec<Deadline>> {
+    Ok(Json(Database::global().get_deadlines(query.group).await?))
+}
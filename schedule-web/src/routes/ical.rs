@@ -0,0 +1,52 @@
+use anyhow::Result;
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use schedule_core::database::week::WeekType;
+use schedule_core::database::Database;
+use schedule_core::locale;
+
+use crate::calendar::{Calendar, Event};
+
+pub async fn calendar(Path(file): Path<String>) -> Response {
+    let token = match file.strip_suffix(".ics") {
+        Some(token) => token,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    match build_calendar(token).await {
+        Ok(Some(calendar)) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            calendar.to_string(),
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(error) => {
+            log::error!("Failed to build calendar: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn build_calendar(token: &str) -> Result<Option<Calendar>> {
+    let database = Database::global();
+    let user_id = match database.get_user_by_calendar_token(token).await? {
+        Some(user_id) => user_id,
+        None => return Ok(None),
+    };
+    let language = database.get_language(user_id).await?.unwrap_or_default();
+    let distribution_start = database.get_distribution_start().await?;
+    let mut events = vec![];
+    for week in WeekType::all() {
+        let lessons_week = database.get_week_schedule(user_id, &week).await?;
+        for (day, lessons) in &lessons_week.0 {
+            events.extend(lessons.0.iter().enumerate().map(|(index, lesson)| {
+                Event::new(distribution_start, week, day, index, lesson, language)
+            }));
+        }
+    }
+    Ok(Some(Calendar {
+        name: locale::global().get(language, "calendar-name"),
+        events,
+    }))
+}
@@ -0,0 +1,212 @@
+use anyhow::Result;
+use askama::Template;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use schedule_core::bell;
+use schedule_core::database::day::Day;
+use schedule_core::database::group::Group;
+use schedule_core::database::lesson::{Lesson, LessonsWeek};
+use schedule_core::database::teacher::Teacher;
+use schedule_core::database::week::WeekType;
+use schedule_core::database::Database;
+use std::future::Future;
+
+use crate::routes::{page, pdf};
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate {
+    groups: Vec<Group>,
+    teachers: Vec<Teacher>,
+    rooms: Vec<String>,
+}
+
+pub async fn index() -> Response {
+    let database = Database::global();
+    let lists = async {
+        Ok::<_, anyhow::Error>(IndexTemplate {
+            groups: database.get_groups().await?,
+            teachers: database.get_teachers().await?,
+            rooms: database.get_rooms().await?,
+        })
+    };
+    match lists.await {
+        Ok(template) => page(template),
+        Err(error) => internal_error(error),
+    }
+}
+
+struct GridRow {
+    time: String,
+    cells: Vec<Vec<Lesson>>,
+}
+
+/// Week laid out as a table with bells as rows and days as columns.
+struct WeekGrid {
+    week: WeekType,
+    days: Vec<Day>,
+    rows: Vec<GridRow>,
+}
+
+impl WeekGrid {
+    fn new(week: WeekType, lessons_week: LessonsWeek) -> Self {
+        let days: Vec<Day> = Day::all()
+            .iter()
+            .copied()
+            .filter(|day| *day != Day::Sunday || lessons_week.0.contains_key(day))
+            .collect();
+        let mut times = bell::bells();
+        times.extend(
+            lessons_week
+                .0
+                .values()
+                .flat_map(|lessons| lessons.0.iter().map(|lesson| lesson.time)),
+        );
+        times.sort();
+        times.dedup();
+
+        let mut rows: Vec<GridRow> = times
+            .iter()
+            .map(|time| GridRow {
+                time: time.format("%H:%M").to_string(),
+                cells: days.iter().map(|_| vec![]).collect(),
+            })
+            .collect();
+        for (day, lessons) in lessons_week.0 {
+            let column = match days.iter().position(|other| *other == day) {
+                Some(column) => column,
+                None => continue,
+            };
+            for lesson in lessons.0 {
+                if let Ok(row) = times.binary_search(&lesson.time) {
+                    rows[row].cells[column].push(lesson);
+                }
+            }
+        }
+        WeekGrid { week, days, rows }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "timetable.html")]
+struct TimetableTemplate {
+    title: String,
+    current: WeekType,
+    weeks: Vec<WeekGrid>,
+    show_teacher: bool,
+    show_room: bool,
+    /// Link to the timetable as a PDF, if it has one.
+    pdf: Option<String>,
+}
+
+pub async fn group(Path(id): Path<i32>) -> Response {
+    let database = Database::global();
+    let group = match database.get_group(id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => return not_found(),
+        Err(error) => return internal_error(error),
+    };
+    timetable(
+        format!("Группа {}", group.name),
+        true,
+        true,
+        Some(format!("/groups/{}/pdf", id)),
+        |week| async move { database.get_group_week_schedule(id, &week).await },
+    )
+    .await
+}
+
+pub async fn group_pdf(Path(id): Path<i32>) -> Response {
+    let database = Database::global();
+    let group = match database.get_group(id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => return not_found(),
+        Err(error) => return internal_error(error),
+    };
+    let weeks = async {
+        let mut weeks = vec![];
+        for week in WeekType::all() {
+            weeks.push((week, database.get_group_week_schedule(id, &week).await?));
+        }
+        Ok::<_, anyhow::Error>(weeks)
+    };
+    match weeks.await {
+        Ok(weeks) => pdf(
+            &format!("Группа {}", group.name),
+            &weeks,
+            &format!("group-{}.pdf", id),
+        ),
+        Err(error) => internal_error(error),
+    }
+}
+
+pub async fn teacher(Path(id): Path<i32>) -> Response {
+    let database = Database::global();
+    let teacher = match database.get_teacher(id).await {
+        Ok(Some(teacher)) => teacher,
+        Ok(None) => return not_found(),
+        Err(error) => return internal_error(error),
+    };
+    timetable(teacher.full_name(), false, true, None, |week| async move {
+        database.get_teacher_week_schedule(id, &week).await
+    })
+    .await
+}
+
+pub async fn room(Path(room): Path<String>) -> Response {
+    let database = Database::global();
+    match database.get_rooms().await {
+        Ok(rooms) if rooms.contains(&room) => {}
+        Ok(_) => return not_found(),
+        Err(error) => return internal_error(error),
+    }
+    let title = format!("Аудитория {}", room);
+    let room = &room;
+    timetable(title, true, false, None, |week| async move {
+        database.get_room_week_schedule(room, &week).await
+    })
+    .await
+}
+
+/// Renders both weeks of a timetable, loading each with `load`.
+async fn timetable<F, Fut>(
+    title: String,
+    show_teacher: bool,
+    show_room: bool,
+    pdf: Option<String>,
+    load: F,
+) -> Response
+where
+    F: Fn(WeekType) -> Fut,
+    Fut: Future<Output = Result<LessonsWeek>>,
+{
+    let weeks = async {
+        let current = Database::global().get_distribution_week().await?;
+        let mut weeks = vec![];
+        for week in WeekType::all() {
+            weeks.push(WeekGrid::new(week, load(week).await?));
+        }
+        Ok::<_, anyhow::Error>((current, weeks))
+    };
+    match weeks.await {
+        Ok((current, weeks)) => page(TimetableTemplate {
+            title,
+            current,
+            weeks,
+            show_teacher,
+            show_room,
+            pdf,
+        }),
+        Err(error) => internal_error(error),
+    }
+}
+
+fn not_found() -> Response {
+    (StatusCode::NOT_FOUND, "Не найдено").into_response()
+}
+
+fn internal_error(error: anyhow::Error) -> Response {
+    log::error!("Failed to load timetable: {}", error);
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}